@@ -1,6 +1,10 @@
 use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
     env::var,
-    fmt,
+    fmt, fs,
+    future::Future,
+    ops::RangeInclusive,
+    path::PathBuf,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -10,48 +14,52 @@ use std::{
 
 use base64::{engine::general_purpose, Engine};
 use bitcoin::{
+    address::NetworkUnchecked,
     bip32::Xpriv,
     block::Header,
     consensus::{self, encode::serialize_hex},
-    Address, Block, BlockHash, Network, Transaction, Txid,
+    Address, Block, BlockHash, MerkleBlock, Network, Psbt, Script, Transaction, Txid,
 };
 use reqwest::{
-    header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE},
-    Client as ReqwestClient,
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Client as ReqwestClient, Proxy,
 };
 use serde::{de, Deserialize, Serialize};
-use serde_json::{
-    json,
-    value::{RawValue, Value},
-};
+use serde_json::{json, value::Value};
 use tokio::time::sleep;
 use tracing::*;
 
 use super::types::GetBlockHeaderVerbosityZero;
 use crate::{
-    error::{BitcoinRpcError, ClientError},
-    traits::{Broadcaster, Reader, Signer, Wallet},
+    error::{BitcoinRpcError, ClientError, RpcErrorCode},
+    retry::{ConstantBackoff, RetryPolicy},
+    traits::{
+        Broadcaster, Mining, NetworkControl, NodeControl, PsbtRpc, Reader, RegtestControl, Signer,
+        Wallet,
+    },
+    transport::{HttpTransport, Transport},
     types::{
+        AddNodeCommand, AnalyzePsbt, BannedSubnet, BlockStatsField, BlockStatsTarget, ChainTip,
         CreateRawTransaction, CreateRawTransactionInput, CreateRawTransactionOutput, CreateWallet,
-        GetAddressInfo, GetBlockVerbosityOne, GetBlockVerbosityZero, GetBlockchainInfo,
-        GetMempoolInfo, GetNewAddress, GetRawTransactionVerbosityOne,
-        GetRawTransactionVerbosityZero, GetTransaction, GetTxOut, ImportDescriptor,
-        ImportDescriptorResult, ListDescriptors, ListTransactions, ListUnspent,
-        ListUnspentQueryOptions, PreviousTransactionOutput, PsbtBumpFee, PsbtBumpFeeOptions,
-        SighashType, SignRawTransactionWithWallet, SubmitPackage, TestMempoolAccept,
-        WalletCreateFundedPsbt, WalletCreateFundedPsbtOptions, WalletProcessPsbtResult,
+        DecodePsbt, DecodeRawTransaction, DecodeScript, DisconnectTarget, EstimateMode,
+        EstimateRawFee, GenerateBlock, GetAddressInfo, GetBlockFilter, GetBlockHeaderVerbose,
+        GetBlockStats, GetBlockTemplate, GetBlockVerbose, GetBlockVerbosityOne,
+        GetBlockVerbosityZero, GetBlockchainInfo, GetDeploymentInfo, GetDescriptorInfo,
+        GetMempoolEntry, GetMempoolInfo, GetMiningInfo, GetNetTotals, GetNetworkInfo,
+        GetNewAddress, GetRawTransactionVerbosityOne, GetRawTransactionVerbosityZero, GetRpcInfo,
+        GetTransaction, GetTxOut, GetTxOutProof, GetTxOutSetInfo, ImportDescriptor,
+        ImportDescriptorResult, IndexInfo, ListDescriptors, ListTransactions, ListUnspent,
+        ListUnspentQueryOptions, PeerInfo, PreviousTransactionOutput, PsbtBumpFee,
+        PsbtBumpFeeOptions, ScanObject, ScanTxOutSet, ScanTxOutSetStatus, SetBanCommand,
+        SighashType, SignRawTransactionWithWallet, SmartFeeEstimate, SubmitBlockReason,
+        SubmitPackage, TemplateRule, TestMempoolAccept, TxOutSetHashType, WalletCreateFundedPsbt,
+        WalletCreateFundedPsbtOptions, WalletProcessPsbtResult,
     },
 };
 
 /// This is an alias for the result type returned by the [`Client`].
 pub type ClientResult<T> = Result<T, ClientError>;
 
-/// The maximum number of retries for a request.
-const DEFAULT_MAX_RETRIES: u8 = 3;
-
-/// The maximum number of retries for a request.
-const DEFAULT_RETRY_INTERVAL_MS: u64 = 1_000;
-
 /// Custom implementation to convert a value to a `Value` type.
 pub fn to_value<T>(value: T) -> ClientResult<Value>
 where
@@ -61,14 +69,73 @@ where
         .map_err(|e| ClientError::Param(format!("Error creating value: {e}")))
 }
 
+/// Authentication method used to connect to a `bitcoind` instance.
+#[derive(Clone, PartialEq, Eq)]
+pub enum Auth {
+    /// Static username and password, as set by `rpcuser`/`rpcpassword` or `rpcauth`.
+    UserPass(String, String),
+
+    /// Path to the `.cookie` file that `bitcoind` writes on startup.
+    ///
+    /// The cookie changes every time `bitcoind` restarts, so the file is re-read whenever
+    /// the server answers with `401 Unauthorized`.
+    CookieFile(PathBuf),
+}
+
+impl Auth {
+    /// Builds the `Authorization` header value for this authentication method.
+    ///
+    /// For [`Auth::CookieFile`] this reads the cookie file from disk.
+    pub(crate) fn authorization(&self) -> ClientResult<HeaderValue> {
+        let (username, password) = match self {
+            Auth::UserPass(username, password) => (username.clone(), password.clone()),
+            Auth::CookieFile(path) => {
+                let cookie = fs::read_to_string(path)
+                    .map_err(|e| ClientError::CookieFile(format!("{}: {e}", path.display())))?;
+                let (username, password) = cookie.trim().split_once(':').ok_or_else(|| {
+                    ClientError::CookieFile(format!("{}: malformed cookie", path.display()))
+                })?;
+                (username.to_string(), password.to_string())
+            }
+        };
+        if username.is_empty() || password.is_empty() {
+            return Err(ClientError::MissingUserPassword);
+        }
+
+        let user_pw = general_purpose::STANDARD.encode(format!("{username}:{password}"));
+        let mut authorization: HeaderValue = format!("Basic {user_pw}")
+            .parse()
+            .map_err(|_| ClientError::Other("Error parsing header".to_string()))?;
+        authorization.set_sensitive(true);
+        Ok(authorization)
+    }
+}
+
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Never leak the password into logs.
+            Auth::UserPass(username, _) => f
+                .debug_tuple("UserPass")
+                .field(username)
+                .field(&"***")
+                .finish(),
+            Auth::CookieFile(path) => f.debug_tuple("CookieFile").field(path).finish(),
+        }
+    }
+}
+
 /// An `async` client for interacting with a `bitcoind` instance.
-#[derive(Debug, Clone)]
-pub struct Client {
-    /// The URL of the `bitcoind` instance.
-    url: String,
+///
+/// Generic over the [`Transport`] that carries requests to `bitcoind`, which is
+/// [`HttpTransport`] unless built with [`Client::from_transport`].
+#[derive(Debug)]
+pub struct Client<T = HttpTransport> {
+    /// The transport that carries requests to `bitcoind`.
+    transport: Arc<T>,
 
-    /// The underlying `async` HTTP client.
-    client: ReqwestClient,
+    /// The wallet that requests are sent to, if any.
+    wallet: Option<String>,
 
     /// The ID of the current request.
     ///
@@ -77,23 +144,125 @@ pub struct Client {
     /// Using an [`Arc`] so that [`Client`] is [`Clone`].
     id: Arc<AtomicUsize>,
 
-    /// The maximum number of retries for a request.
-    max_retries: u8,
+    /// Decides whether and when failed requests are retried.
+    retry_policy: Arc<dyn RetryPolicy>,
+}
+
+impl<T> Clone for Client<T> {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            wallet: self.wallet.clone(),
+            id: self.id.clone(),
+            retry_policy: self.retry_policy.clone(),
+        }
+    }
+}
+
+/// Number of blocks [`BlockStatsRange`] fetches statistics for in a single batched request.
+pub const BLOCK_STATS_BATCH_SIZE: usize = 100;
+
+/// Statistics of a range of blocks, see [`Client::get_block_stats_range`].
+#[derive(Debug)]
+pub struct BlockStatsRange<'a, T> {
+    /// The client fetching the statistics.
+    client: &'a Client<T>,
+
+    /// Heights of the blocks not fetched yet.
+    heights: RangeInclusive<u64>,
+
+    /// The statistics to compute.
+    fields: Option<Vec<BlockStatsField>>,
+
+    /// Statistics fetched but not returned yet.
+    fetched: VecDeque<ClientResult<GetBlockStats>>,
+}
+
+impl<T: Transport> BlockStatsRange<'_, T> {
+    /// Returns the statistics of the next block, or `None` once the range is exhausted.
+    ///
+    /// # Note
+    ///
+    /// The range ends early after an error that fails a whole batch, such as a connection
+    /// error. Errors of single blocks, such as a height past the tip, are returned in place of
+    /// their statistics.
+    pub async fn next(&mut self) -> Option<ClientResult<GetBlockStats>> {
+        if self.fetched.is_empty() {
+            let heights = self
+                .heights
+                .by_ref()
+                .take(BLOCK_STATS_BATCH_SIZE)
+                .collect::<Vec<_>>();
+            if heights.is_empty() {
+                return None;
+            }
+            match self.fetch(&heights).await {
+                Ok(stats) => self.fetched.extend(stats),
+                Err(err) => {
+                    // Drain the range, the remaining batches would fail as well.
+                    self.heights.by_ref().for_each(drop);
+                    return Some(Err(err));
+                }
+            }
+        }
+        self.fetched.pop_front()
+    }
+
+    /// Fetches the statistics of the blocks at the given heights in a single batched request.
+    async fn fetch(&self, heights: &[u64]) -> ClientResult<Vec<ClientResult<GetBlockStats>>> {
+        let calls = heights
+            .iter()
+            .map(|height| {
+                Ok((
+                    "getblockstats",
+                    block_stats_params((*height).into(), self.fields.as_deref())?,
+                ))
+            })
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.client.call_batch::<GetBlockStats>(&calls).await
+    }
+}
 
-    /// Interval between retries for a request in ms.
-    retry_interval: u64,
+/// Builds the parameters of JSON-RPC method `getblockstats`.
+fn block_stats_params(
+    target: BlockStatsTarget,
+    fields: Option<&[BlockStatsField]>,
+) -> ClientResult<Vec<Value>> {
+    let mut params = vec![to_value(target)?];
+    if let Some(fields) = fields {
+        params.push(to_value(fields)?);
+    }
+    Ok(params)
 }
 
 /// Response returned by the `bitcoind` RPC server.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-struct Response<R> {
+pub(crate) struct Response<R> {
     pub result: Option<R>,
     pub error: Option<BitcoinRpcError>,
     pub id: u64,
 }
 
+impl<R: de::DeserializeOwned> Response<R> {
+    /// Converts the response into its result, mapping RPC errors into [`ClientError::Server`].
+    fn into_result(self) -> ClientResult<R> {
+        if let Some(err) = self.error {
+            return Err(ClientError::Server(err.code, err.message));
+        }
+        self.result.map_or_else(null_result, Ok)
+    }
+}
+
+/// Deserializes a `null` result, which only types such as `()` and [`Option`] accept.
+fn null_result<R: de::DeserializeOwned>() -> ClientResult<R> {
+    R::deserialize(Value::Null).map_err(|_| ClientError::Other("Empty data received".to_string()))
+}
+
 impl Client {
     /// Creates a new [`Client`] with the given URL, username, and password.
+    ///
+    /// `max_retries` is the maximum number of attempts per request, including the first one,
+    /// and defaults to 3. See [`ClientBuilder`] for more configuration options.
     pub fn new(
         url: String,
         username: String,
@@ -101,171 +270,509 @@ impl Client {
         max_retries: Option<u8>,
         retry_interval: Option<u64>,
     ) -> ClientResult<Self> {
-        if username.is_empty() || password.is_empty() {
-            return Err(ClientError::MissingUserPassword);
-        }
-
-        let user_pw = general_purpose::STANDARD.encode(format!("{username}:{password}"));
-        let authorization = format!("Basic {user_pw}")
-            .parse()
-            .map_err(|_| ClientError::Other("Error parsing header".to_string()))?;
-
-        let content_type = "application/json"
-            .parse()
-            .map_err(|_| ClientError::Other("Error parsing header".to_string()))?;
-        let headers =
-            HeaderMap::from_iter([(AUTHORIZATION, authorization), (CONTENT_TYPE, content_type)]);
-
-        trace!(headers = ?headers);
+        ClientBuilder::new(url)
+            .auth(Auth::UserPass(username, password))
+            .retry_policy(constant_backoff(max_retries, retry_interval))
+            .build()
+    }
 
-        let client = ReqwestClient::builder()
-            .default_headers(headers)
+    /// Creates a new [`Client`] with the given URL that authenticates using `bitcoind`'s
+    /// `.cookie` file.
+    ///
+    /// The cookie file is read immediately and again whenever `bitcoind` rejects the
+    /// credentials, so the [`Client`] survives `bitcoind` restarts.
+    ///
+    /// `max_retries` is the maximum number of attempts per request, as in [`Client::new`].
+    pub fn new_with_cookie_file(
+        url: String,
+        cookie_file: impl Into<PathBuf>,
+        max_retries: Option<u8>,
+        retry_interval: Option<u64>,
+    ) -> ClientResult<Self> {
+        ClientBuilder::new(url)
+            .auth(Auth::CookieFile(cookie_file.into()))
+            .retry_policy(constant_backoff(max_retries, retry_interval))
             .build()
-            .map_err(|e| ClientError::Other(format!("Could not create client: {e}")))?;
+    }
 
-        let id = Arc::new(AtomicUsize::new(0));
+    /// Creates a new [`ClientBuilder`] for the `bitcoind` instance at the given URL.
+    pub fn builder(url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder::new(url)
+    }
+}
 
-        let max_retries = max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
-        let retry_interval = retry_interval.unwrap_or(DEFAULT_RETRY_INTERVAL_MS);
+impl<T: Transport> Client<T> {
+    /// Creates a new [`Client`] that sends requests through the given [`Transport`].
+    pub fn from_transport(transport: T, retry_policy: impl RetryPolicy + 'static) -> Self {
+        Self {
+            transport: Arc::new(transport),
+            wallet: None,
+            id: Arc::new(AtomicUsize::new(0)),
+            retry_policy: Arc::new(retry_policy),
+        }
+    }
 
-        trace!(url = %url, "Created bitcoin client");
+    /// Returns the underlying [`Transport`].
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
 
-        Ok(Self {
-            url,
-            client,
-            id,
-            max_retries,
-            retry_interval,
-        })
+    /// Returns a handle to the given wallet that sends RPCs to the `/wallet/<name>` endpoint.
+    ///
+    /// `bitcoind` rejects [`Wallet`] and [`Signer`] RPCs sent to the bare node URL with error
+    /// code -19 when more than one wallet is loaded, so multi-wallet nodes need one handle per
+    /// wallet. Non-wallet RPCs keep working through the handle.
+    ///
+    /// The handle shares the transport, and with it the HTTP connection pool and credentials,
+    /// and the request ID counter with this [`Client`].
+    pub fn wallet(&self, wallet: impl Into<String>) -> Self {
+        Self {
+            wallet: Some(wallet.into()),
+            ..self.clone()
+        }
     }
 
     fn next_id(&self) -> usize {
         self.id.fetch_add(1, Ordering::AcqRel)
     }
 
-    async fn call<T: de::DeserializeOwned + fmt::Debug>(
+    async fn call<R: de::DeserializeOwned + fmt::Debug>(
         &self,
         method: &str,
         params: &[Value],
-    ) -> ClientResult<T> {
-        let mut retries = 0;
-        loop {
-            trace!(%method, ?params, %retries, "Calling bitcoin client");
+    ) -> ClientResult<R> {
+        let id = self.next_id();
+        trace!(%method, ?params, %id, "Calling bitcoin client");
+
+        let request = json!({
+            "jsonrpc": "1.0",
+            "id": id,
+            "method": method,
+            "params": params
+        });
+        self.with_retries(|| async {
+            let raw_response = self.send(&request).await?;
+            let data: Response<R> = serde_json::from_str(&raw_response)
+                .map_err(|e| ClientError::Parse(e.to_string()))?;
+            data.into_result()
+        })
+        .await
+    }
 
-            let id = self.next_id();
+    /// Sends many JSON-RPC calls to `bitcoind` in a single HTTP request.
+    ///
+    /// Each call is a `(method, params)` pair. All calls in a batch must deserialize into the
+    /// same type `R`; use [`Value`] to mix heterogeneous calls and decode them afterwards.
+    ///
+    /// # Returns
+    ///
+    /// One [`ClientResult`] per call, in the same order as `calls`. Responses are matched back
+    /// to their calls by JSON-RPC `id`, so an error in one call does not affect the others.
+    /// The outer [`ClientResult`] only fails if the batch as a whole could not be sent or parsed.
+    ///
+    /// The batch is retried according to the [`RetryPolicy`] only if it could not be sent or
+    /// `bitcoind` answered every call with a retryable error, such as while it is warming up,
+    /// so no call is executed twice. A call without a response fails with
+    /// [`ClientError::MalformedResponse`] and is never retried, since it may have run.
+    pub async fn call_batch<R: de::DeserializeOwned + fmt::Debug>(
+        &self,
+        calls: &[(&str, Vec<Value>)],
+    ) -> ClientResult<Vec<ClientResult<R>>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
 
-            let response = self
-                .client
-                .post(&self.url)
-                .json(&json!({
+        let ids: Vec<u64> = calls.iter().map(|_| self.next_id() as u64).collect();
+        let requests: Vec<Value> = calls
+            .iter()
+            .zip(&ids)
+            .map(|((method, params), id)| {
+                json!({
                     "jsonrpc": "1.0",
                     "id": id,
                     "method": method,
                     "params": params
-                }))
-                .send()
-                .await;
-            trace!(?response, "Response received");
-            match response {
-                Ok(resp) => {
-                    // Check HTTP status code first before parsing body
-                    let resp = match resp.error_for_status() {
-                        Err(e) if e.is_status() => {
-                            if let Some(status) = e.status() {
-                                let reason =
-                                    status.canonical_reason().unwrap_or("Unknown").to_string();
-                                return Err(ClientError::Status(status.as_u16(), reason));
-                            } else {
-                                return Err(ClientError::Other(e.to_string()));
-                            }
-                        }
-                        Err(e) => {
-                            return Err(ClientError::Other(e.to_string()));
-                        }
-                        Ok(resp) => resp,
+                })
+            })
+            .collect();
+        trace!(batch_size = %requests.len(), "Calling bitcoin client with batch");
+
+        let request = Value::Array(requests);
+        self.with_retries(|| async {
+            let raw_response = self.send(&request).await?;
+            let responses: Vec<Response<Value>> = match serde_json::from_str(&raw_response) {
+                Ok(responses) => responses,
+                Err(e) => {
+                    // `bitcoind` answers with a single error object if it rejects the whole
+                    // batch.
+                    return match serde_json::from_str::<Response<Value>>(&raw_response) {
+                        Ok(Response {
+                            error: Some(err), ..
+                        }) => Err(err.into()),
+                        _ => Err(ClientError::Parse(e.to_string())),
                     };
-
-                    let raw_response = resp
-                        .text()
-                        .await
-                        .map_err(|e| ClientError::Parse(e.to_string()))?;
-                    trace!(%raw_response, "Raw response received");
-                    let data: Response<T> = serde_json::from_str(&raw_response)
-                        .map_err(|e| ClientError::Parse(e.to_string()))?;
-                    if let Some(err) = data.error {
-                        return Err(ClientError::Server(err.code, err.message));
-                    }
-                    return data
-                        .result
-                        .ok_or_else(|| ClientError::Other("Empty data received".to_string()));
-                }
-                Err(err) => {
-                    warn!(err = %err, "Error calling bitcoin client");
-
-                    if err.is_body() {
-                        // Body error is unrecoverable
-                        return Err(ClientError::Body(err.to_string()));
-                    } else if err.is_status() {
-                        // Status error is unrecoverable
-                        let e = match err.status() {
-                            Some(code) => ClientError::Status(code.as_u16(), err.to_string()),
-                            _ => ClientError::Other(err.to_string()),
-                        };
-                        return Err(e);
-                    } else if err.is_decode() {
-                        // Error decoding response, might be recoverable
-                        let e = ClientError::MalformedResponse(err.to_string());
-                        warn!(%e, "decoding error, retrying...");
-                    } else if err.is_connect() {
-                        // Connection error, might be recoverable
-                        let e = ClientError::Connection(err.to_string());
-                        warn!(%e, "connection error, retrying...");
-                    } else if err.is_timeout() {
-                        // Timeout error, might be recoverable
-                        let e = ClientError::Timeout;
-                        warn!(%e, "timeout error, retrying...");
-                    } else if err.is_request() {
-                        // General request error, might be recoverable
-                        let e = ClientError::Request(err.to_string());
-                        warn!(%e, "request error, retrying...");
-                    } else if err.is_builder() {
-                        // Request builder error is unrecoverable
-                        return Err(ClientError::ReqBuilder(err.to_string()));
-                    } else if err.is_redirect() {
-                        // Redirect error is unrecoverable
-                        return Err(ClientError::HttpRedirect(err.to_string()));
-                    } else {
-                        // Unknown error is unrecoverable
-                        return Err(ClientError::Other("Unknown error".to_string()));
-                    }
                 }
+            };
+
+            let mut responses: HashMap<u64, Response<Value>> =
+                responses.into_iter().map(|resp| (resp.id, resp)).collect();
+            let results = ids
+                .iter()
+                .map(|id| match responses.remove(id) {
+                    Some(resp) => resp.into_result().and_then(|value| match value {
+                        Value::Null => null_result(),
+                        value => serde_json::from_value(value)
+                            .map_err(|e| ClientError::Parse(e.to_string())),
+                    }),
+                    None => Err(ClientError::MalformedResponse(format!(
+                        "Missing response for request id {id}"
+                    ))),
+                })
+                .collect::<Vec<ClientResult<R>>>();
+
+            let all_retryable = results.iter().all(|result| match result {
+                Err(err @ ClientError::Server(..)) => self.retry_policy.is_retryable(err),
+                _ => false,
+            });
+            match &results[..] {
+                [Err(err), ..] if all_retryable => Err(err.clone()),
+                _ => Ok(results),
+            }
+        })
+        .await
+    }
+
+    /// Streams the statistics of the blocks in the given height range of the active chain.
+    ///
+    /// The statistics are fetched in batched requests of [`BLOCK_STATS_BATCH_SIZE`] blocks as
+    /// [`BlockStatsRange::next`] is called. See [`Reader::get_block_stats`] for the `fields`.
+    pub fn get_block_stats_range(
+        &self,
+        heights: RangeInclusive<u64>,
+        fields: Option<&[BlockStatsField]>,
+    ) -> BlockStatsRange<'_, T> {
+        BlockStatsRange {
+            client: self,
+            heights,
+            fields: fields.map(<[BlockStatsField]>::to_vec),
+            fetched: VecDeque::new(),
+        }
+    }
+
+    /// Gets the [`BlockHash`]es at the given heights in a single batched request.
+    async fn get_block_hashes_at(&self, heights: &[u64]) -> ClientResult<Vec<BlockHash>> {
+        let calls = heights
+            .iter()
+            .map(|height| Ok(("getblockhash", vec![to_value(height)?])))
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.call_batch::<BlockHash>(&calls)
+            .await?
+            .into_iter()
+            .collect()
+    }
+
+    /// Sends a JSON-RPC request body through the [`Transport`] once and returns the raw
+    /// response body.
+    async fn send(&self, body: &Value) -> ClientResult<String> {
+        self.transport
+            .send_request(body, self.wallet.as_deref())
+            .await
+    }
+
+    /// Runs a request, from sending it to reading its result, and retries errors according to
+    /// the [`RetryPolicy`] of the [`Client`].
+    ///
+    /// Errors are classified whether the [`Transport`] returns them or `bitcoind` reports them
+    /// in the response body, so an RPC error such as a warmup is retried the same way over
+    /// every transport.
+    async fn with_retries<R, F, Fut>(&self, mut request: F) -> ClientResult<R>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ClientResult<R>>,
+    {
+        let mut retries = 0;
+        loop {
+            trace!(%retries, "Sending request to bitcoin client");
+
+            let err = match request().await {
+                Ok(result) => return Ok(result),
+                Err(err) => err,
+            };
+            if !self.retry_policy.is_retryable(&err) {
+                return Err(err);
             }
+
             retries += 1;
-            if retries >= self.max_retries {
-                return Err(ClientError::MaxRetriesExceeded(self.max_retries));
+            if retries > self.retry_policy.max_retries() {
+                return Err(ClientError::MaxRetriesExceeded(
+                    self.retry_policy.max_retries(),
+                    Box::new(err),
+                ));
             }
-            sleep(Duration::from_millis(self.retry_interval)).await;
+            let delay = self.retry_policy.delay(retries);
+            warn!(%err, ?delay, %retries, "retrying...");
+            sleep(delay).await;
         }
     }
 }
 
-impl Reader for Client {
-    async fn estimate_smart_fee(&self, conf_target: u16) -> ClientResult<u64> {
-        let result = self
-            .call::<Box<RawValue>>("estimatesmartfee", &[to_value(conf_target)?])
-            .await?
-            .to_string();
+/// Builder for a [`Client`] with full control over the underlying HTTP transport.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use bitcoind_async_client::{Auth, Client};
+/// # fn main() -> Result<(), bitcoind_async_client::error::ClientError> {
+/// let client = Client::builder("http://localhost:8332")
+///     .auth(Auth::CookieFile("/home/bitcoin/.bitcoin/.cookie".into()))
+///     .wallet("default")
+///     .timeout(Duration::from_secs(30))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    /// The URL of the `bitcoind` instance.
+    url: String,
 
-        let result_map: Value = result.parse::<Value>()?;
+    /// The authentication method.
+    auth: Option<Auth>,
 
-        let btc_vkb = result_map
-            .get("feerate")
-            .unwrap_or(&"0.00001".parse::<Value>().unwrap())
-            .as_f64()
-            .unwrap();
+    /// The wallet that requests are sent to, if any.
+    wallet: Option<String>,
+
+    /// Decides whether and when failed requests are retried.
+    retry_policy: Arc<dyn RetryPolicy>,
+
+    /// Timeout for a whole request, from connecting until the response body is read.
+    timeout: Option<Duration>,
+
+    /// Timeout for establishing a connection.
+    connect_timeout: Option<Duration>,
+
+    /// The `User-Agent` header sent with every request.
+    user_agent: Option<String>,
+
+    /// How long idle connections are kept in the pool.
+    pool_idle_timeout: Option<Duration>,
+
+    /// Maximum number of idle connections kept in the pool.
+    pool_max_idle_per_host: Option<usize>,
+
+    /// Proxy URL that all requests are routed through.
+    proxy: Option<String>,
+
+    /// Extra headers sent with every request.
+    headers: HeaderMap,
+}
+
+impl ClientBuilder {
+    /// Creates a new [`ClientBuilder`] for the `bitcoind` instance at the given URL.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            auth: None,
+            wallet: None,
+            retry_policy: Arc::new(ConstantBackoff::default()),
+            timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            proxy: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Sets the authentication method. Required.
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Sends all requests to the `/wallet/<name>` endpoint of the given wallet.
+    pub fn wallet(mut self, wallet: impl Into<String>) -> Self {
+        self.wallet = Some(wallet.into());
+        self
+    }
+
+    /// Sets the [`RetryPolicy`] deciding whether and when failed requests are retried.
+    ///
+    /// Defaults to [`ConstantBackoff::default`]: 3 retries, 1 second apart.
+    pub fn retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Arc::new(retry_policy);
+        self
+    }
+
+    /// Sets a timeout for a whole request, from connecting until the response body is read.
+    ///
+    /// Defaults to no timeout. Keep in mind that some RPCs, such as `scantxoutset` or
+    /// `importdescriptors`, can legitimately take minutes.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a timeout for establishing a connection.
+    ///
+    /// Defaults to no timeout.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets how long idle connections are kept in the pool.
+    pub fn pool_idle_timeout(mut self, pool_idle_timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(pool_idle_timeout);
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept in the pool.
+    pub fn pool_max_idle_per_host(mut self, pool_max_idle_per_host: usize) -> Self {
+        self.pool_max_idle_per_host = Some(pool_max_idle_per_host);
+        self
+    }
+
+    /// Routes all requests through the proxy at the given URL.
+    ///
+    /// Supports `http://` and `https://` proxies.
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Adds an extra header sent with every request.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Builds the [`Client`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::MissingUserPassword`] if no authentication method was set or the
+    /// credentials are empty, and [`ClientError::CookieFile`] if the cookie file can't be read.
+    pub fn build(mut self) -> ClientResult<Client> {
+        let wallet = self.wallet.take();
+        let retry_policy = self.retry_policy.clone();
+        let transport = self.build_transport()?;
+        Ok(Client {
+            transport: Arc::new(transport),
+            wallet,
+            id: Arc::new(AtomicUsize::new(0)),
+            retry_policy,
+        })
+    }
+
+    /// Builds only the [`HttpTransport`], to wrap it in another [`Transport`] such as a
+    /// [`RecordingTransport`](crate::transport::RecordingTransport).
+    ///
+    /// The wallet and retry policy of the builder are not used, pass them to
+    /// [`Client::from_transport`] and [`Client::wallet`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`ClientBuilder::build`].
+    pub fn build_transport(self) -> ClientResult<HttpTransport> {
+        let auth = self.auth.ok_or(ClientError::MissingUserPassword)?;
+
+        let content_type = "application/json"
+            .parse()
+            .map_err(|_| ClientError::Other("Error parsing header".to_string()))?;
+        let mut headers = self.headers;
+        headers.insert(CONTENT_TYPE, content_type);
+
+        trace!(headers = ?headers, ?auth);
+
+        let mut builder = ReqwestClient::builder().default_headers(headers);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(user_agent) = self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(pool_idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(pool_idle_timeout);
+        }
+        if let Some(pool_max_idle_per_host) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(pool_max_idle_per_host);
+        }
+        if let Some(proxy) = self.proxy {
+            let proxy =
+                Proxy::all(proxy).map_err(|e| ClientError::Other(format!("Invalid proxy: {e}")))?;
+            builder = builder.proxy(proxy);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| ClientError::Other(format!("Could not create client: {e}")))?;
+
+        trace!(url = %self.url, "Created bitcoin client");
+
+        HttpTransport::new(self.url, client, auth)
+    }
+}
+
+/// The default maximum number of attempts per request of the positional [`Client`]
+/// constructors.
+const DEFAULT_MAX_ATTEMPTS: u8 = 3;
+
+/// Creates the [`ConstantBackoff`] used by the positional [`Client`] constructors.
+///
+/// Their `max_retries` has always counted attempts rather than retries, so it is converted to
+/// keep the number of attempts unchanged.
+fn constant_backoff(max_attempts: Option<u8>, retry_interval: Option<u64>) -> ConstantBackoff {
+    let default = ConstantBackoff::default();
+    ConstantBackoff::new(
+        u32::from(max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS)).saturating_sub(1),
+        retry_interval.map_or(default.interval, Duration::from_millis),
+    )
+}
+
+impl<T: Transport> Reader for Client<T> {
+    async fn estimate_smart_fee(&self, conf_target: u16) -> ClientResult<u64> {
+        let estimate = self
+            .call::<SmartFeeEstimate>("estimatesmartfee", &[to_value(conf_target)?])
+            .await?;
+        // Fall back to the default minimum relay fee of 0.00001 BTC/kvB.
+        Ok(estimate
+            .fee_rate
+            .map_or(1, |fee_rate| fee_rate.to_sat_per_vb_floor()))
+    }
+
+    async fn estimate_smart_fee_with_mode(
+        &self,
+        conf_target: u16,
+        mode: EstimateMode,
+    ) -> ClientResult<SmartFeeEstimate> {
+        self.call::<SmartFeeEstimate>(
+            "estimatesmartfee",
+            &[to_value(conf_target)?, to_value(mode)?],
+        )
+        .await
+    }
 
-        // convert to sat/vB and round up
-        Ok((btc_vkb * 100_000_000.0 / 1000.0) as u64)
+    async fn estimate_raw_fee(
+        &self,
+        conf_target: u16,
+        threshold: Option<f64>,
+    ) -> ClientResult<EstimateRawFee> {
+        let mut params = vec![to_value(conf_target)?];
+        if let Some(threshold) = threshold {
+            params.push(to_value(threshold)?);
+        }
+        self.call::<EstimateRawFee>("estimaterawfee", &params).await
     }
 
     async fn get_block_header(&self, hash: &BlockHash) -> ClientResult<Header> {
@@ -281,6 +788,17 @@ impl Reader for Client {
         Ok(header)
     }
 
+    async fn get_block_header_verbose(
+        &self,
+        hash: &BlockHash,
+    ) -> ClientResult<GetBlockHeaderVerbose> {
+        self.call::<GetBlockHeaderVerbose>(
+            "getblockheader",
+            &[to_value(hash.to_string())?, to_value(true)?],
+        )
+        .await
+    }
+
     async fn get_block(&self, hash: &BlockHash) -> ClientResult<Block> {
         let get_block = self
             .call::<GetBlockVerbosityZero>("getblock", &[to_value(hash.to_string())?, to_value(0)?])
@@ -291,6 +809,28 @@ impl Reader for Client {
         Ok(block)
     }
 
+    async fn get_block_verbose(
+        &self,
+        hash: &BlockHash,
+        include_prevouts: bool,
+    ) -> ClientResult<GetBlockVerbose> {
+        let verbosity = if include_prevouts { 3 } else { 2 };
+        self.call::<GetBlockVerbose>(
+            "getblock",
+            &[to_value(hash.to_string())?, to_value(verbosity)?],
+        )
+        .await
+    }
+
+    async fn get_block_stats(
+        &self,
+        target: BlockStatsTarget,
+        fields: Option<&[BlockStatsField]>,
+    ) -> ClientResult<GetBlockStats> {
+        self.call::<GetBlockStats>("getblockstats", &block_stats_params(target, fields)?)
+            .await
+    }
+
     async fn get_block_height(&self, hash: &BlockHash) -> ClientResult<u64> {
         let block_verobose = self
             .call::<GetBlockVerbosityOne>("getblock", &[to_value(hash.to_string())?])
@@ -310,6 +850,45 @@ impl Reader for Client {
         self.get_block(&hash).await
     }
 
+    async fn get_block_headers_at(&self, heights: &[u64]) -> ClientResult<Vec<Header>> {
+        let hashes = self.get_block_hashes_at(heights).await?;
+        let calls = hashes
+            .iter()
+            .map(|hash| {
+                Ok((
+                    "getblockheader",
+                    vec![to_value(hash.to_string())?, to_value(false)?],
+                ))
+            })
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.call_batch::<GetBlockHeaderVerbosityZero>(&calls)
+            .await?
+            .into_iter()
+            .map(|header| {
+                header?
+                    .header()
+                    .map_err(|err| ClientError::Other(format!("header decode: {err}")))
+            })
+            .collect()
+    }
+
+    async fn get_blocks_at(&self, heights: &[u64]) -> ClientResult<Vec<Block>> {
+        let hashes = self.get_block_hashes_at(heights).await?;
+        let calls = hashes
+            .iter()
+            .map(|hash| Ok(("getblock", vec![to_value(hash.to_string())?, to_value(0)?])))
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.call_batch::<GetBlockVerbosityZero>(&calls)
+            .await?
+            .into_iter()
+            .map(|block| {
+                block?
+                    .block()
+                    .map_err(|err| ClientError::Other(format!("block decode: {err}")))
+            })
+            .collect()
+    }
+
     async fn get_block_count(&self) -> ClientResult<u64> {
         self.call::<u64>("getblockcount", &[]).await
     }
@@ -338,6 +917,38 @@ impl Reader for Client {
         self.call::<GetMempoolInfo>("getmempoolinfo", &[]).await
     }
 
+    async fn get_raw_mempool_verbose(&self) -> ClientResult<BTreeMap<Txid, GetMempoolEntry>> {
+        self.call::<BTreeMap<Txid, GetMempoolEntry>>("getrawmempool", &[to_value(true)?])
+            .await
+    }
+
+    async fn get_mempool_entry(&self, txid: &Txid) -> ClientResult<GetMempoolEntry> {
+        self.call::<GetMempoolEntry>("getmempoolentry", &[to_value(txid.to_string())?])
+            .await
+    }
+
+    async fn get_mempool_ancestors(
+        &self,
+        txid: &Txid,
+    ) -> ClientResult<BTreeMap<Txid, GetMempoolEntry>> {
+        self.call::<BTreeMap<Txid, GetMempoolEntry>>(
+            "getmempoolancestors",
+            &[to_value(txid.to_string())?, to_value(true)?],
+        )
+        .await
+    }
+
+    async fn get_mempool_descendants(
+        &self,
+        txid: &Txid,
+    ) -> ClientResult<BTreeMap<Txid, GetMempoolEntry>> {
+        self.call::<BTreeMap<Txid, GetMempoolEntry>>(
+            "getmempooldescendants",
+            &[to_value(txid.to_string())?, to_value(true)?],
+        )
+        .await
+    }
+
     async fn get_raw_transaction_verbosity_zero(
         &self,
         txid: &Txid,
@@ -377,16 +988,118 @@ impl Reader for Client {
         .await
     }
 
+    async fn get_tx_out_set_info(
+        &self,
+        hash_type: Option<TxOutSetHashType>,
+    ) -> ClientResult<GetTxOutSetInfo> {
+        self.call::<GetTxOutSetInfo>(
+            "gettxoutsetinfo",
+            &[to_value(hash_type.unwrap_or_default())?],
+        )
+        .await
+    }
+
+    async fn scan_tx_out_set_start(
+        &self,
+        scan_objects: &[ScanObject],
+    ) -> ClientResult<ScanTxOutSet> {
+        self.call::<ScanTxOutSet>(
+            "scantxoutset",
+            &[to_value("start")?, to_value(scan_objects)?],
+        )
+        .await
+    }
+
+    async fn scan_tx_out_set_status(&self) -> ClientResult<Option<ScanTxOutSetStatus>> {
+        self.call::<Option<ScanTxOutSetStatus>>("scantxoutset", &[to_value("status")?])
+            .await
+    }
+
+    async fn scan_tx_out_set_abort(&self) -> ClientResult<bool> {
+        self.call::<bool>("scantxoutset", &[to_value("abort")?])
+            .await
+    }
+
     async fn network(&self) -> ClientResult<Network> {
-        self.call::<GetBlockchainInfo>("getblockchaininfo", &[])
+        let chain = self
+            .call::<GetBlockchainInfo>("getblockchaininfo", &[])
             .await?
-            .chain
-            .parse::<Network>()
+            .chain;
+        // `bitcoind` calls mainnet and testnet3 "main" and "test".
+        Network::from_core_arg(&chain)
+            .or_else(|_| chain.parse::<Network>())
             .map_err(|e| ClientError::Parse(e.to_string()))
     }
+
+    async fn get_block_filter(&self, hash: &BlockHash) -> ClientResult<GetBlockFilter> {
+        self.call::<GetBlockFilter>("getblockfilter", &[to_value(hash.to_string())?])
+            .await
+    }
+
+    async fn get_tx_out_proof(
+        &self,
+        txids: &[Txid],
+        block_hash: Option<&BlockHash>,
+    ) -> ClientResult<MerkleBlock> {
+        let mut params = vec![to_value(txids)?];
+        if let Some(block_hash) = block_hash {
+            params.push(to_value(block_hash.to_string())?);
+        }
+        let get_tx_out_proof = self.call::<GetTxOutProof>("gettxoutproof", &params).await?;
+        get_tx_out_proof
+            .merkle_block()
+            .map_err(|err| ClientError::Other(format!("merkle block decode: {err}")))
+    }
+
+    async fn verify_tx_out_proof(&self, proof: &MerkleBlock) -> ClientResult<Vec<Txid>> {
+        self.call::<Vec<Txid>>("verifytxoutproof", &[to_value(serialize_hex(proof))?])
+            .await
+    }
+
+    async fn get_chain_tips(&self) -> ClientResult<Vec<ChainTip>> {
+        self.call::<Vec<ChainTip>>("getchaintips", &[]).await
+    }
+
+    async fn decode_raw_transaction(&self, tx: &Transaction) -> ClientResult<DecodeRawTransaction> {
+        self.call::<DecodeRawTransaction>("decoderawtransaction", &[to_value(serialize_hex(tx))?])
+            .await
+    }
+
+    async fn decode_script(&self, script: &Script) -> ClientResult<DecodeScript> {
+        self.call::<DecodeScript>("decodescript", &[to_value(script.to_hex_string())?])
+            .await
+    }
+
+    async fn get_descriptor_info(&self, descriptor: &str) -> ClientResult<GetDescriptorInfo> {
+        self.call::<GetDescriptorInfo>("getdescriptorinfo", &[to_value(descriptor)?])
+            .await
+    }
+
+    async fn derive_addresses(
+        &self,
+        descriptor: &str,
+        range: Option<(u32, u32)>,
+    ) -> ClientResult<Vec<Address>> {
+        let mut params = vec![to_value(descriptor)?];
+        if let Some((start, end)) = range {
+            params.push(to_value([start, end])?);
+        }
+        let addresses = self
+            .call::<Vec<Address<NetworkUnchecked>>>("deriveaddresses", &params)
+            .await?;
+        let network = self.network().await?;
+        addresses
+            .into_iter()
+            .map(|address| {
+                address
+                    .require_network(network)
+                    .map_err(|_| ClientError::WrongNetworkAddress(network))
+            })
+            .collect()
+    }
 }
 
-impl Broadcaster for Client {
+impl<T: Transport> Broadcaster for Client<T> {
     async fn send_raw_transaction(&self, tx: &Transaction) -> ClientResult<Txid> {
         let txstr = serialize_hex(tx);
         trace!(txstr = %txstr, "Sending raw transaction");
@@ -398,11 +1111,11 @@ impl Broadcaster for Client {
                 trace!(?txid, "Transaction sent");
                 Ok(txid)
             }
-            Err(ClientError::Server(i, s)) => match i {
-                // Dealing with known and common errors
-                -27 => Ok(tx.compute_txid()), // Tx already in chain
-                _ => Err(ClientError::Server(i, s)),
-            },
+            // Tx already in chain
+            Err(e) if e.rpc_error_code() == Some(RpcErrorCode::VerifyAlreadyInUtxoSet) => {
+                Ok(tx.compute_txid())
+            }
+            Err(e @ ClientError::Server(..)) => Err(e),
             Err(e) => Err(ClientError::Other(e.to_string())),
         }
     }
@@ -421,7 +1134,7 @@ impl Broadcaster for Client {
     }
 }
 
-impl Wallet for Client {
+impl<T: Transport> Wallet for Client<T> {
     async fn get_new_address(&self) -> ClientResult<Address> {
         let address_unchecked = self
             .call::<GetNewAddress>("getnewaddress", &[])
@@ -521,7 +1234,7 @@ impl Wallet for Client {
     }
 }
 
-impl Signer for Client {
+impl<T: Transport> Signer for Client<T> {
     async fn sign_raw_transaction_with_wallet(
         &self,
         tx: &Transaction,
@@ -581,17 +1294,46 @@ impl Signer for Client {
             load_on_startup: Some(true),
         };
 
-        // TODO: this should check for -35 error code which is good,
-        //       means that is already created
-        let _wallet_create = self
-            .call::<Value>("createwallet", &[to_value(wallet_args.clone())?])
-            .await;
-        // TODO: this should check for -35 error code which is good, -18 is bad.
-        let _wallet_load = self
-            .call::<Value>("loadwallet", &[to_value(wallet_args)?])
+        // `load_on_startup` is the seventh positional argument of `createwallet`,
+        // `null` leaves the ones in between at their defaults.
+        let wallet_create = self
+            .call::<Value>(
+                "createwallet",
+                &[
+                    to_value(&wallet_args.wallet_name)?,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    Value::Null,
+                    to_value(wallet_args.load_on_startup)?,
+                ],
+            )
             .await;
+        match wallet_create {
+            Ok(_) => {}
+            // The wallet was created before, make sure it is loaded.
+            Err(e) if e.rpc_error_code() == Some(RpcErrorCode::WalletAlreadyExists) => {
+                let wallet_load = self
+                    .call::<Value>(
+                        "loadwallet",
+                        &[
+                            to_value(&wallet_args.wallet_name)?,
+                            to_value(wallet_args.load_on_startup)?,
+                        ],
+                    )
+                    .await;
+                match wallet_load {
+                    Ok(_) => {}
+                    Err(e) if e.rpc_error_code() == Some(RpcErrorCode::WalletAlreadyLoaded) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Err(e) => return Err(e),
+        }
 
         let result = self
+            .wallet(wallet_args.wallet_name)
             .call::<Vec<ImportDescriptorResult>>("importdescriptors", &[to_value(descriptors)?])
             .await?;
         Ok(result)
@@ -599,12 +1341,12 @@ impl Signer for Client {
 
     async fn wallet_process_psbt(
         &self,
-        psbt: &str,
+        psbt: &Psbt,
         sign: Option<bool>,
         sighashtype: Option<SighashType>,
         bip32_derivs: Option<bool>,
     ) -> ClientResult<WalletProcessPsbtResult> {
-        let mut params = vec![to_value(psbt)?, to_value(sign.unwrap_or(true))?];
+        let mut params = vec![to_value(psbt.to_string())?, to_value(sign.unwrap_or(true))?];
 
         if let Some(sighashtype) = sighashtype {
             params.push(to_value(sighashtype)?);
@@ -633,22 +1375,351 @@ impl Signer for Client {
     }
 }
 
+impl<T: Transport> Mining for Client<T> {
+    async fn get_block_template(&self, rules: &[TemplateRule]) -> ClientResult<GetBlockTemplate> {
+        self.call::<GetBlockTemplate>("getblocktemplate", &[json!({ "rules": rules })])
+            .await
+    }
+
+    async fn get_mining_info(&self) -> ClientResult<GetMiningInfo> {
+        self.call::<GetMiningInfo>("getmininginfo", &[]).await
+    }
+
+    async fn get_network_hashps(
+        &self,
+        blocks: Option<u64>,
+        height: Option<u64>,
+    ) -> ClientResult<f64> {
+        // `bitcoind` takes -1 for the chain tip.
+        let height = height.map_or(Ok(json!(-1)), to_value)?;
+        self.call::<f64>(
+            "getnetworkhashps",
+            &[to_value(blocks.unwrap_or(120))?, height],
+        )
+        .await
+    }
+
+    async fn submit_block(&self, block: &Block) -> ClientResult<Option<SubmitBlockReason>> {
+        let reason = self
+            .call::<Option<String>>("submitblock", &[to_value(serialize_hex(block))?])
+            .await?;
+        Ok(reason.map(SubmitBlockReason::from))
+    }
+
+    async fn submit_header(&self, header: &Header) -> ClientResult<Option<SubmitBlockReason>> {
+        match self
+            .call::<()>("submitheader", &[to_value(serialize_hex(header))?])
+            .await
+        {
+            Ok(()) => Ok(None),
+            Err(ClientError::Server(code, message))
+                if RpcErrorCode::from(code) == RpcErrorCode::VerifyError =>
+            {
+                Ok(Some(SubmitBlockReason::from(message)))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<T: Transport> Client<T> {
+    /// Fails with [`ClientError::NotRegtest`] unless `bitcoind` runs on [`Network::Regtest`].
+    async fn require_regtest(&self) -> ClientResult<()> {
+        match self.network().await? {
+            Network::Regtest => Ok(()),
+            network => Err(ClientError::NotRegtest(network)),
+        }
+    }
+}
+
+impl<T: Transport> RegtestControl for Client<T> {
+    async fn generate_to_address(
+        &self,
+        count: u64,
+        address: &Address,
+    ) -> ClientResult<Vec<BlockHash>> {
+        self.require_regtest().await?;
+        self.call::<Vec<BlockHash>>(
+            "generatetoaddress",
+            &[to_value(count)?, to_value(address.to_string())?],
+        )
+        .await
+    }
+
+    async fn generate_to_descriptor(
+        &self,
+        count: u64,
+        descriptor: &str,
+    ) -> ClientResult<Vec<BlockHash>> {
+        self.require_regtest().await?;
+        self.call::<Vec<BlockHash>>(
+            "generatetodescriptor",
+            &[to_value(count)?, to_value(descriptor)?],
+        )
+        .await
+    }
+
+    async fn generate_block(
+        &self,
+        address: &Address,
+        transactions: &[Transaction],
+    ) -> ClientResult<BlockHash> {
+        self.require_regtest().await?;
+        let transactions = transactions.iter().map(serialize_hex).collect::<Vec<_>>();
+        let generate_block = self
+            .call::<GenerateBlock>(
+                "generateblock",
+                &[to_value(address.to_string())?, to_value(transactions)?],
+            )
+            .await?;
+        Ok(generate_block.hash)
+    }
+
+    async fn set_mock_time(&self, timestamp: u64) -> ClientResult<()> {
+        self.require_regtest().await?;
+        self.call::<()>("setmocktime", &[to_value(timestamp)?])
+            .await
+    }
+
+    async fn invalidate_block(&self, hash: &BlockHash) -> ClientResult<()> {
+        self.require_regtest().await?;
+        self.call::<()>("invalidateblock", &[to_value(hash.to_string())?])
+            .await
+    }
+
+    async fn reconsider_block(&self, hash: &BlockHash) -> ClientResult<()> {
+        self.require_regtest().await?;
+        self.call::<()>("reconsiderblock", &[to_value(hash.to_string())?])
+            .await
+    }
+}
+
+impl<T: Transport> NodeControl for Client<T> {
+    async fn uptime(&self) -> ClientResult<u64> {
+        self.call::<u64>("uptime", &[]).await
+    }
+
+    async fn get_rpc_info(&self) -> ClientResult<GetRpcInfo> {
+        self.call::<GetRpcInfo>("getrpcinfo", &[]).await
+    }
+
+    async fn logging(
+        &self,
+        include: &[&str],
+        exclude: &[&str],
+    ) -> ClientResult<BTreeMap<String, bool>> {
+        self.call::<BTreeMap<String, bool>>("logging", &[to_value(include)?, to_value(exclude)?])
+            .await
+    }
+
+    async fn stop(&self) -> ClientResult<()> {
+        self.call::<String>("stop", &[]).await?;
+        Ok(())
+    }
+
+    async fn prune_blockchain(&self, height: u64) -> ClientResult<u64> {
+        self.call::<u64>("pruneblockchain", &[to_value(height)?])
+            .await
+    }
+
+    async fn get_index_info(
+        &self,
+        index_name: Option<&str>,
+    ) -> ClientResult<BTreeMap<String, IndexInfo>> {
+        let params = match index_name {
+            Some(index_name) => vec![to_value(index_name)?],
+            None => Vec::new(),
+        };
+        self.call::<BTreeMap<String, IndexInfo>>("getindexinfo", &params)
+            .await
+    }
+
+    async fn get_deployment_info(
+        &self,
+        block_hash: Option<&BlockHash>,
+    ) -> ClientResult<GetDeploymentInfo> {
+        let params = match block_hash {
+            Some(block_hash) => vec![to_value(block_hash.to_string())?],
+            None => Vec::new(),
+        };
+        self.call::<GetDeploymentInfo>("getdeploymentinfo", &params)
+            .await
+    }
+}
+
+impl<T: Transport> PsbtRpc for Client<T> {
+    async fn create_psbt(
+        &self,
+        inputs: &[CreateRawTransactionInput],
+        outputs: &[CreateRawTransactionOutput],
+        locktime: Option<u32>,
+        replaceable: Option<bool>,
+    ) -> ClientResult<Psbt> {
+        let psbt = self
+            .call::<String>(
+                "createpsbt",
+                &[
+                    to_value(inputs)?,
+                    to_value(outputs)?,
+                    to_value(locktime.unwrap_or(0))?,
+                    to_value(replaceable)?,
+                ],
+            )
+            .await?;
+        parse_psbt(&psbt)
+    }
+
+    async fn combine_psbt(&self, psbts: &[Psbt]) -> ClientResult<Psbt> {
+        let psbts = psbts.iter().map(Psbt::to_string).collect::<Vec<_>>();
+        let psbt = self
+            .call::<String>("combinepsbt", &[to_value(psbts)?])
+            .await?;
+        parse_psbt(&psbt)
+    }
+
+    async fn finalize_psbt(
+        &self,
+        psbt: &Psbt,
+        extract: Option<bool>,
+    ) -> ClientResult<WalletProcessPsbtResult> {
+        self.call::<WalletProcessPsbtResult>(
+            "finalizepsbt",
+            &[
+                to_value(psbt.to_string())?,
+                to_value(extract.unwrap_or(true))?,
+            ],
+        )
+        .await
+    }
+
+    async fn join_psbts(&self, psbts: &[Psbt]) -> ClientResult<Psbt> {
+        let psbts = psbts.iter().map(Psbt::to_string).collect::<Vec<_>>();
+        let psbt = self
+            .call::<String>("joinpsbts", &[to_value(psbts)?])
+            .await?;
+        parse_psbt(&psbt)
+    }
+
+    async fn utxo_update_psbt(
+        &self,
+        psbt: &Psbt,
+        descriptors: Option<&[ScanObject]>,
+    ) -> ClientResult<Psbt> {
+        let mut params = vec![to_value(psbt.to_string())?];
+        if let Some(descriptors) = descriptors {
+            params.push(to_value(descriptors)?);
+        }
+        let psbt = self.call::<String>("utxoupdatepsbt", &params).await?;
+        parse_psbt(&psbt)
+    }
+
+    async fn convert_to_psbt(
+        &self,
+        tx: &Transaction,
+        permit_sig_data: Option<bool>,
+    ) -> ClientResult<Psbt> {
+        let psbt = self
+            .call::<String>(
+                "converttopsbt",
+                &[
+                    to_value(serialize_hex(tx))?,
+                    to_value(permit_sig_data.unwrap_or(false))?,
+                ],
+            )
+            .await?;
+        parse_psbt(&psbt)
+    }
+
+    async fn decode_psbt(&self, psbt: &Psbt) -> ClientResult<DecodePsbt> {
+        self.call::<DecodePsbt>("decodepsbt", &[to_value(psbt.to_string())?])
+            .await
+    }
+
+    async fn analyze_psbt(&self, psbt: &Psbt) -> ClientResult<AnalyzePsbt> {
+        self.call::<AnalyzePsbt>("analyzepsbt", &[to_value(psbt.to_string())?])
+            .await
+    }
+}
+
+/// Parses a base64 PSBT returned by `bitcoind`.
+fn parse_psbt(psbt: &str) -> ClientResult<Psbt> {
+    psbt.parse::<Psbt>()
+        .map_err(|e| ClientError::Other(format!("Failed to deserialize PSBT: {e}")))
+}
+
+impl<T: Transport> NetworkControl for Client<T> {
+    async fn get_network_info(&self) -> ClientResult<GetNetworkInfo> {
+        self.call::<GetNetworkInfo>("getnetworkinfo", &[]).await
+    }
+
+    async fn get_peer_info(&self) -> ClientResult<Vec<PeerInfo>> {
+        self.call::<Vec<PeerInfo>>("getpeerinfo", &[]).await
+    }
+
+    async fn get_connection_count(&self) -> ClientResult<u64> {
+        self.call::<u64>("getconnectioncount", &[]).await
+    }
+
+    async fn get_net_totals(&self) -> ClientResult<GetNetTotals> {
+        self.call::<GetNetTotals>("getnettotals", &[]).await
+    }
+
+    async fn add_node(&self, node: &str, command: AddNodeCommand) -> ClientResult<()> {
+        self.call::<()>("addnode", &[to_value(node)?, to_value(command)?])
+            .await
+    }
+
+    async fn disconnect_node(&self, target: &DisconnectTarget) -> ClientResult<()> {
+        let params = match target {
+            DisconnectTarget::Address(address) => vec![to_value(address)?],
+            DisconnectTarget::Id(id) => vec![to_value("")?, to_value(id)?],
+        };
+        self.call::<()>("disconnectnode", &params).await
+    }
+
+    async fn set_ban(
+        &self,
+        subnet: &str,
+        command: SetBanCommand,
+        ban_time: Option<u64>,
+    ) -> ClientResult<()> {
+        let mut params = vec![to_value(subnet)?, to_value(command)?];
+        if let Some(ban_time) = ban_time {
+            params.push(to_value(ban_time)?);
+        }
+        self.call::<()>("setban", &params).await
+    }
+
+    async fn list_banned(&self) -> ClientResult<Vec<BannedSubnet>> {
+        self.call::<Vec<BannedSubnet>>("listbanned", &[]).await
+    }
+
+    async fn set_network_active(&self, active: bool) -> ClientResult<bool> {
+        self.call::<bool>("setnetworkactive", &[to_value(active)?])
+            .await
+    }
+}
+
 #[cfg(test)]
 mod test {
 
-    use std::sync::Once;
+    use std::{env, process, sync::Once};
 
     use bitcoin::{
         consensus::{self, encode::deserialize_hex},
         hashes::Hash,
-        transaction, Amount, FeeRate, NetworkKind,
+        transaction, Amount, FeeRate, NetworkKind, OutPoint,
     };
     use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
     use super::*;
     use crate::{
-        test_utils::corepc_node_helpers::{get_bitcoind_and_client, mine_blocks},
-        types::{CreateRawTransactionInput, CreateRawTransactionOutput},
+        retry::ExponentialBackoff,
+        test_utils::{
+            corepc_node_helpers::{get_bitcoind_and_client, mine_blocks},
+            transport_helpers::FnTransport,
+        },
+        types::{ChainTipStatus, CreateRawTransactionInput, CreateRawTransactionOutput, PsbtRole},
     };
 
     /// 50 BTC in [`Network::Regtest`].
@@ -695,6 +1766,52 @@ mod test {
         let got = client.get_block(expected).await.unwrap().block_hash();
         assert_eq!(*expected, got);
 
+        // get_block_verbose
+        let block = client.get_block_verbose(expected, true).await.unwrap();
+        assert_eq!(block.hash, *expected);
+        assert_eq!(block.height, blocks.len() as u64);
+        assert!(block.tx[0].transaction.is_coinbase());
+        assert_eq!(block.tx[0].fee, None);
+        let block = client.get_block_verbose(expected, false).await.unwrap();
+        assert_eq!(block.tx.len(), block.n_tx as usize);
+
+        // get_block_stats
+        let stats = client
+            .get_block_stats(BlockStatsTarget::Hash(*expected), None)
+            .await
+            .unwrap();
+        assert_eq!(stats.height, Some(blocks.len() as u64));
+        assert_eq!(stats.subsidy, Some(COINBASE_AMOUNT));
+        let stats = client
+            .get_block_stats(
+                BlockStatsTarget::Height(1),
+                Some(&[BlockStatsField::TotalFee]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stats.total_fee, Some(Amount::ZERO));
+        assert_eq!(stats.height, None);
+
+        // get_block_header_verbose
+        let header = client.get_block_header_verbose(expected).await.unwrap();
+        assert_eq!(header.hash, *expected);
+        assert_eq!(header.height, blocks.len() as u64);
+        assert_eq!(header.confirmations, 1);
+        assert_eq!(header.previous_block_hash, Some(blocks[blocks.len() - 2]));
+        assert_eq!(header.next_block_hash, None);
+
+        // get_chain_tips
+        let tips = client.get_chain_tips().await.unwrap();
+        assert_eq!(tips.len(), 1);
+        assert_eq!(tips[0].hash, *expected);
+        assert_eq!(tips[0].status, ChainTipStatus::Active);
+
+        // find_fork
+        let fork = client.find_fork(&blocks[0], expected).await.unwrap();
+        assert_eq!(fork.fork_point, blocks[0]);
+        assert_eq!(fork.connected, blocks[1..]);
+        assert!(fork.disconnected.is_empty());
+
         // get_block_at
         let target_height = blocks.len() as u64;
         let expected = blocks.last().unwrap();
@@ -762,12 +1879,49 @@ mod test {
         assert!(got.loaded);
         assert_eq!(got.size, 1);
         assert_eq!(got.unbroadcastcount, 1);
+        assert_eq!(got.minrelaytxfee, FeeRate::BROADCAST_MIN);
+
+        // get_raw_mempool_verbose
+        let got = client.get_raw_mempool_verbose().await.unwrap();
+        assert_eq!(got.keys().collect::<Vec<_>>(), vec![&txid]);
+
+        // get_mempool_entry
+        let entry = client.get_mempool_entry(&txid).await.unwrap();
+        assert_eq!(entry, got[&txid]);
+        assert_eq!(entry.ancestor_count, 1);
+        assert!(entry.depends.is_empty());
+        assert!(entry.fee_rate().unwrap() >= FeeRate::BROADCAST_MIN);
+
+        // get_mempool_ancestors and get_mempool_descendants
+        assert!(client
+            .get_mempool_ancestors(&txid)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(client
+            .get_mempool_descendants(&txid)
+            .await
+            .unwrap()
+            .is_empty());
 
         // estimate_smart_fee
         let got = client.estimate_smart_fee(1).await.unwrap();
         let expected = 1; // 1 sat/vB
         assert_eq!(expected, got);
 
+        // estimate_smart_fee_with_mode
+        let got = client
+            .estimate_smart_fee_with_mode(1, EstimateMode::Economical)
+            .await
+            .unwrap();
+        assert_eq!(got.fee_rate, None);
+        assert!(!got.errors.is_empty());
+
+        // estimate_raw_fee
+        let got = client.estimate_raw_fee(20, None).await.unwrap();
+        assert!(got.short.is_none());
+        assert!(got.long.is_some());
+
         // sign_raw_transaction_with_wallet
         let got = client
             .sign_raw_transaction_with_wallet(&tx, None)
@@ -805,6 +1959,26 @@ mod test {
             .unwrap();
         assert_eq!(got.len(), 3);
 
+        // get_tx_out_proof and verify_tx_out_proof
+        let proof = client.get_tx_out_proof(&[txid], None).await.unwrap();
+        let tip = client
+            .get_block_hash(client.get_block_count().await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(proof.header.block_hash(), tip);
+        assert_eq!(
+            client.get_tx_out_proof(&[txid], Some(&tip)).await.unwrap(),
+            proof
+        );
+        assert_eq!(
+            client.verify_tx_out_proof(&proof).await.unwrap(),
+            vec![txid]
+        );
+        assert_eq!(
+            client.verify_tx_out_proof_locally(&proof).await.unwrap(),
+            vec![txid]
+        );
+
         // listdescriptors
         let got = client.get_xpriv().await.unwrap().unwrap().network;
         let expected = NetworkKind::Test;
@@ -813,14 +1987,34 @@ mod test {
         // importdescriptors
         // taken from https://github.com/rust-bitcoin/rust-bitcoin/blob/bb38aeb786f408247d5bbc88b9fa13616c74c009/bitcoin/examples/taproot-psbt.rs#L18C38-L18C149
         let descriptor_string = "tr([e61b318f/20000'/20']tprv8ZgxMBicQKsPd4arFr7sKjSnKFDVMR2JHw9Y8L9nXN4kiok4u28LpHijEudH3mMYoL4pM5UL9Bgdz2M4Cy8EzfErmU9m86ZTw6hCzvFeTg7/101/*)#2plamwqs".to_owned();
+
+        // getdescriptorinfo
+        let (descriptor, checksum) = descriptor_string.split_once('#').unwrap();
+        let info = client.get_descriptor_info(descriptor).await.unwrap();
+        assert_eq!(info.checksum, checksum);
+        assert!(info.is_range);
+        assert!(info.is_solvable);
+        assert!(info.has_private_keys);
+        assert!(!info.descriptor.contains("tprv"));
+
+        // deriveaddresses
+        let got = client
+            .derive_addresses(&info.descriptor, Some((0, 2)))
+            .await
+            .unwrap();
+        assert_eq!(got.len(), 3);
+        assert!(got.iter().all(|address| address.script_pubkey().is_p2tr()));
+        assert!(client.derive_addresses(descriptor, None).await.is_err());
+
         let timestamp = "now".to_owned();
         let list_descriptors = vec![ImportDescriptor {
             desc: descriptor_string,
             active: Some(true),
             timestamp,
         }];
+        // Import into the node's own wallet so the calls below keep targeting a single wallet.
         let got = client
-            .import_descriptors(list_descriptors, "strata".to_owned())
+            .import_descriptors(list_descriptors, "default".to_owned())
             .await
             .unwrap();
         let expected = vec![ImportDescriptorResult { success: true }];
@@ -839,20 +2033,50 @@ mod test {
         assert!(!funded_psbt.psbt.inputs.is_empty());
         assert!(funded_psbt.fee.to_sat() > 0);
 
+        // decodepsbt and analyzepsbt
+        let decoded = client.decode_psbt(&funded_psbt.psbt).await.unwrap();
+        assert_eq!(decoded.fee, Some(funded_psbt.fee));
+        assert_eq!(decoded.tx.txid, funded_psbt.psbt.unsigned_tx.compute_txid());
+        let analysis = client.analyze_psbt(&funded_psbt.psbt).await.unwrap();
+        assert_eq!(analysis.next, PsbtRole::Signer);
+        assert_eq!(analysis.fee, Some(funded_psbt.fee));
+        assert!(analysis.inputs.iter().all(|input| input.has_utxo
+            && input.next == Some(PsbtRole::Signer)
+            && !input.missing.as_ref().unwrap().signatures.is_empty()));
+
         let processed_psbt = client
-            .wallet_process_psbt(&funded_psbt.psbt.to_string(), None, None, None)
+            .wallet_process_psbt(&funded_psbt.psbt, None, None, None)
             .await
             .unwrap();
         assert!(!processed_psbt.psbt.as_ref().unwrap().inputs.is_empty());
         assert!(processed_psbt.complete);
+        let analysis = client
+            .analyze_psbt(processed_psbt.psbt.as_ref().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(analysis.next, PsbtRole::Extractor);
+        assert!(analysis.estimated_vsize.is_some());
+        assert!(analysis.estimated_feerate.is_some());
 
         let finalized_psbt = client
-            .wallet_process_psbt(&funded_psbt.psbt.to_string(), Some(true), None, None)
+            .wallet_process_psbt(&funded_psbt.psbt, Some(true), None, None)
             .await
             .unwrap();
         assert!(finalized_psbt.complete);
         assert!(finalized_psbt.hex.is_some());
         let signed_tx = finalized_psbt.hex.as_ref().unwrap();
+
+        // decoderawtransaction and decodescript
+        let decoded = client.decode_raw_transaction(signed_tx).await.unwrap();
+        assert_eq!(decoded.txid, signed_tx.compute_txid());
+        assert_eq!(decoded.vsize, signed_tx.vsize() as u64);
+        let script_pubkey = psbt_address.script_pubkey();
+        assert!(decoded
+            .vout
+            .iter()
+            .any(|output| output.script_pubkey.script == script_pubkey));
+        let decoded = client.decode_script(&script_pubkey).await.unwrap();
+        assert_eq!(decoded.address, Some(psbt_address.as_unchecked().clone()));
         let signed_txid = signed_tx.compute_txid();
         let got = client
             .test_mempool_accept(signed_tx)
@@ -943,7 +2167,7 @@ mod test {
         assert_eq!(expected, got);
 
         let address = bitcoind.client.new_address().unwrap();
-        let blocks = mine_blocks(&bitcoind, 101, Some(address)).unwrap();
+        let blocks = mine_blocks(&bitcoind, 101, Some(address.clone())).unwrap();
         let last_block = client.get_block(blocks.first().unwrap()).await.unwrap();
         let coinbase_tx = last_block.coinbase().unwrap();
 
@@ -954,6 +2178,33 @@ mod test {
             .unwrap();
         assert_eq!(got.value, COINBASE_AMOUNT.to_btc());
 
+        // get_tx_out_set_info
+        let got = client.get_tx_out_set_info(None).await.unwrap();
+        assert_eq!(got.height, 101);
+        assert_eq!(got.best_block, blocks[100]);
+        assert!(got.hash_serialized_3.is_some());
+        let got = client
+            .get_tx_out_set_info(Some(TxOutSetHashType::MuHash))
+            .await
+            .unwrap();
+        assert!(got.muhash.is_some());
+        assert_eq!(got.hash_serialized_3, None);
+
+        // scan_tx_out_set_start and scan_tx_out_set_status
+        let got = client
+            .scan_tx_out_set_start(&[format!("addr({address})").into()])
+            .await
+            .unwrap();
+        assert!(got.success);
+        assert_eq!(got.unspents.len(), 101);
+        assert_eq!(got.total_amount, COINBASE_AMOUNT * 101);
+        assert!(got
+            .unspents
+            .iter()
+            .any(|unspent| unspent.outpoint() == OutPoint::new(coinbase_tx.compute_txid(), 0)));
+        assert_eq!(client.scan_tx_out_set_status().await.unwrap(), None);
+        assert!(!client.scan_tx_out_set_abort().await.unwrap());
+
         // gettxout should fail with a spent UTXO.
         let new_address = bitcoind.client.new_address().unwrap();
         let send_amount = Amount::from_sat(COINBASE_AMOUNT.to_sat() - 2_000); // 2k sats as fees.
@@ -1163,6 +2414,48 @@ mod test {
         assert_eq!(result.package_msg, "success");
     }
 
+    #[tokio::test]
+    async fn call_batch() {
+        init_tracing();
+
+        let (bitcoind, client) = get_bitcoind_and_client();
+        let blocks = mine_blocks(&bitcoind, 10, None).unwrap();
+
+        // Empty batches never hit the server.
+        let got = client.call_batch::<BlockHash>(&[]).await.unwrap();
+        assert!(got.is_empty());
+
+        // Errors are reported per call and do not affect the rest of the batch.
+        let calls = [
+            ("getblockhash", vec![to_value(1).unwrap()]),
+            ("getblockhash", vec![to_value(1_000).unwrap()]),
+            ("getblockhash", vec![to_value(10).unwrap()]),
+        ];
+        let got = client.call_batch::<BlockHash>(&calls).await.unwrap();
+        assert_eq!(got.len(), 3);
+        assert_eq!(got[0].as_ref().unwrap(), &blocks[0]);
+        assert!(matches!(got[1], Err(ClientError::Server(-8, _))));
+        assert_eq!(got[2].as_ref().unwrap(), &blocks[9]);
+
+        // get_block_headers_at
+        let heights: Vec<u64> = (1..=10).collect();
+        let got = client.get_block_headers_at(&heights).await.unwrap();
+        let got: Vec<BlockHash> = got.iter().map(|header| header.block_hash()).collect();
+        assert_eq!(got, blocks);
+
+        // get_blocks_at
+        let got = client.get_blocks_at(&heights).await.unwrap();
+        let got: Vec<BlockHash> = got.iter().map(|block| block.block_hash()).collect();
+        assert_eq!(got, blocks);
+
+        // get_block_header_at
+        let got = client.get_block_header_at(5).await.unwrap().block_hash();
+        assert_eq!(got, blocks[4]);
+
+        // Unknown heights fail the whole helper.
+        assert!(client.get_block_headers_at(&[1, 1_000]).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_invalid_credentials_return_401_error() {
         init_tracing();
@@ -1196,11 +2489,613 @@ mod test {
         }
     }
 
-    #[tokio::test]
-    async fn psbt_bump_fee() {
-        init_tracing();
+    #[test]
+    fn auth_reads_cookie_file() {
+        let cookie_file = env::temp_dir().join(format!("auth_reads_cookie_file-{}", process::id()));
 
-        let (bitcoind, client) = get_bitcoind_and_client();
+        fs::write(&cookie_file, "__cookie__:deadbeef\n").unwrap();
+        let got = Auth::CookieFile(cookie_file.clone())
+            .authorization()
+            .unwrap();
+        let expected = Auth::UserPass("__cookie__".to_string(), "deadbeef".to_string())
+            .authorization()
+            .unwrap();
+        assert_eq!(got, expected);
+        assert!(got.is_sensitive());
+
+        fs::write(&cookie_file, "no-separator").unwrap();
+        let got = Auth::CookieFile(cookie_file.clone()).authorization();
+        assert!(matches!(got, Err(ClientError::CookieFile(_))));
+
+        fs::remove_file(&cookie_file).unwrap();
+        let got = Auth::CookieFile(cookie_file).authorization();
+        assert!(matches!(got, Err(ClientError::CookieFile(_))));
+
+        let got = Auth::UserPass("user".to_string(), String::new()).authorization();
+        assert_eq!(got, Err(ClientError::MissingUserPassword));
+    }
+
+    #[tokio::test]
+    async fn cookie_file_auth_reloads_on_401() {
+        init_tracing();
+
+        let (bitcoind, _) = get_bitcoind_and_client();
+        let url = bitcoind.rpc_url();
+
+        // Reading the node's cookie file directly works.
+        let client =
+            Client::new_with_cookie_file(url.clone(), &bitcoind.params.cookie_file, None, None)
+                .unwrap();
+        let got = client.network().await.unwrap();
+        assert_eq!(got, Network::Regtest);
+
+        // Simulate a node restart by starting with a stale cookie and rotating it afterwards.
+        let cookie_file = env::temp_dir().join(format!("cookie_reload-{}", process::id()));
+        fs::write(&cookie_file, "__cookie__:stale").unwrap();
+        let client = Client::new_with_cookie_file(url, &cookie_file, None, None).unwrap();
+        fs::copy(&bitcoind.params.cookie_file, &cookie_file).unwrap();
+
+        let got = client.network().await.unwrap();
+        assert_eq!(got, Network::Regtest);
+        fs::remove_file(&cookie_file).unwrap();
+    }
+
+    #[test]
+    fn client_builder() {
+        // Authentication is required.
+        let got = ClientBuilder::new("http://127.0.0.1:18443").build();
+        assert_eq!(got.unwrap_err(), ClientError::MissingUserPassword);
+
+        let got = ClientBuilder::new("http://127.0.0.1:18443")
+            .auth(Auth::UserPass("user".to_string(), "password".to_string()))
+            .proxy("not a proxy url")
+            .build();
+        assert!(matches!(got, Err(ClientError::Other(_))));
+
+        let client = Client::builder("http://127.0.0.1:18443/")
+            .auth(Auth::UserPass("user".to_string(), "password".to_string()))
+            .wallet("my wallet/1")
+            .retry_policy(ConstantBackoff::new(5, Duration::from_millis(10)))
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(5))
+            .user_agent("bitcoind-async-client-test")
+            .pool_idle_timeout(Duration::from_secs(90))
+            .pool_max_idle_per_host(4)
+            .header(
+                HeaderName::from_static("x-request-source"),
+                HeaderValue::from_static("test"),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(client.retry_policy.max_retries(), 5);
+        assert_eq!(client.retry_policy.delay(1), Duration::from_millis(10));
+        assert_eq!(
+            client.transport().endpoint(client.wallet.as_deref()),
+            "http://127.0.0.1:18443/wallet/my%20wallet%2F1"
+        );
+    }
+
+    #[test]
+    fn positional_constructors_count_attempts() {
+        let url = "http://127.0.0.1:18443".to_string();
+        let client = |max_retries| {
+            Client::new(
+                url.clone(),
+                "user".to_string(),
+                "password".to_string(),
+                max_retries,
+                None,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(client(None).retry_policy.max_retries(), 2);
+        assert_eq!(client(Some(5)).retry_policy.max_retries(), 4);
+        assert_eq!(client(Some(1)).retry_policy.max_retries(), 0);
+        assert_eq!(client(Some(0)).retry_policy.max_retries(), 0);
+    }
+
+    #[tokio::test]
+    async fn retry_policy_reports_last_error() {
+        init_tracing();
+
+        // Nothing listens on the discard port, so every attempt fails to connect.
+        let client = Client::builder("http://127.0.0.1:9")
+            .auth(Auth::UserPass("user".to_string(), "password".to_string()))
+            .retry_policy(ExponentialBackoff::new(
+                2,
+                Duration::from_millis(1),
+                Duration::from_millis(10),
+            ))
+            .build()
+            .unwrap();
+
+        let got = client.get_block_count().await.unwrap_err();
+        match got {
+            ClientError::MaxRetriesExceeded(max_retries, err) => {
+                assert_eq!(max_retries, 2);
+                assert!(matches!(*err, ClientError::Connection(_)));
+            }
+            _ => panic!("Expected MaxRetriesExceeded(2, Connection(_)), but got: {got:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_transport_retries_warmup() {
+        init_tracing();
+
+        // bitcoind is warming up for the first two requests.
+        let transport = FnTransport::new(|n, request, wallet| {
+            assert_eq!(request["method"], "getblockcount");
+            assert_eq!(wallet, Some("alice"));
+            if n < 2 {
+                return Err(ClientError::Server(
+                    -28,
+                    "Loading block index...".to_string(),
+                ));
+            }
+            Ok(json!({"result": 42, "error": null, "id": request["id"]}).to_string())
+        });
+        let client =
+            Client::from_transport(transport, ConstantBackoff::new(2, Duration::from_millis(1)))
+                .wallet("alice");
+
+        let got = client.get_block_count().await.unwrap();
+        assert_eq!(got, 42);
+        assert_eq!(client.transport().requests(), 3);
+
+        // Non-retryable errors are returned right away.
+        let transport =
+            FnTransport::new(|_, _, _| Err(ClientError::Server(-5, "Block not found".to_string())));
+        let client = Client::from_transport(transport, ConstantBackoff::default());
+        let got = client.get_block_count().await;
+        assert_eq!(
+            got,
+            Err(ClientError::Server(-5, "Block not found".to_string()))
+        );
+        assert_eq!(client.transport().requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn custom_transport_matches_batch_ids() {
+        init_tracing();
+
+        // Answer in reverse order, with an error for the second call and no answer for the
+        // fourth.
+        let transport = FnTransport::new(|_, request, _| {
+            let requests = request.as_array().unwrap();
+            let responses: Vec<Value> = requests
+                .iter()
+                .enumerate()
+                .rev()
+                .filter(|(i, _)| *i != 3)
+                .map(|(i, request)| match i {
+                    1 => json!({
+                        "result": null,
+                        "error": {"code": -8, "message": "Block height out of range"},
+                        "id": request["id"],
+                    }),
+                    _ => json!({"result": i, "error": null, "id": request["id"]}),
+                })
+                .collect();
+            Ok(Value::Array(responses).to_string())
+        });
+        let client = Client::from_transport(transport, ConstantBackoff::default());
+
+        let calls: Vec<(&str, Vec<Value>)> = (0..4)
+            .map(|height| ("getblockhash", vec![to_value(height).unwrap()]))
+            .collect();
+        let got = client.call_batch::<u64>(&calls).await.unwrap();
+        assert_eq!(got[0], Ok(0));
+        assert_eq!(
+            got[1],
+            Err(ClientError::Server(
+                -8,
+                "Block height out of range".to_string()
+            ))
+        );
+        assert_eq!(got[2], Ok(2));
+        assert!(matches!(got[3], Err(ClientError::MalformedResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn custom_transport_does_not_retry_missing_batch_ids() {
+        init_tracing();
+
+        // Answer no call, as if the responses were lost after `bitcoind` ran them.
+        let transport = FnTransport::new(|_, _, _| Ok("[]".to_string()));
+        let client =
+            Client::from_transport(transport, ConstantBackoff::new(2, Duration::from_millis(1)));
+
+        let calls = [
+            ("sendrawtransaction", vec![]),
+            ("sendrawtransaction", vec![]),
+        ];
+        let got = client.call_batch::<Txid>(&calls).await.unwrap();
+        assert!(got
+            .iter()
+            .all(|result| matches!(result, Err(ClientError::MalformedResponse(_)))));
+        assert_eq!(client.transport().requests(), 1);
+
+        // Nor if only the other calls are warming up.
+        let transport = FnTransport::new(|_, request, _| {
+            let request = &request.as_array().unwrap()[0];
+            Ok(json!([{
+                "result": null,
+                "error": {"code": -28, "message": "Loading block index..."},
+                "id": request["id"],
+            }])
+            .to_string())
+        });
+        let client =
+            Client::from_transport(transport, ConstantBackoff::new(2, Duration::from_millis(1)));
+
+        let got = client.call_batch::<Txid>(&calls).await.unwrap();
+        assert_eq!(
+            got[0],
+            Err(ClientError::Server(
+                -28,
+                "Loading block index...".to_string()
+            ))
+        );
+        assert!(matches!(got[1], Err(ClientError::MalformedResponse(_))));
+        assert_eq!(client.transport().requests(), 1);
+    }
+
+    /// Serves JSON-RPC over HTTP on a local port, answering the `n`-th request with
+    /// `bitcoind(n, request)`, and returns the URL and the number of requests served.
+    fn serve_http(
+        bitcoind: impl Fn(usize, &Value) -> Value + Send + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let served = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = std::io::BufReader::new(stream.unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    std::io::BufRead::read_line(&mut reader, &mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                std::io::Read::read_exact(&mut reader, &mut body).unwrap();
+                let request = serde_json::from_slice(&body).unwrap();
+                let n = served.fetch_add(1, Ordering::AcqRel);
+                let response = bitcoind(n, &request).to_string();
+                // `bitcoind` answers JSON-RPC 1.0 requests that fail with an HTTP error status.
+                let status = match response.contains(r#""error":null"#) || request.is_array() {
+                    true => "200 OK",
+                    false => "500 Internal Server Error",
+                };
+                let mut stream = reader.into_inner();
+                std::io::Write::write_all(
+                    &mut stream,
+                    format!(
+                        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                        response.len()
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn retries_rpc_errors_in_response_body() {
+        init_tracing();
+
+        // bitcoind is warming up for the first two requests, and only says so in the body.
+        let bitcoind = |n: usize, request: &Value| {
+            let response = |request: &Value| match n {
+                0 | 1 => json!({
+                    "result": null,
+                    "error": {"code": -28, "message": "Loading block index..."},
+                    "id": request["id"],
+                }),
+                _ => json!({"result": 42, "error": null, "id": request["id"]}),
+            };
+            match request.as_array() {
+                Some(requests) => Value::Array(requests.iter().map(response).collect()),
+                None => response(request),
+            }
+        };
+        let client = |url: String, max_retries: u32| {
+            Client::builder(url)
+                .auth(Auth::UserPass("user".to_string(), "password".to_string()))
+                .retry_policy(ConstantBackoff::new(max_retries, Duration::from_millis(1)))
+                .build()
+                .unwrap()
+        };
+
+        let (url, requests) = serve_http(bitcoind);
+        assert_eq!(client(url, 2).get_block_count().await.unwrap(), 42);
+        assert_eq!(requests.load(Ordering::Acquire), 3);
+
+        let (url, requests) = serve_http(bitcoind);
+        let calls = [("getblockcount", vec![]), ("getblockcount", vec![])];
+        let got = client(url, 2).call_batch::<u64>(&calls).await.unwrap();
+        assert_eq!(got, vec![Ok(42), Ok(42)]);
+        assert_eq!(requests.load(Ordering::Acquire), 3);
+
+        // Gives up with the last error.
+        let (url, _) = serve_http(bitcoind);
+        assert_eq!(
+            client(url, 1).get_block_count().await,
+            Err(ClientError::MaxRetriesExceeded(
+                1,
+                Box::new(ClientError::Server(
+                    -28,
+                    "Loading block index...".to_string()
+                ))
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn block_ranges_batch_through_reader() {
+        init_tracing();
+
+        let genesis = bitcoin::constants::genesis_block(Network::Regtest);
+        let block = genesis.clone();
+        let transport = FnTransport::new(move |_, request, _| {
+            let responses: Vec<Value> = request
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|request| {
+                    let result = match request["method"].as_str().unwrap() {
+                        "getblockhash" => json!(block.block_hash()),
+                        "getblockheader" => json!(serialize_hex(&block.header)),
+                        "getblock" => json!(serialize_hex(&block)),
+                        method => panic!("unexpected method {method}"),
+                    };
+                    json!({"result": result, "error": null, "id": request["id"]})
+                })
+                .collect();
+            Ok(Value::Array(responses).to_string())
+        });
+        let client = Client::from_transport(transport, ConstantBackoff::default());
+
+        // Code generic over `Reader` gets the batched implementations.
+        async fn headers_and_blocks(
+            reader: &(impl Reader + Sync),
+            heights: &[u64],
+        ) -> (Vec<Header>, Vec<Block>) {
+            (
+                reader.get_block_headers_at(heights).await.unwrap(),
+                reader.get_blocks_at(heights).await.unwrap(),
+            )
+        }
+        let (headers, blocks) = headers_and_blocks(&client, &[0, 0, 0]).await;
+        assert_eq!(headers, vec![genesis.header; 3]);
+        assert_eq!(blocks, vec![genesis; 3]);
+        assert_eq!(client.transport().requests(), 4);
+    }
+
+    #[tokio::test]
+    async fn block_stats_range_batches() {
+        init_tracing();
+
+        let transport = FnTransport::new(|_, request, _| {
+            let responses: Vec<Value> = request
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|request| {
+                    assert_eq!(request["method"], "getblockstats");
+                    assert_eq!(request["params"][1], json!(["height", "totalfee"]));
+                    let height = &request["params"][0];
+                    match height.as_u64().unwrap() {
+                        120 => json!({
+                            "result": null,
+                            "error": {"code": -8, "message": "Target block height 120 after current tip 119"},
+                            "id": request["id"],
+                        }),
+                        _ => json!({
+                            "result": {"height": height, "totalfee": 1000},
+                            "error": null,
+                            "id": request["id"],
+                        }),
+                    }
+                })
+                .collect();
+            Ok(Value::Array(responses).to_string())
+        });
+        let client = Client::from_transport(transport, ConstantBackoff::default());
+
+        let fields = [BlockStatsField::Height, BlockStatsField::TotalFee];
+        let mut range = client.get_block_stats_range(10..=120, Some(&fields));
+        let mut heights = Vec::new();
+        while let Some(stats) = range.next().await {
+            match stats {
+                Ok(stats) => {
+                    assert_eq!(stats.total_fee, Some(Amount::from_sat(1000)));
+                    heights.push(stats.height.unwrap());
+                }
+                Err(err) => {
+                    assert_eq!(err.rpc_error_code(), Some(RpcErrorCode::InvalidParameter))
+                }
+            }
+        }
+        assert_eq!(heights, (10..120).collect::<Vec<_>>());
+        assert_eq!(client.transport().requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn regtest_control_refuses_other_networks() {
+        init_tracing();
+
+        let transport = FnTransport::new(|_, request, _| {
+            assert_eq!(request["method"], "getblockchaininfo");
+            let result = json!({
+                "chain": "main",
+                "blocks": 0,
+                "headers": 0,
+                "bestblockhash": "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+                "difficulty": 1.0,
+                "mediantime": 1231006505,
+                "verificationprogress": 0.0,
+                "initialblockdownload": true,
+                "chainwork": "0000000000000000000000000000000000000000000000000000000100010001",
+                "size_on_disk": 293,
+                "pruned": false,
+            });
+            Ok(json!({"result": result, "error": null, "id": request["id"]}).to_string())
+        });
+        let client = Client::from_transport(transport, ConstantBackoff::default());
+
+        assert_eq!(client.network().await, Ok(Network::Bitcoin));
+        let hash = BlockHash::all_zeros();
+        assert_eq!(
+            client.invalidate_block(&hash).await,
+            Err(ClientError::NotRegtest(Network::Bitcoin))
+        );
+        assert_eq!(
+            client.set_mock_time(1).await,
+            Err(ClientError::NotRegtest(Network::Bitcoin))
+        );
+        // Only `getblockchaininfo` was called.
+        assert_eq!(client.transport().requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn null_results() {
+        init_tracing();
+
+        let transport = FnTransport::new(|_, request, _| {
+            let response =
+                |request: &Value| json!({"result": null, "error": null, "id": request["id"]});
+            Ok(match request.as_array() {
+                Some(requests) => Value::Array(requests.iter().map(response).collect()),
+                None => response(request),
+            }
+            .to_string())
+        });
+        let client = Client::from_transport(transport, ConstantBackoff::default());
+
+        // Methods without a result and optional results accept `null`.
+        client
+            .add_node("127.0.0.1:18444", AddNodeCommand::Add)
+            .await
+            .unwrap();
+        assert_eq!(client.scan_tx_out_set_status().await.unwrap(), None);
+
+        // Other results do not.
+        assert_eq!(
+            client.get_block_count().await,
+            Err(ClientError::Other("Empty data received".to_string()))
+        );
+        let calls = [("getblockcount", Vec::new())];
+        assert_eq!(
+            client.call_batch::<u64>(&calls).await.unwrap(),
+            vec![Err(ClientError::Other("Empty data received".to_string()))]
+        );
+        assert_eq!(
+            client.call_batch::<Option<u64>>(&calls).await.unwrap(),
+            vec![Ok(None)]
+        );
+    }
+
+    #[tokio::test]
+    async fn client_builder_transport_options() {
+        init_tracing();
+
+        let (bitcoind, _) = get_bitcoind_and_client();
+
+        let client = Client::builder(bitcoind.rpc_url())
+            .auth(Auth::CookieFile(bitcoind.params.cookie_file.clone()))
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(5))
+            .user_agent("bitcoind-async-client-test")
+            .pool_max_idle_per_host(1)
+            .header(
+                HeaderName::from_static("x-request-source"),
+                HeaderValue::from_static("test"),
+            )
+            .build()
+            .unwrap();
+        let got = client.network().await.unwrap();
+        assert_eq!(got, Network::Regtest);
+    }
+
+    #[tokio::test]
+    async fn wallet_scoped_calls() {
+        init_tracing();
+
+        let (bitcoind, client) = get_bitcoind_and_client();
+        mine_blocks(&bitcoind, 101, None).unwrap();
+
+        // Importing descriptors creates the named wallet, leaving two wallets loaded.
+        let descriptor_string = "tr([e61b318f/20000'/20']tprv8ZgxMBicQKsPd4arFr7sKjSnKFDVMR2JHw9Y8L9nXN4kiok4u28LpHijEudH3mMYoL4pM5UL9Bgdz2M4Cy8EzfErmU9m86ZTw6hCzvFeTg7/101/*)#2plamwqs".to_owned();
+        let list_descriptors = vec![ImportDescriptor {
+            desc: descriptor_string,
+            active: Some(true),
+            timestamp: "now".to_owned(),
+        }];
+        let got = client
+            .import_descriptors(list_descriptors, "strata".to_owned())
+            .await
+            .unwrap();
+        assert_eq!(got, vec![ImportDescriptorResult { success: true }]);
+        let mut wallets = client.list_wallets().await.unwrap();
+        wallets.sort();
+        assert_eq!(wallets, vec!["default".to_string(), "strata".to_string()]);
+
+        // bitcoind can't tell which wallet the bare client means.
+        let got = client.get_new_address().await;
+        assert!(matches!(got, Err(ClientError::Server(-19, _))));
+
+        // Wallet handles can.
+        let default_wallet = client.wallet("default");
+        let strata_wallet = client.wallet("strata");
+        let address = default_wallet.get_new_address().await.unwrap();
+        let got = default_wallet.get_address_info(&address).await.unwrap();
+        assert!(got.is_mine.unwrap());
+        let got = strata_wallet.get_address_info(&address).await.unwrap();
+        assert!(!got.is_mine.unwrap());
+
+        // The descriptor was imported into the named wallet only.
+        // NOTE: bitcoind may normalize the hardened derivation markers, so match on the fingerprint.
+        let got = strata_wallet
+            .call::<ListDescriptors>("listdescriptors", &[])
+            .await
+            .unwrap();
+        assert!(got
+            .descriptors
+            .iter()
+            .any(|d| d.desc.contains("[e61b318f/")));
+        let got = default_wallet
+            .call::<ListDescriptors>("listdescriptors", &[])
+            .await
+            .unwrap();
+        assert!(!got
+            .descriptors
+            .iter()
+            .any(|d| d.desc.contains("[e61b318f/")));
+
+        // Handles share the request ID counter with their parent.
+        let id = client.next_id();
+        assert_eq!(strata_wallet.next_id(), id + 1);
+    }
+
+    #[tokio::test]
+    async fn psbt_bump_fee() {
+        init_tracing();
+
+        let (bitcoind, client) = get_bitcoind_and_client();
 
         // Mine blocks to have funds
         mine_blocks(&bitcoind, 101, None).unwrap();
@@ -1271,4 +3166,282 @@ mod test {
             "Bumped transaction should be accepted in mempool"
         );
     }
+
+    #[tokio::test]
+    async fn network_control() {
+        init_tracing();
+
+        let (_bitcoind, client) = get_bitcoind_and_client();
+
+        // get_network_info
+        let info = client.get_network_info().await.unwrap();
+        assert!(info.server_version().0 > 28);
+        assert_eq!(info.user_agent()[0].name, "Satoshi");
+        assert!(info.network_active);
+        assert_eq!(info.relay_fee, FeeRate::BROADCAST_MIN);
+
+        // get_peer_info, get_connection_count and get_net_totals
+        assert!(client.get_peer_info().await.unwrap().is_empty());
+        assert_eq!(client.get_connection_count().await.unwrap(), 0);
+        assert_eq!(client.get_net_totals().await.unwrap().total_bytes_sent, 0);
+
+        // add_node and disconnect_node
+        client
+            .add_node("127.0.0.1:1", AddNodeCommand::Add)
+            .await
+            .unwrap();
+        client
+            .add_node("127.0.0.1:1", AddNodeCommand::Remove)
+            .await
+            .unwrap();
+        let got = client
+            .disconnect_node(&DisconnectTarget::Id(42))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            got.rpc_error_code(),
+            Some(RpcErrorCode::ClientNodeNotConnected)
+        );
+
+        // set_ban and list_banned
+        client
+            .set_ban("192.0.2.0/24", SetBanCommand::Add, Some(3600))
+            .await
+            .unwrap();
+        let banned = client.list_banned().await.unwrap();
+        assert_eq!(banned.len(), 1);
+        assert_eq!(banned[0].address, "192.0.2.0/24");
+        assert_eq!(banned[0].ban_duration, 3600);
+        client
+            .set_ban("192.0.2.0/24", SetBanCommand::Remove, None)
+            .await
+            .unwrap();
+        assert!(client.list_banned().await.unwrap().is_empty());
+
+        // set_network_active
+        assert!(!client.set_network_active(false).await.unwrap());
+        assert!(!client.get_network_info().await.unwrap().network_active);
+        assert!(client.set_network_active(true).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn mining() {
+        init_tracing();
+
+        let (bitcoind, client) = get_bitcoind_and_client();
+        mine_blocks(&bitcoind, 101, None).unwrap();
+
+        // get_block_template
+        let template = client
+            .get_block_template(&[TemplateRule::Segwit])
+            .await
+            .unwrap();
+        assert_eq!(template.height, 102);
+        assert_eq!(template.coinbase_value, COINBASE_AMOUNT);
+        assert!(template.transactions.is_empty());
+
+        // submit_header with too little work
+        let address = client.get_new_address().await.unwrap();
+        let mut block = template
+            .block(address.script_pubkey(), b"bitcoind-async-client")
+            .unwrap();
+        while block.header.validate_pow(template.target).is_ok() {
+            block.header.nonce += 1;
+        }
+        let got = client.submit_header(&block.header).await.unwrap();
+        assert_eq!(got, Some(SubmitBlockReason::HighHash));
+
+        // submit_block
+        while block.header.validate_pow(template.target).is_err() {
+            block.header.nonce += 1;
+        }
+        assert_eq!(client.submit_block(&block).await.unwrap(), None);
+        assert_eq!(
+            client.submit_block(&block).await.unwrap(),
+            Some(SubmitBlockReason::Duplicate)
+        );
+        assert_eq!(client.submit_header(&block.header).await.unwrap(), None);
+
+        // get_mining_info and get_network_hashps
+        let info = client.get_mining_info().await.unwrap();
+        assert_eq!(info.blocks, 102);
+        assert_eq!(info.chain, "regtest");
+        assert!(client.get_network_hashps(None, None).await.unwrap() > 0.0);
+    }
+
+    #[tokio::test]
+    async fn regtest_control() {
+        init_tracing();
+
+        let (_bitcoind, client) = get_bitcoind_and_client();
+        let address = client.get_new_address().await.unwrap();
+
+        // generate_to_address and generate_to_descriptor
+        let hashes = client.generate_to_address(101, &address).await.unwrap();
+        assert_eq!(hashes.len(), 101);
+        let hashes = client
+            .generate_to_descriptor(1, &format!("addr({address})"))
+            .await
+            .unwrap();
+        assert_eq!(client.get_block_count().await.unwrap(), 102);
+
+        // generate_block with a chosen transaction
+        let tx = client
+            .call::<String>(
+                "sendtoaddress",
+                &[to_value(address.to_string()).unwrap(), to_value(1).unwrap()],
+            )
+            .await
+            .unwrap()
+            .parse::<Txid>()
+            .unwrap();
+        let tx = client.get_transaction(&tx).await.unwrap().hex;
+        let hash = client
+            .generate_block(&address, std::slice::from_ref(&tx))
+            .await
+            .unwrap();
+        let block = client.get_block(&hash).await.unwrap();
+        assert_eq!(block.txdata[1], tx);
+
+        // invalidate_block and reconsider_block
+        client.invalidate_block(&hash).await.unwrap();
+        assert_eq!(client.get_block_count().await.unwrap(), 102);
+        assert_eq!(
+            client.get_block_hash(102).await.unwrap(),
+            *hashes.last().unwrap()
+        );
+        client.reconsider_block(&hash).await.unwrap();
+        assert_eq!(client.get_block_count().await.unwrap(), 103);
+
+        // set_mock_time
+        let time = block.header.time as u64 + 3600;
+        client.set_mock_time(time).await.unwrap();
+        let hash = client.generate_to_address(1, &address).await.unwrap()[0];
+        let header = client.get_block_header(&hash).await.unwrap();
+        assert_eq!(header.time as u64, time);
+        client.set_mock_time(0).await.unwrap();
+    }
+
+    #[tokio::test()]
+    async fn psbt_workflow() {
+        init_tracing();
+
+        let (bitcoind, client) = get_bitcoind_and_client();
+        mine_blocks(&bitcoind, 102, None).unwrap();
+        let utxos = client
+            .list_unspent(None, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(utxos.len(), 2);
+        let address = client.get_new_address().await.unwrap();
+        let outputs = [CreateRawTransactionOutput::AddressAmount {
+            address: address.to_string(),
+            amount: 49.0,
+        }];
+
+        // create_psbt and join_psbts
+        let mut psbts = Vec::new();
+        for utxo in &utxos {
+            let inputs = [CreateRawTransactionInput {
+                txid: utxo.txid.to_string(),
+                vout: utxo.vout,
+            }];
+            let psbt = client
+                .create_psbt(&inputs, &outputs, None, Some(true))
+                .await
+                .unwrap();
+            assert_eq!(
+                psbt.unsigned_tx.input[0].previous_output,
+                OutPoint::new(utxo.txid, utxo.vout)
+            );
+            assert_eq!(
+                psbt.unsigned_tx.output[0].script_pubkey,
+                address.script_pubkey()
+            );
+            psbts.push(psbt);
+        }
+        let joined = client.join_psbts(&psbts).await.unwrap();
+        assert_eq!(joined.unsigned_tx.input.len(), 2);
+        assert_eq!(joined.unsigned_tx.output.len(), 2);
+        let psbt = psbts.swap_remove(0);
+
+        // utxo_update_psbt
+        let updated = client.utxo_update_psbt(&psbt, None).await.unwrap();
+        let witness_utxo = updated.inputs[0].witness_utxo.as_ref().unwrap();
+        assert_eq!(witness_utxo.value, COINBASE_AMOUNT);
+        let descriptors = [ScanObject::from(format!(
+            "addr({})",
+            utxos[0].address.clone().assume_checked()
+        ))];
+        let got = client
+            .utxo_update_psbt(&psbt, Some(&descriptors))
+            .await
+            .unwrap();
+        assert_eq!(got.inputs[0].witness_utxo, updated.inputs[0].witness_utxo);
+
+        // wallet_process_psbt and combine_psbt
+        let signed = client
+            .wallet_process_psbt(&psbt, Some(true), None, None)
+            .await
+            .unwrap();
+        assert!(signed.complete);
+        let combined = client
+            .combine_psbt(&[updated, signed.psbt.unwrap()])
+            .await
+            .unwrap();
+        assert!(combined.inputs[0].final_script_witness.is_some());
+
+        // finalize_psbt
+        let finalized = client.finalize_psbt(&combined, Some(false)).await.unwrap();
+        assert!(finalized.complete);
+        assert!(finalized.psbt.is_some());
+        assert!(finalized.hex.is_none());
+        let extracted = client.finalize_psbt(&combined, None).await.unwrap();
+        assert!(extracted.complete);
+        assert!(extracted.psbt.is_none());
+        let tx = extracted.hex.unwrap();
+        assert_eq!(tx.compute_txid(), psbt.unsigned_tx.compute_txid());
+
+        // convert_to_psbt refuses signed transactions unless told to drop the signatures
+        assert!(client.convert_to_psbt(&tx, None).await.is_err());
+        let converted = client.convert_to_psbt(&tx, Some(true)).await.unwrap();
+        assert_eq!(converted.unsigned_tx, psbt.unsigned_tx);
+    }
+
+    #[tokio::test]
+    async fn node_control() {
+        init_tracing();
+
+        let (bitcoind, client) = get_bitcoind_and_client();
+        mine_blocks(&bitcoind, 1, None).unwrap();
+
+        // uptime and get_rpc_info
+        assert!(client.uptime().await.unwrap() < 3600);
+        let info = client.get_rpc_info().await.unwrap();
+        assert_eq!(info.active_commands[0].method, "getrpcinfo");
+        assert!(info.log_path.ends_with("debug.log"));
+
+        // logging
+        let got = client.logging(&["mempool"], &[]).await.unwrap();
+        assert!(got["mempool"]);
+        let got = client.logging(&[], &["mempool"]).await.unwrap();
+        assert!(!got["mempool"]);
+
+        // prune_blockchain needs -prune
+        let got = client.prune_blockchain(1).await.unwrap_err();
+        assert_eq!(got.rpc_error_code(), Some(RpcErrorCode::MiscError));
+
+        // get_index_info, without any index enabled
+        assert!(client.get_index_info(None).await.unwrap().is_empty());
+
+        // get_deployment_info
+        let got = client.get_deployment_info(None).await.unwrap();
+        assert_eq!(got.height, 1);
+        assert!(got.deployments["segwit"].active);
+        let tip = client.get_block_hash(1).await.unwrap();
+        assert_eq!(client.get_deployment_info(Some(&tip)).await.unwrap(), got);
+
+        // stop
+        client.stop().await.unwrap();
+    }
 }
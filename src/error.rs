@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Error as SerdeJsonError;
 use thiserror::Error;
 
+use crate::types::RejectReason;
+
 /// The error type for errors produced in this library.
 #[derive(Error, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ClientError {
@@ -13,6 +15,10 @@ pub enum ClientError {
     #[error("Missing username or password")]
     MissingUserPassword,
 
+    /// Could not read the `bitcoind` cookie file
+    #[error("Could not read cookie file: {0}")]
+    CookieFile(String),
+
     /// RPC server returned an error
     ///
     /// # Note
@@ -21,6 +27,8 @@ pub enum ClientError {
     /// Check
     /// <https://github.com/bitcoin/bitcoin/blob/96b0a8f858ab24f3672360b8c830553b963de726/src/rpc/protocol.h#L24>
     /// and good luck!
+    ///
+    /// [`ClientError::rpc_error_code`] returns the code as a typed [`RpcErrorCode`].
     #[error("RPC server returned error '{1}' (code {0})")]
     Server(i32, String),
 
@@ -61,8 +69,10 @@ pub enum ClientError {
     ReqBuilder(String),
 
     /// Maximum retries exceeded, not retryable
-    #[error("Max retries {0} exceeded")]
-    MaxRetriesExceeded(u8),
+    ///
+    /// Holds the maximum number of retries and the error of the last attempt.
+    #[error("Max retries {0} exceeded: {1}")]
+    MaxRetriesExceeded(u32, Box<ClientError>),
 
     /// General request error, retry might help
     #[error("Could not create request: {0}")]
@@ -72,6 +82,11 @@ pub enum ClientError {
     #[error("Network address: {0}")]
     WrongNetworkAddress(Network),
 
+    /// A regtest-only call was refused because `bitcoind` runs on another network, not
+    /// retryable
+    #[error("Refusing regtest-only call on network: {0}")]
+    NotRegtest(Network),
+
     /// Server version is unexpected or incompatible
     #[error(transparent)]
     UnexpectedServerVersion(#[from] UnexpectedServerVersionError),
@@ -80,6 +95,28 @@ pub enum ClientError {
     #[error(transparent)]
     Sign(#[from] SignRawTransactionWithWalletError),
 
+    /// A block filter does not match its filter header, not retryable
+    ///
+    /// Holds the height of the block. Either `bitcoind` served a bad filter or the chain
+    /// reorganized while scanning.
+    #[error("Block filter does not match its filter header at height {0}")]
+    FilterHeaderMismatch(u64),
+
+    /// A transaction inclusion proof does not verify, not retryable
+    #[error("Invalid transaction inclusion proof: {0}")]
+    InvalidTxOutProof(String),
+
+    /// Data for the coinbase `scriptSig` of a block template would take it over the 100 byte
+    /// consensus limit, not retryable
+    ///
+    /// Holds the length of the data.
+    #[error("Coinbase data of {0} bytes does not fit in the coinbase scriptSig")]
+    CoinbaseDataTooLong(usize),
+
+    /// Request does not match the recording being replayed, not retryable
+    #[error("Request does not match the recording: {0}")]
+    Replay(String),
+
     /// Could not get a [`Xpriv`](bitcoin::bip32::Xpriv) from the wallet
     #[error("Could not get xpriv from wallet")]
     Xpriv,
@@ -90,16 +127,330 @@ pub enum ClientError {
 }
 
 impl ClientError {
+    /// Creates a [`ClientError::Server`] from a typed [`RpcErrorCode`].
+    pub fn rpc(code: RpcErrorCode, message: impl Into<String>) -> Self {
+        Self::Server(code.code(), message.into())
+    }
+
+    /// Returns the [`RpcErrorCode`] of an error returned by `bitcoind`.
+    ///
+    /// Looks through [`ClientError::MaxRetriesExceeded`] at the error of the last attempt.
+    pub fn rpc_error_code(&self) -> Option<RpcErrorCode> {
+        match self {
+            Self::Server(code, _) => Some(RpcErrorCode::from(*code)),
+            Self::MaxRetriesExceeded(_, last) => last.rpc_error_code(),
+            _ => None,
+        }
+    }
+
+    /// Whether retrying the request might succeed.
+    ///
+    /// True for connection errors, timeouts, malformed responses, `bitcoind` warming up
+    /// ([`RpcErrorCode::InWarmup`]) and a full RPC work queue (HTTP 503).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Connection(_) | Self::Timeout | Self::Request(_) | Self::MalformedResponse(_) => {
+                true
+            }
+            Self::Server(..) => self.rpc_error_code() == Some(RpcErrorCode::InWarmup),
+            // "Work queue depth exceeded"
+            Self::Status(status, _) => *status == 503,
+            _ => false,
+        }
+    }
+
+    /// Whether `bitcoind` reported a wallet error, such as insufficient funds or a wallet that
+    /// is not loaded.
+    pub fn is_wallet_error(&self) -> bool {
+        self.rpc_error_code()
+            .is_some_and(RpcErrorCode::is_wallet_error)
+    }
+
+    /// Whether `bitcoind` rejected a transaction or block while verifying it.
+    pub fn is_verify_error(&self) -> bool {
+        self.rpc_error_code()
+            .is_some_and(RpcErrorCode::is_verify_error)
+    }
+
+    /// Whether `bitcoind` does not know the requested transaction.
     pub fn is_tx_not_found(&self) -> bool {
-        matches!(self, Self::Server(-5, _))
+        match self {
+            Self::Server(_, message) => {
+                self.rpc_error_code() == Some(RpcErrorCode::InvalidAddressOrKey)
+                    && message.to_lowercase().contains("transaction")
+            }
+            _ => false,
+        }
     }
 
+    /// Whether `bitcoind` does not know the requested block, by hash or by height.
     pub fn is_block_not_found(&self) -> bool {
-        matches!(self, Self::Server(-5, _))
+        match (self, self.rpc_error_code()) {
+            (Self::Server(_, message), Some(RpcErrorCode::InvalidAddressOrKey)) => {
+                message.starts_with("Block not found")
+            }
+            (Self::Server(_, message), Some(RpcErrorCode::InvalidParameter)) => {
+                message.starts_with("Block height out of range")
+            }
+            _ => false,
+        }
     }
 
+    /// Parses why `bitcoind` rejected a transaction sent with `sendrawtransaction`.
+    ///
+    /// Returns `None` unless the error is [`RpcErrorCode::VerifyRejected`], or
+    /// [`RpcErrorCode::VerifyError`] which `bitcoind` uses for missing inputs and excessive fees.
+    pub fn reject_reason(&self) -> Option<RejectReason> {
+        match (self, self.rpc_error_code()?) {
+            (
+                Self::Server(_, message),
+                RpcErrorCode::VerifyRejected | RpcErrorCode::VerifyError,
+            ) => Some(RejectReason::from(message.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Whether `bitcoind` rejected a transaction because of missing, spent or invalid inputs.
+    ///
+    /// Based on the [`reject_reason`](Self::reject_reason), so fee and policy rejections, such
+    /// as [`RejectReason::MinRelayFeeNotMet`], do not count.
     pub fn is_missing_or_invalid_input(&self) -> bool {
-        matches!(self, Self::Server(-26, _)) || matches!(self, Self::Server(-25, _))
+        match self.reject_reason() {
+            Some(
+                RejectReason::MissingInputs
+                | RejectReason::PrematureCoinbaseSpend
+                | RejectReason::MempoolConflict
+                | RejectReason::ScriptVerifyFailed,
+            ) => true,
+            Some(RejectReason::NonStandard(code)) => code == "bad-txns-nonstandard-inputs",
+            _ => false,
+        }
+    }
+}
+
+/// Error codes of the `bitcoind` RPC server, as defined in Bitcoin Core's
+/// [`protocol.h`](https://github.com/bitcoin/bitcoin/blob/master/src/rpc/protocol.h).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "i32", into = "i32")]
+pub enum RpcErrorCode {
+    /// `RPC_INVALID_REQUEST`: the request is not a valid JSON-RPC request.
+    InvalidRequest,
+    /// `RPC_METHOD_NOT_FOUND`: the method does not exist, or is disabled.
+    MethodNotFound,
+    /// `RPC_INVALID_PARAMS`: wrong number or kind of parameters.
+    InvalidParams,
+    /// `RPC_INTERNAL_ERROR`: internal JSON-RPC error.
+    InternalError,
+    /// `RPC_PARSE_ERROR`: the request is not valid JSON.
+    ParseError,
+
+    /// `RPC_MISC_ERROR`: `std::exception` thrown in command handling.
+    MiscError,
+    /// `RPC_TYPE_ERROR`: unexpected type was passed as parameter.
+    TypeError,
+    /// `RPC_INVALID_ADDRESS_OR_KEY`: invalid address or key, also used for unknown blocks and
+    /// transactions.
+    InvalidAddressOrKey,
+    /// `RPC_OUT_OF_MEMORY`: ran out of memory during operation.
+    OutOfMemory,
+    /// `RPC_INVALID_PARAMETER`: invalid, missing or duplicate parameter.
+    InvalidParameter,
+    /// `RPC_DATABASE_ERROR`: database error.
+    DatabaseError,
+    /// `RPC_DESERIALIZATION_ERROR`: error parsing or validating structure in raw format.
+    DeserializationError,
+    /// `RPC_VERIFY_ERROR`: general error during transaction or block submission.
+    VerifyError,
+    /// `RPC_VERIFY_REJECTED`: transaction or block was rejected by network rules.
+    VerifyRejected,
+    /// `RPC_VERIFY_ALREADY_IN_UTXO_SET`: transaction already in the UTXO set.
+    VerifyAlreadyInUtxoSet,
+    /// `RPC_IN_WARMUP`: client still warming up.
+    InWarmup,
+    /// `RPC_METHOD_DEPRECATED`: RPC method is deprecated.
+    MethodDeprecated,
+    /// `RPC_FORBIDDEN_BY_SAFE_MODE`: no longer used by Bitcoin Core.
+    ForbiddenBySafeMode,
+
+    /// `RPC_CLIENT_NOT_CONNECTED`: Bitcoin is not connected.
+    ClientNotConnected,
+    /// `RPC_CLIENT_IN_INITIAL_DOWNLOAD`: still downloading initial blocks.
+    ClientInInitialDownload,
+    /// `RPC_CLIENT_NODE_ALREADY_ADDED`: node is already added.
+    ClientNodeAlreadyAdded,
+    /// `RPC_CLIENT_NODE_NOT_ADDED`: node has not been added before.
+    ClientNodeNotAdded,
+    /// `RPC_CLIENT_NODE_NOT_CONNECTED`: node to disconnect not found in connected nodes.
+    ClientNodeNotConnected,
+    /// `RPC_CLIENT_INVALID_IP_OR_SUBNET`: invalid IP or subnet.
+    ClientInvalidIpOrSubnet,
+    /// `RPC_CLIENT_P2P_DISABLED`: no valid connection manager instance found.
+    ClientP2pDisabled,
+    /// `RPC_CLIENT_MEMPOOL_DISABLED`: no mempool instance found.
+    ClientMempoolDisabled,
+    /// `RPC_CLIENT_NODE_CAPACITY_REACHED`: max number of outbound or block-relay connections
+    /// already open.
+    ClientNodeCapacityReached,
+
+    /// `RPC_WALLET_ERROR`: unspecified problem with the wallet.
+    WalletError,
+    /// `RPC_WALLET_INSUFFICIENT_FUNDS`: not enough funds in wallet or account.
+    WalletInsufficientFunds,
+    /// `RPC_WALLET_INVALID_LABEL_NAME`: invalid label name.
+    WalletInvalidLabelName,
+    /// `RPC_WALLET_KEYPOOL_RAN_OUT`: keypool ran out, call `keypoolrefill` first.
+    WalletKeypoolRanOut,
+    /// `RPC_WALLET_UNLOCK_NEEDED`: enter the wallet passphrase with `walletpassphrase` first.
+    WalletUnlockNeeded,
+    /// `RPC_WALLET_PASSPHRASE_INCORRECT`: the wallet passphrase entered was incorrect.
+    WalletPassphraseIncorrect,
+    /// `RPC_WALLET_WRONG_ENC_STATE`: command given in wrong wallet encryption state.
+    WalletWrongEncState,
+    /// `RPC_WALLET_ENCRYPTION_FAILED`: failed to encrypt the wallet.
+    WalletEncryptionFailed,
+    /// `RPC_WALLET_ALREADY_UNLOCKED`: wallet is already unlocked.
+    WalletAlreadyUnlocked,
+    /// `RPC_WALLET_NOT_FOUND`: invalid wallet specified.
+    WalletNotFound,
+    /// `RPC_WALLET_NOT_SPECIFIED`: no wallet specified, with more than one wallet loaded.
+    WalletNotSpecified,
+    /// `RPC_WALLET_ALREADY_LOADED`: this same wallet is already loaded.
+    WalletAlreadyLoaded,
+    /// `RPC_WALLET_ALREADY_EXISTS`: there is already a wallet with the same name.
+    WalletAlreadyExists,
+
+    /// A code not known to this library.
+    Unknown(i32),
+}
+
+impl RpcErrorCode {
+    /// Returns the numeric code.
+    pub fn code(self) -> i32 {
+        match self {
+            Self::InvalidRequest => -32600,
+            Self::MethodNotFound => -32601,
+            Self::InvalidParams => -32602,
+            Self::InternalError => -32603,
+            Self::ParseError => -32700,
+            Self::MiscError => -1,
+            Self::ForbiddenBySafeMode => -2,
+            Self::TypeError => -3,
+            Self::InvalidAddressOrKey => -5,
+            Self::OutOfMemory => -7,
+            Self::InvalidParameter => -8,
+            Self::DatabaseError => -20,
+            Self::DeserializationError => -22,
+            Self::VerifyError => -25,
+            Self::VerifyRejected => -26,
+            Self::VerifyAlreadyInUtxoSet => -27,
+            Self::InWarmup => -28,
+            Self::MethodDeprecated => -32,
+            Self::ClientNotConnected => -9,
+            Self::ClientInInitialDownload => -10,
+            Self::ClientNodeAlreadyAdded => -23,
+            Self::ClientNodeNotAdded => -24,
+            Self::ClientNodeNotConnected => -29,
+            Self::ClientInvalidIpOrSubnet => -30,
+            Self::ClientP2pDisabled => -31,
+            Self::ClientMempoolDisabled => -33,
+            Self::ClientNodeCapacityReached => -34,
+            Self::WalletError => -4,
+            Self::WalletInsufficientFunds => -6,
+            Self::WalletInvalidLabelName => -11,
+            Self::WalletKeypoolRanOut => -12,
+            Self::WalletUnlockNeeded => -13,
+            Self::WalletPassphraseIncorrect => -14,
+            Self::WalletWrongEncState => -15,
+            Self::WalletEncryptionFailed => -16,
+            Self::WalletAlreadyUnlocked => -17,
+            Self::WalletNotFound => -18,
+            Self::WalletNotSpecified => -19,
+            Self::WalletAlreadyLoaded => -35,
+            Self::WalletAlreadyExists => -36,
+            Self::Unknown(code) => code,
+        }
+    }
+
+    /// Whether this is one of the `RPC_WALLET_*` codes.
+    pub fn is_wallet_error(self) -> bool {
+        matches!(
+            self,
+            Self::WalletError
+                | Self::WalletInsufficientFunds
+                | Self::WalletInvalidLabelName
+                | Self::WalletKeypoolRanOut
+                | Self::WalletUnlockNeeded
+                | Self::WalletPassphraseIncorrect
+                | Self::WalletWrongEncState
+                | Self::WalletEncryptionFailed
+                | Self::WalletAlreadyUnlocked
+                | Self::WalletNotFound
+                | Self::WalletNotSpecified
+                | Self::WalletAlreadyLoaded
+                | Self::WalletAlreadyExists
+        )
+    }
+
+    /// Whether this is one of the `RPC_VERIFY_*` codes.
+    pub fn is_verify_error(self) -> bool {
+        matches!(
+            self,
+            Self::VerifyError | Self::VerifyRejected | Self::VerifyAlreadyInUtxoSet
+        )
+    }
+}
+
+impl From<i32> for RpcErrorCode {
+    fn from(code: i32) -> Self {
+        match code {
+            -32600 => Self::InvalidRequest,
+            -32601 => Self::MethodNotFound,
+            -32602 => Self::InvalidParams,
+            -32603 => Self::InternalError,
+            -32700 => Self::ParseError,
+            -1 => Self::MiscError,
+            -2 => Self::ForbiddenBySafeMode,
+            -3 => Self::TypeError,
+            -5 => Self::InvalidAddressOrKey,
+            -7 => Self::OutOfMemory,
+            -8 => Self::InvalidParameter,
+            -20 => Self::DatabaseError,
+            -22 => Self::DeserializationError,
+            -25 => Self::VerifyError,
+            -26 => Self::VerifyRejected,
+            -27 => Self::VerifyAlreadyInUtxoSet,
+            -28 => Self::InWarmup,
+            -32 => Self::MethodDeprecated,
+            -9 => Self::ClientNotConnected,
+            -10 => Self::ClientInInitialDownload,
+            -23 => Self::ClientNodeAlreadyAdded,
+            -24 => Self::ClientNodeNotAdded,
+            -29 => Self::ClientNodeNotConnected,
+            -30 => Self::ClientInvalidIpOrSubnet,
+            -31 => Self::ClientP2pDisabled,
+            -33 => Self::ClientMempoolDisabled,
+            -34 => Self::ClientNodeCapacityReached,
+            -4 => Self::WalletError,
+            -6 => Self::WalletInsufficientFunds,
+            -11 => Self::WalletInvalidLabelName,
+            -12 => Self::WalletKeypoolRanOut,
+            -13 => Self::WalletUnlockNeeded,
+            -14 => Self::WalletPassphraseIncorrect,
+            -15 => Self::WalletWrongEncState,
+            -16 => Self::WalletEncryptionFailed,
+            -17 => Self::WalletAlreadyUnlocked,
+            -18 => Self::WalletNotFound,
+            -19 => Self::WalletNotSpecified,
+            -35 => Self::WalletAlreadyLoaded,
+            -36 => Self::WalletAlreadyExists,
+            code => Self::Unknown(code),
+        }
+    }
+}
+
+impl From<RpcErrorCode> for i32 {
+    fn from(code: RpcErrorCode) -> Self {
+        code.code()
     }
 }
 
@@ -177,3 +528,81 @@ impl fmt::Display for UnexpectedServerVersionError {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rpc_error_code_round_trip() {
+        for code in [-32700, -32601, -36, -28, -27, -18, -5, -4, -1, 0, -999] {
+            assert_eq!(RpcErrorCode::from(code).code(), code);
+        }
+        assert_eq!(RpcErrorCode::from(-999), RpcErrorCode::Unknown(-999));
+        assert_eq!(
+            serde_json::to_string(&RpcErrorCode::VerifyRejected).unwrap(),
+            "-26"
+        );
+        assert_eq!(
+            serde_json::from_str::<RpcErrorCode>("-35").unwrap(),
+            RpcErrorCode::WalletAlreadyLoaded
+        );
+    }
+
+    #[test]
+    fn classify_errors() {
+        let warmup = ClientError::rpc(RpcErrorCode::InWarmup, "Loading block index...");
+        assert!(warmup.is_retryable());
+        assert!(!ClientError::MaxRetriesExceeded(3, Box::new(warmup.clone())).is_retryable());
+        assert_eq!(
+            ClientError::MaxRetriesExceeded(3, Box::new(warmup)).rpc_error_code(),
+            Some(RpcErrorCode::InWarmup)
+        );
+
+        let insufficient_funds = ClientError::Server(-6, "Insufficient funds".to_string());
+        assert!(insufficient_funds.is_wallet_error());
+        assert!(!insufficient_funds.is_verify_error());
+
+        let rejected = ClientError::Server(-26, "min relay fee not met".to_string());
+        assert!(rejected.is_verify_error());
+        assert!(!rejected.is_missing_or_invalid_input());
+        assert!(!rejected.is_wallet_error());
+        assert_eq!(
+            rejected.reject_reason(),
+            Some(RejectReason::MinRelayFeeNotMet)
+        );
+
+        let spent = ClientError::Server(-25, "bad-txns-inputs-missingorspent".to_string());
+        assert!(spent.is_verify_error());
+        assert!(spent.is_missing_or_invalid_input());
+        assert_eq!(spent.reject_reason(), Some(RejectReason::MissingInputs));
+        let nonstandard = ClientError::Server(-26, "bad-txns-nonstandard-inputs".to_string());
+        assert!(nonstandard.is_missing_or_invalid_input());
+        assert!(!ClientError::Server(-26, "dust".to_string()).is_missing_or_invalid_input());
+        assert_eq!(insufficient_funds.reject_reason(), None);
+        assert_eq!(ClientError::Timeout.rpc_error_code(), None);
+    }
+
+    #[test]
+    fn not_found() {
+        let block = ClientError::Server(-5, "Block not found".to_string());
+        let height = ClientError::Server(-8, "Block height out of range".to_string());
+        let tx = ClientError::Server(
+            -5,
+            "No such mempool or blockchain transaction. Use gettransaction for wallet transactions."
+                .to_string(),
+        );
+        let wallet_tx = ClientError::Server(-5, "Invalid or non-wallet transaction id".to_string());
+        let address = ClientError::Server(-5, "Invalid address".to_string());
+
+        assert!(block.is_block_not_found());
+        assert!(height.is_block_not_found());
+        assert!(!tx.is_block_not_found());
+        assert!(!address.is_block_not_found());
+
+        assert!(tx.is_tx_not_found());
+        assert!(wallet_tx.is_tx_not_found());
+        assert!(!block.is_tx_not_found());
+        assert!(!address.is_tx_not_found());
+    }
+}
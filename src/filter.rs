@@ -0,0 +1,186 @@
+//! Client-side matching of BIP 158 compact block filters.
+//!
+//! A [`FilterScanner`] walks a range of blocks, matches their basic block filters against a
+//! set of scripts locally and only fetches the blocks that match. It verifies the BIP 157
+//! filter header chain on the way, so a filter that does not belong to the chain is detected.
+use bitcoin::{bip158::FilterHeader, hashes::Hash, Block, BlockHash, ScriptBuf};
+
+use crate::{client::ClientResult, error::ClientError, traits::Reader};
+
+/// A block whose filter matched one of the scripts of a [`FilterScanner`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterMatch {
+    /// The height of the block.
+    pub height: u64,
+
+    /// The hash of the block.
+    pub hash: BlockHash,
+
+    /// The block.
+    pub block: Block,
+}
+
+/// Scans a range of blocks of the active chain for scripts using their block filters.
+///
+/// Blocks are fetched through [`Reader::get_block`] only if their filter matches one of the
+/// scripts. Filters are probabilistic: every block paying to or spending from one of the
+/// scripts matches, but about one in 784931 other blocks matches as well.
+///
+/// # Note
+///
+/// Needs `bitcoind` to run with `-blockfilterindex=1`.
+///
+/// The filter header of the block before the range is the trust anchor. It is fetched from
+/// the node unless set with [`FilterScanner::with_filter_header`], such as from a previous
+/// scan. A reorg during the scan fails with [`ClientError::FilterHeaderMismatch`].
+#[derive(Debug)]
+pub struct FilterScanner<'a, R> {
+    /// The node to scan.
+    reader: &'a R,
+
+    /// The scripts to look for.
+    scripts: Vec<ScriptBuf>,
+
+    /// Height of the next block to scan.
+    next_height: u64,
+
+    /// Height of the last block to scan.
+    end_height: u64,
+
+    /// Filter header of the block before the next block to scan.
+    filter_header: Option<FilterHeader>,
+}
+
+impl<'a, R: Reader> FilterScanner<'a, R> {
+    /// Creates a new [`FilterScanner`] looking for the given scripts in the blocks from
+    /// `start_height` to `end_height`, both included.
+    pub fn new(
+        reader: &'a R,
+        scripts: impl IntoIterator<Item = ScriptBuf>,
+        start_height: u64,
+        end_height: u64,
+    ) -> Self {
+        Self {
+            reader,
+            scripts: scripts.into_iter().collect(),
+            next_height: start_height,
+            end_height,
+            filter_header: None,
+        }
+    }
+
+    /// Sets the filter header of the block before the range instead of fetching it.
+    pub fn with_filter_header(mut self, filter_header: FilterHeader) -> Self {
+        self.filter_header = Some(filter_header);
+        self
+    }
+
+    /// Returns the filter header of the last scanned block, once verified.
+    ///
+    /// Can be passed to [`FilterScanner::with_filter_header`] to resume scanning later.
+    pub fn filter_header(&self) -> Option<FilterHeader> {
+        self.filter_header
+    }
+
+    /// Returns the height of the next block to scan.
+    pub fn next_height(&self) -> u64 {
+        self.next_height
+    }
+
+    /// Returns the next block matching one of the scripts, or `None` once the range is
+    /// exhausted.
+    ///
+    /// # Note
+    ///
+    /// A block is only marked as scanned once its filter was verified and, if it matches,
+    /// fetched. Calling this again after an error retries the block that failed.
+    pub async fn next(&mut self) -> Option<ClientResult<FilterMatch>> {
+        while self.next_height <= self.end_height {
+            match self.scan_next().await {
+                Ok(None) => continue,
+                Ok(Some(found)) => return Some(Ok(found)),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+
+    /// Scans the block at [`FilterScanner::next_height`].
+    async fn scan_next(&mut self) -> ClientResult<Option<FilterMatch>> {
+        let height = self.next_height;
+        let previous_header = match self.filter_header {
+            Some(filter_header) => filter_header,
+            None if height == 0 => FilterHeader::all_zeros(),
+            None => {
+                let hash = self.reader.get_block_hash(height - 1).await?;
+                self.reader.get_block_filter(&hash).await?.header
+            }
+        };
+
+        let hash = self.reader.get_block_hash(height).await?;
+        let filter = self.reader.get_block_filter(&hash).await?;
+        if filter.filter.filter_header(&previous_header) != filter.header {
+            return Err(ClientError::FilterHeaderMismatch(height));
+        }
+
+        let matched = !self.scripts.is_empty()
+            && filter
+                .filter
+                .match_any(&hash, self.scripts.iter().map(|script| script.as_bytes()))
+                .map_err(|err| ClientError::Other(format!("block filter decode: {err}")))?;
+        let found = match matched {
+            true => Some(FilterMatch {
+                height,
+                hash,
+                block: self.reader.get_block(&hash).await?,
+            }),
+            false => None,
+        };
+
+        self.filter_header = Some(filter.header);
+        self.next_height += 1;
+        Ok(found)
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use bitcoin::{Address, Network};
+
+    use super::*;
+    use crate::mock::MockBitcoind;
+
+    #[tokio::test]
+    async fn scan_for_scripts() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+
+        let address = "bcrt1q6rz28mcfaxtmd6v789l9rrlrusdprr9pz3cppk"
+            .parse::<Address<_>>()
+            .unwrap()
+            .require_network(Network::Regtest)
+            .unwrap();
+        let hash = bitcoind.generate_to_address(1, &address)[0];
+        bitcoind.mine_blocks(5);
+
+        let mut scanner = FilterScanner::new(&bitcoind, [address.script_pubkey()], 50, 107);
+        let found = scanner.next().await.unwrap().unwrap();
+        assert_eq!(found.height, 102);
+        assert_eq!(found.hash, hash);
+        assert_eq!(found.block.block_hash(), hash);
+        assert!(scanner.next().await.is_none());
+
+        let tip = bitcoind.get_block_hash(107).await.unwrap();
+        let tip_filter = bitcoind.get_block_filter(&tip).await.unwrap();
+        assert_eq!(scanner.filter_header(), Some(tip_filter.header));
+        assert_eq!(scanner.next_height(), 108);
+
+        // A filter header that does not belong to the chain is detected.
+        let mut scanner = FilterScanner::new(&bitcoind, [address.script_pubkey()], 50, 107)
+            .with_filter_header(tip_filter.header);
+        assert_eq!(
+            scanner.next().await.unwrap(),
+            Err(ClientError::FilterHeaderMismatch(50))
+        );
+    }
+}
@@ -1,6 +1,12 @@
 pub mod client;
 pub mod error;
+pub mod filter;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod proof;
+pub mod retry;
 pub mod traits;
+pub mod transport;
 pub mod types;
 
 #[cfg(test)]
@@ -0,0 +1,3746 @@
+//! An in-memory mock of `bitcoind`.
+//!
+//! [`MockBitcoind`] simulates a regtest chain, its mempool and a wallet, and implements
+//! [`Reader`], [`Broadcaster`], [`Wallet`] and [`Signer`]. Code written against these traits can
+//! be unit-tested deterministically, without downloading and spawning a node.
+//!
+//! ```
+//! use bitcoind_async_client::{error::ClientError, mock::MockBitcoind, traits::Reader};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let bitcoind = MockBitcoind::new();
+//! bitcoind.mine_blocks(101);
+//! assert_eq!(bitcoind.get_block_count().await.unwrap(), 101);
+//!
+//! // Make the next `getblockcount` call fail.
+//! let warmup = ClientError::Server(-28, "Loading block index...".to_string());
+//! bitcoind.fail_next("getblockcount", warmup.clone());
+//! assert_eq!(bitcoind.get_block_count().await, Err(warmup));
+//! # }
+//! ```
+//!
+//! # Note
+//!
+//! The simulation is intentionally shallow:
+//!
+//! - Transactions are checked for missing, double-spent and immature inputs, and for paying the
+//!   minimum relay fee, but scripts and signatures are **not** verified.
+//! - `gettxoutsetinfo` computes no UTXO set hashes, and `scantxoutset` only understands `addr()`
+//!   and `raw()` descriptors.
+//! - Decoded scripts have no descriptors and `decodescript` always wraps scripts in P2WSH.
+//! - `getdescriptorinfo` computes checksums but only guesses the flags and does not canonicalize
+//!   descriptors, and `deriveaddresses` only understands `addr()` and `raw()` descriptors.
+//! - The wallet only derives and signs for P2WPKH addresses. Descriptors passed to
+//!   [`Signer::import_descriptors`] are recorded but not used.
+//!
+//! Only available with the `mock` feature.
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    mem,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use bitcoin::{
+    absolute::LockTime,
+    bip158::{self, BlockFilter, FilterHeader},
+    bip32::{ChildNumber, Xpriv},
+    block::{self, Header},
+    consensus::encode::serialize_hex,
+    constants::genesis_block,
+    ecdsa,
+    hashes::Hash,
+    hex::{DisplayHex, FromHex},
+    script::{Builder, PushBytesBuf},
+    secp256k1::{Message, SECP256K1},
+    sighash::{EcdsaSighashType, SighashCache},
+    transaction::Version,
+    Address, Amount, Block, BlockHash, CompressedPublicKey, FeeRate, MerkleBlock, Network,
+    NetworkKind, OutPoint, PrivateKey, Psbt, Script, ScriptBuf, Sequence, SignedAmount,
+    Transaction, TxIn, TxMerkleNode, TxOut, Txid, Witness, Work,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    client::ClientResult,
+    error::{ClientError, RpcErrorCode},
+    traits::{Broadcaster, Reader, Signer, Wallet},
+    types::{
+        BlockStatsField, BlockStatsTarget, BlockTransaction, BlockTransactionInput, ChainTip,
+        ChainTipStatus, CreateRawTransaction, CreateRawTransactionInput,
+        CreateRawTransactionOutput, DecodeRawTransaction, DecodeScript, DecodeScriptSegwit,
+        DecodedScriptPubKey, DecodedTxIn, DecodedTxOut, EstimateMode, EstimateRawFee,
+        GetAddressInfo, GetBlockFilter, GetBlockHeaderVerbose, GetBlockStats, GetBlockVerbose,
+        GetBlockchainInfo, GetDescriptorInfo, GetMempoolEntry, GetMempoolInfo,
+        GetRawTransactionVerbosityOne, GetRawTransactionVerbosityZero, GetTransaction,
+        GetTransactionDetail, GetTransactionDetailCategory, GetTxOut, GetTxOutSetInfo,
+        ImportDescriptor, ImportDescriptorResult, ListTransactions, ListUnspent,
+        ListUnspentQueryOptions, MempoolEntryFees, PreviousTransactionOutput, Prevout, PsbtBumpFee,
+        PsbtBumpFeeOptions, RawFeeEstimate, ScanObject, ScanTxOutSet, ScanTxOutSetStatus,
+        ScanTxOutSetUnspent, ScriptPubkey, SighashType, SignRawTransactionWithWallet,
+        SmartFeeEstimate, SubmitPackage, SubmitPackageTxResult, SubmitPackageTxResultFees,
+        TestMempoolAccept, TransactionCategory, TxOutSetHashType, WalletCreateFundedPsbt,
+        WalletCreateFundedPsbtOptions, WalletProcessPsbtResult,
+    },
+};
+
+/// Number of blocks a coinbase output has to be buried under before it can be spent.
+const COINBASE_MATURITY: u64 = 100;
+
+/// Number of blocks between block subsidy halvings on regtest.
+const SUBSIDY_HALVING_INTERVAL: u64 = 150;
+
+/// Seconds between the timestamps of consecutive mined blocks.
+const BLOCK_INTERVAL: u32 = 600;
+
+/// Outputs below this value are not worth creating as change.
+const DUST_LIMIT: Amount = Amount::from_sat(294);
+
+/// Seed of the wallet's master key used by [`MockBitcoind::new`].
+const DEFAULT_SEED: [u8; 32] = [1; 32];
+
+/// Name of the wallet that is loaded on startup, as in `corepc-node`.
+const DEFAULT_WALLET: &str = "default";
+
+/// Estimated virtual size of a P2WPKH output.
+const P2WPKH_OUTPUT_VSIZE: u64 = 31;
+
+/// An in-memory mock of `bitcoind` running on regtest.
+///
+/// Clones share the same simulated node, so a clone can be handed to the code under test while
+/// the test itself mines blocks, injects reorgs or scripts errors.
+///
+/// # Note
+///
+/// Errors scripted with [`MockBitcoind::fail_next`] are keyed by the name of the JSON-RPC method
+/// that the [`Client`](crate::Client) would call, such as `sendrawtransaction` for
+/// [`Broadcaster::send_raw_transaction`].
+#[derive(Debug, Clone)]
+pub struct MockBitcoind {
+    /// The simulated node.
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for MockBitcoind {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockBitcoind {
+    /// Creates a new [`MockBitcoind`] with only the regtest genesis block and an empty wallet.
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Creates a new [`MockBitcoind`] whose wallet keys are derived from the given seed.
+    pub fn with_seed(seed: [u8; 32]) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State::new(seed))),
+        }
+    }
+
+    /// Mines `count` blocks paying to a new wallet address, like the `generate` RPC.
+    ///
+    /// Every block includes the whole mempool. Returns the hashes of the new blocks.
+    pub fn mine_blocks(&self, count: usize) -> Vec<BlockHash> {
+        let mut state = self.lock();
+        let script_pubkey = state.wallet.new_address(false).script_pubkey();
+        state.mine(count, &script_pubkey)
+    }
+
+    /// Mines `count` blocks paying to the given address, like the `generatetoaddress` RPC.
+    ///
+    /// Every block includes the whole mempool. Returns the hashes of the new blocks.
+    pub fn generate_to_address(&self, count: usize, address: &Address) -> Vec<BlockHash> {
+        self.lock().mine(count, &address.script_pubkey())
+    }
+
+    /// Disconnects the `count` most recent blocks from the active chain.
+    ///
+    /// The transactions of the disconnected blocks go back to the mempool if they are still
+    /// valid. Returns the hashes of the disconnected blocks, starting at the old tip.
+    ///
+    /// # Panics
+    ///
+    /// If that would disconnect the genesis block.
+    pub fn disconnect_blocks(&self, count: usize) -> Vec<BlockHash> {
+        self.lock().disconnect(count)
+    }
+
+    /// Replaces the `depth` most recent blocks with `depth + 1` new blocks paying to a new wallet
+    /// address.
+    ///
+    /// Transactions of the replaced blocks that are still valid are confirmed again in the new
+    /// blocks. Returns the hashes of the new blocks.
+    ///
+    /// # Panics
+    ///
+    /// If that would disconnect the genesis block.
+    pub fn reorg(&self, depth: usize) -> Vec<BlockHash> {
+        let mut state = self.lock();
+        state.disconnect(depth);
+        let script_pubkey = state.wallet.new_address(false).script_pubkey();
+        state.mine(depth + 1, &script_pubkey)
+    }
+
+    /// Adds `count` headers on top of the active chain without their blocks, as announced by a
+    /// peer whose blocks were not downloaded yet.
+    ///
+    /// The headers form a [`ChainTipStatus::HeadersOnly`] branch that never becomes active.
+    /// Returns the hashes of the new headers.
+    pub fn receive_headers(&self, count: usize) -> Vec<BlockHash> {
+        self.lock().receive_headers(count)
+    }
+
+    /// Sets the fee rate returned by the fee estimation methods of [`Reader`] and used to fund
+    /// transactions.
+    ///
+    /// Defaults to 1 sat/vB.
+    pub fn set_fee_rate(&self, fee_rate: FeeRate) {
+        self.lock().fee_rate = fee_rate;
+    }
+
+    /// Makes the next call of the given JSON-RPC method fail with `error`.
+    ///
+    /// Errors scripted for the same method are returned in order, one per call.
+    pub fn fail_next(&self, method: &str, error: ClientError) {
+        self.lock()
+            .errors
+            .entry(method.to_string())
+            .or_default()
+            .push_back(error);
+    }
+
+    /// Locks the simulated node.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs `f` on the simulated node, unless an error was scripted for one of the JSON-RPC
+    /// `methods` that the [`Client`](crate::Client) would call.
+    fn with_state<R>(
+        &self,
+        methods: &[&str],
+        f: impl FnOnce(&mut State) -> ClientResult<R>,
+    ) -> ClientResult<R> {
+        let mut state = self.lock();
+        for method in methods {
+            if let Some(error) = state.errors.get_mut(*method).and_then(VecDeque::pop_front) {
+                return Err(error);
+            }
+        }
+        f(&mut state)
+    }
+}
+
+/// An unspent output of the active chain.
+#[derive(Debug, Clone)]
+struct Coin {
+    /// The output itself.
+    output: TxOut,
+
+    /// Height of the block that created the output.
+    height: u64,
+
+    /// Whether the output was created by a coinbase transaction.
+    is_coinbase: bool,
+}
+
+/// A transaction in the mempool.
+#[derive(Debug, Clone)]
+struct MempoolEntry {
+    /// The transaction id.
+    txid: Txid,
+
+    /// The transaction.
+    tx: Transaction,
+
+    /// The fee paid by the transaction.
+    fee: Amount,
+
+    /// Height of the chain tip when the transaction entered the mempool.
+    height: u64,
+
+    /// Timestamp of the chain tip when the transaction entered the mempool.
+    time: u64,
+}
+
+/// A transaction known to the node, either confirmed in the active chain or in the mempool.
+#[derive(Debug, Clone, Copy)]
+struct FoundTx<'a> {
+    /// The transaction.
+    tx: &'a Transaction,
+
+    /// Height of the block and position in it, if confirmed.
+    position: Option<(u64, usize)>,
+
+    /// The block time if confirmed, the time it entered the mempool otherwise.
+    time: u64,
+}
+
+/// An output the wallet can spend.
+#[derive(Debug, Clone)]
+struct WalletCoin {
+    /// The outpoint.
+    outpoint: OutPoint,
+
+    /// The output itself.
+    output: TxOut,
+
+    /// Number of confirmations, zero if in the mempool.
+    confirmations: u64,
+
+    /// Whether the output is confirmed or created by the wallet itself.
+    safe: bool,
+}
+
+/// A `listtransactions` or `gettransaction` entry of a wallet transaction.
+#[derive(Debug, Clone)]
+struct WalletEntry {
+    /// The output index.
+    vout: u32,
+
+    /// The address of the output, if it has one.
+    address: Option<Address>,
+
+    /// The category.
+    category: TransactionCategory,
+
+    /// The amount, negative when sending.
+    amount: SignedAmount,
+
+    /// The fee, negative and only for sent outputs.
+    fee: Option<SignedAmount>,
+}
+
+/// The simulated wallet.
+#[derive(Debug)]
+struct MockWallet {
+    /// The BIP32 master key.
+    master: Xpriv,
+
+    /// Derived keys, by script pubkey, with whether they are change.
+    keys: HashMap<ScriptBuf, (PrivateKey, bool)>,
+
+    /// Next derivation index of the receive and change chains.
+    next_index: [u32; 2],
+
+    /// Names of the loaded wallets.
+    wallets: Vec<String>,
+
+    /// Descriptors imported through [`Signer::import_descriptors`], with the wallet name.
+    descriptors: Vec<(String, ImportDescriptor)>,
+}
+
+impl MockWallet {
+    /// Creates a new wallet from a seed.
+    fn new(seed: [u8; 32]) -> Self {
+        Self {
+            master: Xpriv::new_master(NetworkKind::Test, &seed).expect("seed has a valid length"),
+            keys: HashMap::new(),
+            next_index: [0; 2],
+            wallets: vec![DEFAULT_WALLET.to_string()],
+            descriptors: Vec::new(),
+        }
+    }
+
+    /// Derives the next P2WPKH address at `m/84'/1'/0'/<change>/<index>`.
+    fn new_address(&mut self, change: bool) -> Address {
+        let index = self.next_index[change as usize];
+        self.next_index[change as usize] += 1;
+        let path = [
+            ChildNumber::Hardened { index: 84 },
+            ChildNumber::Hardened { index: 1 },
+            ChildNumber::Hardened { index: 0 },
+            ChildNumber::Normal {
+                index: change as u32,
+            },
+            ChildNumber::Normal { index },
+        ];
+        let key = self
+            .master
+            .derive_priv(SECP256K1, &path)
+            .expect("derivation path is valid")
+            .to_priv();
+        let public_key =
+            CompressedPublicKey::from_private_key(SECP256K1, &key).expect("key is compressed");
+        let address = Address::p2wpkh(&public_key, Network::Regtest);
+        self.keys.insert(address.script_pubkey(), (key, change));
+        address
+    }
+
+    /// Returns the key of a script pubkey owned by the wallet.
+    fn key(&self, script_pubkey: &Script) -> Option<&PrivateKey> {
+        self.keys.get(script_pubkey).map(|(key, _)| key)
+    }
+
+    /// Whether the script pubkey is owned by the wallet.
+    fn is_mine(&self, script_pubkey: &Script) -> bool {
+        self.keys.contains_key(script_pubkey)
+    }
+
+    /// Whether the script pubkey is a change script pubkey of the wallet.
+    fn is_change(&self, script_pubkey: &Script) -> bool {
+        self.keys
+            .get(script_pubkey)
+            .is_some_and(|(_, change)| *change)
+    }
+}
+
+/// The state of the simulated node.
+#[derive(Debug)]
+struct State {
+    /// All known blocks, including stale ones, with their heights.
+    blocks: HashMap<BlockHash, (Block, u64)>,
+
+    /// Headers of blocks whose data was never received, with their heights.
+    headers: HashMap<BlockHash, (Header, u64)>,
+
+    /// Hashes of the active chain, indexed by height.
+    chain: Vec<BlockHash>,
+
+    /// Height and position in the block of the transactions in the active chain.
+    tx_index: HashMap<Txid, (u64, usize)>,
+
+    /// Unspent outputs of the active chain.
+    utxos: HashMap<OutPoint, Coin>,
+
+    /// The mempool, parents before children.
+    mempool: Vec<MempoolEntry>,
+
+    /// The wallet.
+    wallet: MockWallet,
+
+    /// Fee rate returned by `estimatesmartfee` and used to fund transactions.
+    fee_rate: FeeRate,
+
+    /// Counter put in the coinbase so that blocks mined on the same parent differ.
+    extra_nonce: u64,
+
+    /// BIP 158 basic filters and their filter headers of all known blocks.
+    filters: HashMap<BlockHash, GetBlockFilter>,
+
+    /// Scripted errors by JSON-RPC method.
+    errors: HashMap<String, VecDeque<ClientError>>,
+}
+
+impl State {
+    /// Creates a new node with only the regtest genesis block.
+    fn new(seed: [u8; 32]) -> Self {
+        let mut state = Self {
+            blocks: HashMap::new(),
+            headers: HashMap::new(),
+            chain: Vec::new(),
+            tx_index: HashMap::new(),
+            utxos: HashMap::new(),
+            mempool: Vec::new(),
+            wallet: MockWallet::new(seed),
+            fee_rate: FeeRate::BROADCAST_MIN,
+            extra_nonce: 0,
+            filters: HashMap::new(),
+            errors: HashMap::new(),
+        };
+        state.connect(genesis_block(Network::Regtest));
+        state
+    }
+
+    /// Height of the chain tip.
+    fn tip_height(&self) -> u64 {
+        self.chain.len() as u64 - 1
+    }
+
+    /// The chain tip.
+    fn tip(&self) -> &Block {
+        &self.blocks[self.chain.last().expect("chain has a genesis block")].0
+    }
+
+    /// Returns a block and its height by hash.
+    fn block(&self, hash: &BlockHash) -> ClientResult<&(Block, u64)> {
+        if self.headers.contains_key(hash) {
+            return Err(ClientError::rpc(
+                RpcErrorCode::MiscError,
+                "Block not available (not fully downloaded)",
+            ));
+        }
+        self.blocks
+            .get(hash)
+            .ok_or_else(|| ClientError::rpc(RpcErrorCode::InvalidAddressOrKey, "Block not found"))
+    }
+
+    /// Returns the header of a block, with or without its data, and its height by hash.
+    fn header(&self, hash: &BlockHash) -> ClientResult<(&Header, u64)> {
+        match (self.blocks.get(hash), self.headers.get(hash)) {
+            (Some((block, height)), _) => Ok((&block.header, *height)),
+            (_, Some((header, height))) => Ok((header, *height)),
+            _ => Err(ClientError::rpc(
+                RpcErrorCode::InvalidAddressOrKey,
+                "Block not found",
+            )),
+        }
+    }
+
+    /// Returns the hash of the block at the given height of the active chain.
+    fn block_hash(&self, height: u64) -> ClientResult<BlockHash> {
+        self.chain.get(height as usize).copied().ok_or_else(|| {
+            ClientError::rpc(RpcErrorCode::InvalidParameter, "Block height out of range")
+        })
+    }
+
+    /// Returns the block at the given height of the active chain.
+    fn block_at(&self, height: u64) -> ClientResult<&Block> {
+        let hash = self.block_hash(height)?;
+        Ok(&self.blocks[&hash].0)
+    }
+
+    /// Number of confirmations of a block at the given height of the active chain.
+    fn confirmations(&self, height: u64) -> u64 {
+        self.tip_height() + 1 - height
+    }
+
+    /// Median time of the last 11 blocks.
+    fn median_time_past(&self) -> u32 {
+        self.median_time_at(self.chain.last().expect("chain has a genesis block"))
+    }
+
+    /// Median time of the 11 blocks ending at the given known block.
+    fn median_time_at(&self, hash: &BlockHash) -> u32 {
+        let mut times = self
+            .ancestors(hash)
+            .take(11)
+            .map(|header| header.time)
+            .collect::<Vec<_>>();
+        times.sort_unstable();
+        times[times.len() / 2]
+    }
+
+    /// Iterates over the headers of a known block and its ancestors, down to the genesis block.
+    fn ancestors<'a>(&'a self, hash: &BlockHash) -> impl Iterator<Item = &'a Header> + 'a {
+        let mut next = self.header(hash).ok().map(|(header, _)| header);
+        std::iter::from_fn(move || {
+            let header = next?;
+            next = self
+                .header(&header.prev_blockhash)
+                .ok()
+                .map(|(header, _)| header);
+            Some(header)
+        })
+    }
+
+    /// Total work of the chain ending at the given known block.
+    fn chain_work(&self, hash: &BlockHash) -> Work {
+        self.ancestors(hash)
+            .map(Header::work)
+            .fold(Work::from_be_bytes([0; 32]), |total, work| total + work)
+    }
+
+    /// Describes the header of a known block as `getblockheader` with verbosity set to `true`.
+    fn header_verbose(&self, hash: &BlockHash) -> ClientResult<GetBlockHeaderVerbose> {
+        let (header, height) = self.header(hash)?;
+        let in_active_chain = self.chain.get(height as usize) == Some(hash);
+        Ok(GetBlockHeaderVerbose {
+            hash: *hash,
+            confirmations: if in_active_chain {
+                self.confirmations(height) as i32
+            } else {
+                -1
+            },
+            height,
+            version: header.version.to_consensus(),
+            version_hex: format!("{:08x}", header.version.to_consensus()),
+            merkle_root: header.merkle_root,
+            time: header.time as usize,
+            median_time: self.median_time_at(hash) as usize,
+            nonce: header.nonce,
+            bits: format!("{:08x}", header.bits.to_consensus()),
+            difficulty: header.difficulty_float(),
+            chain_work: self.chain_work(hash).to_be_bytes().to_lower_hex_string(),
+            n_tx: self
+                .blocks
+                .get(hash)
+                .map_or(0, |(block, _)| block.txdata.len() as u32),
+            previous_block_hash: (height > 0).then_some(header.prev_blockhash),
+            next_block_hash: in_active_chain
+                .then(|| self.chain.get(height as usize + 1).copied())
+                .flatten(),
+        })
+    }
+
+    /// Looks up a transaction in the active chain or in the mempool.
+    fn find_tx(&self, txid: &Txid) -> Option<FoundTx<'_>> {
+        if let Some(&(height, pos)) = self.tx_index.get(txid) {
+            let block = &self.blocks[&self.chain[height as usize]].0;
+            return Some(FoundTx {
+                tx: &block.txdata[pos],
+                position: Some((height, pos)),
+                time: block.header.time as u64,
+            });
+        }
+        self.mempool
+            .iter()
+            .find(|entry| entry.txid == *txid)
+            .map(|entry| FoundTx {
+                tx: &entry.tx,
+                position: None,
+                time: entry.time,
+            })
+    }
+
+    /// Returns the output an outpoint points to, spent or not.
+    fn prevout(&self, outpoint: &OutPoint) -> Option<TxOut> {
+        self.find_tx(&outpoint.txid)
+            .and_then(|found| found.tx.output.get(outpoint.vout as usize).cloned())
+    }
+
+    /// Returns the coin an input of the given block spends, spent or not.
+    ///
+    /// Looks in the block itself before the active chain, so that stale blocks spending their
+    /// own outputs resolve as well.
+    fn spent_coin(&self, outpoint: &OutPoint, block: &Block, height: u64) -> Option<Coin> {
+        let (tx, height, pos) = match block
+            .txdata
+            .iter()
+            .position(|tx| tx.compute_txid() == outpoint.txid)
+        {
+            Some(pos) => (&block.txdata[pos], height, pos),
+            None => {
+                let &(height, pos) = self.tx_index.get(&outpoint.txid)?;
+                let block = &self.blocks[&self.chain[height as usize]].0;
+                (&block.txdata[pos], height, pos)
+            }
+        };
+        Some(Coin {
+            output: tx.output.get(outpoint.vout as usize)?.clone(),
+            height,
+            is_coinbase: pos == 0,
+        })
+    }
+
+    /// Returns a known block with decoded transactions as `getblock` with verbosity 2 or 3.
+    fn block_verbose(
+        &self,
+        hash: &BlockHash,
+        include_prevouts: bool,
+    ) -> ClientResult<GetBlockVerbose> {
+        let (block, height) = self.block(hash)?;
+        let height = *height;
+        let in_active_chain = self.chain.get(height as usize) == Some(hash);
+        let tx = block
+            .txdata
+            .iter()
+            .map(|tx| {
+                let coins = (!tx.is_coinbase())
+                    .then(|| {
+                        tx.input
+                            .iter()
+                            .map(|input| self.spent_coin(&input.previous_output, block, height))
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .flatten();
+                let fee = coins.as_ref().and_then(|coins| {
+                    let input_value = coins.iter().map(|coin| coin.output.value).sum::<Amount>();
+                    let output_value = tx.output.iter().map(|output| output.value).sum();
+                    input_value.checked_sub(output_value)
+                });
+                let vin = match coins.filter(|_| include_prevouts) {
+                    Some(coins) => coins
+                        .into_iter()
+                        .map(|coin| BlockTransactionInput {
+                            prevout: Some(Prevout {
+                                generated: coin.is_coinbase,
+                                height: coin.height,
+                                value: coin.output.value,
+                                script_pubkey: coin.output.script_pubkey,
+                            }),
+                        })
+                        .collect(),
+                    None => vec![BlockTransactionInput { prevout: None }; tx.input.len()],
+                };
+                BlockTransaction {
+                    txid: tx.compute_txid(),
+                    hash: tx.compute_wtxid(),
+                    size: tx.total_size(),
+                    vsize: tx.vsize(),
+                    weight: tx.weight().to_wu(),
+                    transaction: tx.clone(),
+                    fee,
+                    vin,
+                }
+            })
+            .collect();
+        let header = &block.header;
+        Ok(GetBlockVerbose {
+            hash: *hash,
+            confirmations: if in_active_chain {
+                self.confirmations(height) as i32
+            } else {
+                -1
+            },
+            size: block.total_size(),
+            // The weight counts the base size three times more than the total size.
+            stripped_size: Some((block.weight().to_wu() as usize - block.total_size()) / 3),
+            weight: block.weight().to_wu(),
+            height,
+            version: header.version.to_consensus(),
+            version_hex: format!("{:08x}", header.version.to_consensus()),
+            merkle_root: header.merkle_root,
+            tx,
+            time: header.time as usize,
+            median_time: Some(self.median_time_at(hash) as usize),
+            nonce: header.nonce,
+            bits: format!("{:08x}", header.bits.to_consensus()),
+            difficulty: header.difficulty_float(),
+            chain_work: self.chain_work(hash).to_be_bytes().to_lower_hex_string(),
+            n_tx: block.txdata.len() as u32,
+            previous_block_hash: (height > 0).then_some(header.prev_blockhash),
+            next_block_hash: in_active_chain
+                .then(|| self.chain.get(height as usize + 1).copied())
+                .flatten(),
+        })
+    }
+
+    /// Computes the statistics of a known block as `getblockstats`, in the units of its JSON
+    /// result.
+    ///
+    /// See `getblockstats` in `src/rpc/blockchain.cpp` of Bitcoin Core.
+    fn block_stats(&self, hash: &BlockHash) -> ClientResult<Map<String, Value>> {
+        /// Size of an outpoint, a height and a coinbase flag stored with every unspent output.
+        const PER_UTXO_OVERHEAD: i64 = 41;
+
+        let block = self.block_verbose(hash, true)?;
+        let txs = &block.tx;
+        let mut stats = GetBlockStatsAccumulator::default();
+        for tx in txs {
+            let transaction = &tx.transaction;
+            stats.outs += transaction.output.len() as i64;
+            for output in &transaction.output {
+                let size = output.size() as i64 + PER_UTXO_OVERHEAD;
+                stats.utxo_size_inc += size;
+                if !output.script_pubkey.is_op_return() {
+                    stats.utxos_actual += 1;
+                    stats.utxo_size_inc_actual += size;
+                }
+            }
+            let Some(prevouts) = tx.prevouts() else {
+                continue;
+            };
+            stats.ins += prevouts.len() as i64;
+            for prevout in &prevouts {
+                let size = prevout.size() as i64 + PER_UTXO_OVERHEAD;
+                stats.utxo_size_inc -= size;
+                stats.utxo_size_inc_actual -= size;
+            }
+            let fee = tx.fee.unwrap_or(Amount::ZERO).to_sat();
+            let size = tx.size as u64;
+            let weight = tx.weight;
+            stats.total_out += transaction
+                .output
+                .iter()
+                .map(|o| o.value.to_sat())
+                .sum::<u64>();
+            stats.total_size += size;
+            stats.total_weight += weight;
+            if transaction
+                .input
+                .iter()
+                .any(|input| !input.witness.is_empty())
+            {
+                stats.sw_txs += 1;
+                stats.sw_total_size += size;
+                stats.sw_total_weight += weight;
+            }
+            stats.fees.push(fee);
+            stats.sizes.push(size);
+            stats
+                .fee_rates
+                .push(((fee * 4).checked_div(weight).unwrap_or(0), weight));
+        }
+
+        let spends = txs.len() as u64 - 1;
+        let total_fee = stats.fees.iter().sum::<u64>();
+        let fee_rates = stats.fee_rates.iter().map(|(fee_rate, _)| *fee_rate);
+        Ok(json!({
+            "avgfee": total_fee.checked_div(spends).unwrap_or(0),
+            "avgfeerate": (total_fee * 4).checked_div(stats.total_weight).unwrap_or(0),
+            "avgtxsize": stats.total_size.checked_div(spends).unwrap_or(0),
+            "blockhash": hash,
+            "feerate_percentiles": fee_rate_percentiles(stats.fee_rates.clone()),
+            "height": block.height,
+            "ins": stats.ins,
+            "maxfee": stats.fees.iter().max().copied().unwrap_or(0),
+            "maxfeerate": fee_rates.clone().max().unwrap_or(0),
+            "maxtxsize": stats.sizes.iter().max().copied().unwrap_or(0),
+            "medianfee": truncated_median(stats.fees.clone()),
+            "mediantime": block.median_time,
+            "mediantxsize": truncated_median(stats.sizes.clone()),
+            "minfee": stats.fees.iter().min().copied().unwrap_or(0),
+            "minfeerate": fee_rates.min().unwrap_or(0),
+            "mintxsize": stats.sizes.iter().min().copied().unwrap_or(0),
+            "outs": stats.outs,
+            "subsidy": block_subsidy(block.height).to_sat(),
+            "swtotal_size": stats.sw_total_size,
+            "swtotal_weight": stats.sw_total_weight,
+            "swtxs": stats.sw_txs,
+            "time": block.time,
+            "total_out": stats.total_out,
+            "total_size": stats.total_size,
+            "total_weight": stats.total_weight,
+            "totalfee": total_fee,
+            "txs": txs.len(),
+            "utxo_increase": stats.outs - stats.ins,
+            "utxo_size_inc": stats.utxo_size_inc,
+            "utxo_increase_actual": stats.utxos_actual - stats.ins,
+            "utxo_size_inc_actual": stats.utxo_size_inc_actual,
+        })
+        .as_object()
+        .cloned()
+        .expect("stats are an object"))
+    }
+
+    /// Returns the mempool transaction spending the given outpoint, if any.
+    fn mempool_spender(&self, outpoint: &OutPoint) -> Option<&MempoolEntry> {
+        self.mempool.iter().find(|entry| {
+            entry
+                .tx
+                .input
+                .iter()
+                .any(|input| input.previous_output == *outpoint)
+        })
+    }
+
+    /// Returns the mempool entry of a transaction.
+    fn mempool_entry(&self, txid: &Txid) -> ClientResult<&MempoolEntry> {
+        self.mempool
+            .iter()
+            .find(|entry| entry.txid == *txid)
+            .ok_or_else(|| {
+                ClientError::rpc(
+                    RpcErrorCode::InvalidAddressOrKey,
+                    "Transaction not in mempool",
+                )
+            })
+    }
+
+    /// Returns the in-mempool parents of a mempool transaction, or its children.
+    fn mempool_relatives(&self, entry: &MempoolEntry, children: bool) -> Vec<&MempoolEntry> {
+        self.mempool
+            .iter()
+            .filter(|other| {
+                let (parent, child) = match children {
+                    true => (entry, *other),
+                    false => (*other, entry),
+                };
+                child
+                    .tx
+                    .input
+                    .iter()
+                    .any(|input| input.previous_output.txid == parent.txid)
+            })
+            .collect()
+    }
+
+    /// Returns all in-mempool ancestors of a mempool transaction, or all its descendants,
+    /// excluding the transaction itself.
+    fn mempool_closure(&self, entry: &MempoolEntry, descendants: bool) -> Vec<&MempoolEntry> {
+        let mut found = Vec::<&MempoolEntry>::new();
+        let mut queue = vec![entry];
+        while let Some(next) = queue.pop() {
+            for relative in self.mempool_relatives(next, descendants) {
+                if !found.iter().any(|found| found.txid == relative.txid) {
+                    found.push(relative);
+                    queue.push(relative);
+                }
+            }
+        }
+        found
+    }
+
+    /// Describes a mempool transaction as `getmempoolentry`.
+    fn mempool_entry_info(&self, entry: &MempoolEntry) -> GetMempoolEntry {
+        let ancestors = self.mempool_closure(entry, false);
+        let descendants = self.mempool_closure(entry, true);
+        let vsize = |entries: &[&MempoolEntry]| {
+            entries
+                .iter()
+                .map(|entry| entry.tx.vsize() as u64)
+                .sum::<u64>()
+        };
+        let fees =
+            |entries: &[&MempoolEntry]| entries.iter().map(|entry| entry.fee).sum::<Amount>();
+        let txids = |entries: Vec<&MempoolEntry>| entries.iter().map(|entry| entry.txid).collect();
+        GetMempoolEntry {
+            vsize: entry.tx.vsize() as u64,
+            weight: entry.tx.weight().to_wu(),
+            time: entry.time,
+            height: entry.height,
+            descendant_count: descendants.len() as u64 + 1,
+            descendant_size: vsize(&descendants) + entry.tx.vsize() as u64,
+            ancestor_count: ancestors.len() as u64 + 1,
+            ancestor_size: vsize(&ancestors) + entry.tx.vsize() as u64,
+            wtxid: entry.tx.compute_wtxid(),
+            fees: MempoolEntryFees {
+                base: entry.fee,
+                modified: entry.fee,
+                ancestor: fees(&ancestors) + entry.fee,
+                descendant: fees(&descendants) + entry.fee,
+            },
+            bip125_replaceable: entry.tx.is_explicitly_rbf()
+                || ancestors.iter().any(|entry| entry.tx.is_explicitly_rbf()),
+            depends: txids(self.mempool_relatives(entry, false)),
+            spent_by: txids(self.mempool_relatives(entry, true)),
+            unbroadcast: false,
+        }
+    }
+
+    /// Checks whether a transaction can enter the mempool.
+    ///
+    /// Returns the fee of the transaction and the mempool transactions it replaces.
+    fn check_transaction(
+        &self,
+        tx: &Transaction,
+        check_fee: bool,
+    ) -> ClientResult<(Amount, Vec<Txid>)> {
+        let reject = |reason: &str| ClientError::rpc(RpcErrorCode::VerifyRejected, reason);
+        let txid = tx.compute_txid();
+        if tx.is_coinbase() {
+            return Err(reject("coinbase"));
+        }
+        if self.tx_index.contains_key(&txid) {
+            return Err(ClientError::rpc(
+                RpcErrorCode::VerifyAlreadyInUtxoSet,
+                "Transaction already in block chain",
+            ));
+        }
+        if self.mempool.iter().any(|entry| entry.txid == txid) {
+            return Err(reject("txn-already-in-mempool"));
+        }
+
+        let mut input_value = Amount::ZERO;
+        let mut replaces = Vec::new();
+        for input in &tx.input {
+            if let Some(spender) = self.mempool_spender(&input.previous_output) {
+                if !spender.tx.is_explicitly_rbf() {
+                    return Err(reject("txn-mempool-conflict"));
+                }
+                replaces.push(spender.txid);
+            }
+            let value = match self.utxos.get(&input.previous_output) {
+                Some(coin) => {
+                    if coin.is_coinbase && self.tip_height() + 1 - coin.height < COINBASE_MATURITY {
+                        return Err(reject("bad-txns-premature-spend-of-coinbase"));
+                    }
+                    coin.output.value
+                }
+                None => self
+                    .mempool
+                    .iter()
+                    .find(|entry| entry.txid == input.previous_output.txid)
+                    .and_then(|entry| entry.tx.output.get(input.previous_output.vout as usize))
+                    .map(|output| output.value)
+                    .ok_or_else(|| {
+                        ClientError::rpc(
+                            RpcErrorCode::VerifyError,
+                            "bad-txns-inputs-missingorspent",
+                        )
+                    })?,
+            };
+            input_value += value;
+        }
+
+        let output_value = tx.output.iter().map(|output| output.value).sum::<Amount>();
+        let fee = input_value
+            .checked_sub(output_value)
+            .ok_or_else(|| reject("bad-txns-in-belowout"))?;
+        let min_fee = FeeRate::BROADCAST_MIN
+            .fee_vb(tx.vsize() as u64)
+            .unwrap_or(Amount::MAX);
+        if check_fee && fee < min_fee {
+            return Err(reject("min relay fee not met"));
+        }
+        if !replaces.is_empty() {
+            replaces.sort_unstable();
+            replaces.dedup();
+            let replaced_fees = self
+                .mempool
+                .iter()
+                .filter(|entry| replaces.contains(&entry.txid))
+                .map(|entry| entry.fee)
+                .sum::<Amount>();
+            if fee < replaced_fees + min_fee {
+                return Err(reject("insufficient fee"));
+            }
+        }
+        Ok((fee, replaces))
+    }
+
+    /// Adds a transaction to the mempool, evicting the transactions it replaces.
+    fn accept(&mut self, tx: &Transaction) -> ClientResult<Txid> {
+        let (fee, replaces) = self.check_transaction(tx, true)?;
+        self.remove_from_mempool(replaces);
+        let txid = tx.compute_txid();
+        self.mempool.push(MempoolEntry {
+            txid,
+            tx: tx.clone(),
+            fee,
+            height: self.tip_height(),
+            time: self.tip().header.time as u64,
+        });
+        Ok(txid)
+    }
+
+    /// Removes transactions and their descendants from the mempool.
+    fn remove_from_mempool(&mut self, mut txids: Vec<Txid>) {
+        while let Some(txid) = txids.pop() {
+            self.mempool.retain(|entry| entry.txid != txid);
+            txids.extend(
+                self.mempool
+                    .iter()
+                    .filter(|entry| {
+                        entry
+                            .tx
+                            .input
+                            .iter()
+                            .any(|input| input.previous_output.txid == txid)
+                    })
+                    .map(|entry| entry.txid),
+            );
+        }
+    }
+
+    /// Drops the mempool transactions that are no longer valid.
+    fn revalidate_mempool(&mut self) {
+        for mut entry in mem::take(&mut self.mempool) {
+            if let Ok((fee, replaces)) = self.check_transaction(&entry.tx, false) {
+                if replaces.is_empty() {
+                    entry.fee = fee;
+                    self.mempool.push(entry);
+                }
+            }
+        }
+    }
+
+    /// Mines `count` blocks including the whole mempool and paying to `script_pubkey`.
+    fn mine(&mut self, count: usize, script_pubkey: &Script) -> Vec<BlockHash> {
+        (0..count)
+            .map(|_| {
+                let height = self.tip_height() + 1;
+                let prev = self.tip().header;
+                let entries = mem::take(&mut self.mempool);
+                let fees = entries.iter().map(|entry| entry.fee).sum::<Amount>();
+                self.extra_nonce += 1;
+
+                let coinbase = Transaction {
+                    version: Version::TWO,
+                    lock_time: LockTime::ZERO,
+                    input: vec![TxIn {
+                        previous_output: OutPoint::null(),
+                        script_sig: Builder::new()
+                            .push_int(height as i64)
+                            .push_int(self.extra_nonce as i64)
+                            .into_script(),
+                        sequence: Sequence::MAX,
+                        witness: Witness::from_slice(&[[0u8; 32]]),
+                    }],
+                    output: vec![TxOut {
+                        value: block_subsidy(height) + fees,
+                        script_pubkey: script_pubkey.to_owned(),
+                    }],
+                };
+                let mut block = Block {
+                    header: Header {
+                        version: block::Version::from_consensus(0x2000_0000),
+                        prev_blockhash: prev.block_hash(),
+                        merkle_root: TxMerkleNode::all_zeros(),
+                        time: prev.time + BLOCK_INTERVAL,
+                        bits: prev.bits,
+                        nonce: 0,
+                    },
+                    txdata: std::iter::once(coinbase)
+                        .chain(entries.into_iter().map(|entry| entry.tx))
+                        .collect(),
+                };
+
+                // BIP 141 witness commitment, the coinbase witness is the all-zero reserved value.
+                let witness_root = block.witness_root().expect("block has a coinbase");
+                let commitment = Block::compute_witness_commitment(&witness_root, &[0; 32]);
+                let mut commitment_script = vec![0x6a, 0x24, 0xaa, 0x21, 0xa9, 0xed];
+                commitment_script.extend_from_slice(commitment.as_byte_array());
+                block.txdata[0].output.push(TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: ScriptBuf::from_bytes(commitment_script),
+                });
+
+                block.header.merkle_root =
+                    block.compute_merkle_root().expect("block has a coinbase");
+                while block.header.validate_pow(block.header.target()).is_err() {
+                    block.header.nonce += 1;
+                }
+                self.connect(block)
+            })
+            .collect()
+    }
+
+    /// Adds `count` headers without blocks on top of the active chain.
+    fn receive_headers(&mut self, count: usize) -> Vec<BlockHash> {
+        let mut prev = self.tip().header;
+        let mut height = self.tip_height();
+        (0..count)
+            .map(|_| {
+                height += 1;
+                self.extra_nonce += 1;
+                let mut header = Header {
+                    version: block::Version::from_consensus(0x2000_0000),
+                    prev_blockhash: prev.block_hash(),
+                    // Any merkle root will do without transactions to commit to.
+                    merkle_root: TxMerkleNode::hash(&self.extra_nonce.to_le_bytes()),
+                    time: prev.time + BLOCK_INTERVAL,
+                    bits: prev.bits,
+                    nonce: 0,
+                };
+                while header.validate_pow(header.target()).is_err() {
+                    header.nonce += 1;
+                }
+                let hash = header.block_hash();
+                self.headers.insert(hash, (header, height));
+                prev = header;
+                hash
+            })
+            .collect()
+    }
+
+    /// Connects a block on top of the active chain.
+    fn connect(&mut self, block: Block) -> BlockHash {
+        let height = self.chain.len() as u64;
+        let hash = block.block_hash();
+        let filter = self.block_filter(&block, height);
+        self.filters.insert(hash, filter);
+        index_block(&mut self.utxos, &mut self.tx_index, &block, height);
+        self.chain.push(hash);
+        self.blocks.insert(hash, (block, height));
+        self.revalidate_mempool();
+        hash
+    }
+
+    /// Computes the BIP 158 basic filter and filter header of a block extending the active
+    /// chain.
+    fn block_filter(&self, block: &Block, height: u64) -> GetBlockFilter {
+        let filter = BlockFilter::new_script_filter(block, |outpoint| {
+            self.spent_coin(outpoint, block, height)
+                .map(|coin| coin.output.script_pubkey)
+                .ok_or(bip158::Error::UtxoMissing(*outpoint))
+        })
+        .expect("connected blocks only spend known outputs");
+        let previous_header = self
+            .filters
+            .get(&block.header.prev_blockhash)
+            .map_or(FilterHeader::all_zeros(), |filter| filter.header);
+        GetBlockFilter {
+            header: filter.filter_header(&previous_header),
+            filter,
+        }
+    }
+
+    /// Disconnects the `count` most recent blocks from the active chain.
+    fn disconnect(&mut self, count: usize) -> Vec<BlockHash> {
+        assert!(
+            count < self.chain.len(),
+            "cannot disconnect the genesis block"
+        );
+        let disconnected = self
+            .chain
+            .split_off(self.chain.len() - count)
+            .into_iter()
+            .rev()
+            .collect::<Vec<_>>();
+
+        self.utxos.clear();
+        self.tx_index.clear();
+        for (height, hash) in self.chain.iter().enumerate() {
+            let block = &self.blocks[hash].0;
+            index_block(&mut self.utxos, &mut self.tx_index, block, height as u64);
+        }
+
+        // Transactions of disconnected blocks go back to the mempool before the others.
+        let time = self.tip().header.time as u64;
+        let mut mempool = disconnected
+            .iter()
+            .rev()
+            .flat_map(|hash| self.blocks[hash].0.txdata.iter().skip(1))
+            .map(|tx| MempoolEntry {
+                txid: tx.compute_txid(),
+                tx: tx.clone(),
+                fee: Amount::ZERO,
+                height: self.tip_height(),
+                time,
+            })
+            .collect::<Vec<_>>();
+        mempool.append(&mut self.mempool);
+        self.mempool = mempool;
+        self.revalidate_mempool();
+        disconnected
+    }
+
+    /// Outputs the wallet can spend, oldest first.
+    ///
+    /// Immature coinbase outputs and outputs spent in the mempool are left out.
+    fn wallet_coins(&self) -> Vec<WalletCoin> {
+        let mut coins = self
+            .utxos
+            .iter()
+            .filter(|(outpoint, coin)| {
+                self.wallet.is_mine(&coin.output.script_pubkey)
+                    && !(coin.is_coinbase && self.confirmations(coin.height) <= COINBASE_MATURITY)
+                    && self.mempool_spender(outpoint).is_none()
+            })
+            .map(|(outpoint, coin)| {
+                (
+                    coin.height,
+                    WalletCoin {
+                        outpoint: *outpoint,
+                        output: coin.output.clone(),
+                        confirmations: self.confirmations(coin.height),
+                        safe: true,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        coins.sort_by_key(|(height, coin)| (*height, coin.outpoint.txid, coin.outpoint.vout));
+
+        let unconfirmed = self.mempool.iter().flat_map(|entry| {
+            let safe = self.debit(&entry.tx) > Amount::ZERO;
+            entry
+                .tx
+                .output
+                .iter()
+                .enumerate()
+                .map(move |(vout, output)| WalletCoin {
+                    outpoint: OutPoint::new(entry.txid, vout as u32),
+                    output: output.clone(),
+                    confirmations: 0,
+                    safe,
+                })
+        });
+        coins
+            .into_iter()
+            .map(|(_, coin)| coin)
+            .chain(unconfirmed.filter(|coin| {
+                self.wallet.is_mine(&coin.output.script_pubkey)
+                    && self.mempool_spender(&coin.outpoint).is_none()
+            }))
+            .collect()
+    }
+
+    /// Value of the wallet outputs spent by a transaction.
+    fn debit(&self, tx: &Transaction) -> Amount {
+        if tx.is_coinbase() {
+            return Amount::ZERO;
+        }
+        tx.input
+            .iter()
+            .filter_map(|input| self.prevout(&input.previous_output))
+            .filter(|output| self.wallet.is_mine(&output.script_pubkey))
+            .map(|output| output.value)
+            .sum()
+    }
+
+    /// Value of the outputs of a transaction paying to the wallet.
+    fn credit(&self, tx: &Transaction) -> Amount {
+        tx.output
+            .iter()
+            .filter(|output| self.wallet.is_mine(&output.script_pubkey))
+            .map(|output| output.value)
+            .sum()
+    }
+
+    /// Wallet entries of a transaction, empty if it does not involve the wallet.
+    fn wallet_entries(&self, tx: &Transaction, confirmations: u64) -> Vec<WalletEntry> {
+        let debit = self.debit(tx);
+        let value_out = tx.output.iter().map(|output| output.value).sum::<Amount>();
+        let fee = debit
+            .checked_sub(value_out)
+            .map(|fee| -fee.to_signed().unwrap_or(SignedAmount::MAX));
+        let address =
+            |output: &TxOut| Address::from_script(&output.script_pubkey, Network::Regtest).ok();
+
+        let mut entries = Vec::new();
+        for (vout, output) in tx.output.iter().enumerate() {
+            let vout = vout as u32;
+            let amount = output.value.to_signed().unwrap_or(SignedAmount::MAX);
+            if self.wallet.is_mine(&output.script_pubkey) {
+                let category = if !tx.is_coinbase() {
+                    TransactionCategory::Receive
+                } else if confirmations > COINBASE_MATURITY {
+                    TransactionCategory::Generate
+                } else {
+                    TransactionCategory::Immature
+                };
+                // Change is neither sent nor received.
+                if debit == Amount::ZERO || !self.wallet.is_change(&output.script_pubkey) {
+                    entries.push(WalletEntry {
+                        vout,
+                        address: address(output),
+                        category,
+                        amount,
+                        fee: None,
+                    });
+                }
+            } else if debit > Amount::ZERO {
+                entries.push(WalletEntry {
+                    vout,
+                    address: address(output),
+                    category: TransactionCategory::Send,
+                    amount: -amount,
+                    fee,
+                });
+            }
+        }
+        entries
+    }
+
+    /// Finds the prevouts of a transaction, from the node or the given previous outputs.
+    fn prevouts(
+        &self,
+        tx: &Transaction,
+        prev_outputs: &[PreviousTransactionOutput],
+    ) -> Vec<Option<TxOut>> {
+        tx.input
+            .iter()
+            .map(|input| {
+                let outpoint = input.previous_output;
+                self.prevout(&outpoint).or_else(|| {
+                    let prev = prev_outputs
+                        .iter()
+                        .find(|prev| prev.txid == outpoint.txid && prev.vout == outpoint.vout)?;
+                    Some(TxOut {
+                        value: Amount::from_btc(prev.amount?).ok()?,
+                        script_pubkey: ScriptBuf::from_hex(&prev.script_pubkey).ok()?,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Signs the inputs of a transaction spending P2WPKH outputs of the wallet.
+    ///
+    /// Returns the witnesses, `None` for inputs the wallet cannot sign.
+    fn sign(
+        &self,
+        tx: &Transaction,
+        prevouts: &[Option<TxOut>],
+        sighash_type: EcdsaSighashType,
+    ) -> ClientResult<Vec<Option<Witness>>> {
+        let mut cache = SighashCache::new(tx);
+        prevouts
+            .iter()
+            .enumerate()
+            .map(|(index, prevout)| {
+                let Some((prevout, key)) = prevout.as_ref().and_then(|prevout| {
+                    let key = self.wallet.key(&prevout.script_pubkey)?;
+                    Some((prevout, key))
+                }) else {
+                    return Ok(None);
+                };
+                let sighash = cache
+                    .p2wpkh_signature_hash(
+                        index,
+                        &prevout.script_pubkey,
+                        prevout.value,
+                        sighash_type,
+                    )
+                    .map_err(|e| ClientError::Other(e.to_string()))?;
+                let signature = ecdsa::Signature {
+                    signature: SECP256K1
+                        .sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &key.inner),
+                    sighash_type,
+                };
+                Ok(Some(Witness::p2wpkh(
+                    &signature,
+                    &key.public_key(SECP256K1).inner,
+                )))
+            })
+            .collect()
+    }
+
+    /// Converts a wallet coin into a `listunspent` entry.
+    fn list_unspent_entry(&self, coin: &WalletCoin) -> ListUnspent {
+        ListUnspent {
+            txid: coin.outpoint.txid,
+            vout: coin.outpoint.vout,
+            address: Address::from_script(&coin.output.script_pubkey, Network::Regtest)
+                .expect("wallet scripts have addresses")
+                .into_unchecked(),
+            label: None,
+            script_pubkey: coin.output.script_pubkey.to_hex_string(),
+            amount: coin.output.value,
+            confirmations: coin.confirmations as u32,
+            spendable: true,
+            solvable: true,
+            safe: coin.safe,
+        }
+    }
+}
+
+/// Adds the transactions of a block to the UTXO set and transaction index.
+fn index_block(
+    utxos: &mut HashMap<OutPoint, Coin>,
+    tx_index: &mut HashMap<Txid, (u64, usize)>,
+    block: &Block,
+    height: u64,
+) {
+    for (pos, tx) in block.txdata.iter().enumerate() {
+        let txid = tx.compute_txid();
+        if !tx.is_coinbase() {
+            for input in &tx.input {
+                utxos.remove(&input.previous_output);
+            }
+        }
+        // Like in `bitcoind`, the output of the genesis block is not spendable.
+        for (vout, output) in tx.output.iter().enumerate() {
+            if height > 0 && !output.script_pubkey.is_op_return() {
+                utxos.insert(
+                    OutPoint::new(txid, vout as u32),
+                    Coin {
+                        output: output.clone(),
+                        height,
+                        is_coinbase: tx.is_coinbase(),
+                    },
+                );
+            }
+        }
+        tx_index.insert(txid, (height, pos));
+    }
+}
+
+/// Running totals of [`State::block_stats`].
+#[derive(Debug, Default)]
+struct GetBlockStatsAccumulator {
+    /// Number of inputs, excluding the coinbase input.
+    ins: i64,
+
+    /// Number of outputs.
+    outs: i64,
+
+    /// Number of spendable outputs.
+    utxos_actual: i64,
+
+    /// Change in size of the UTXO set.
+    utxo_size_inc: i64,
+
+    /// Change in size of the UTXO set, ignoring unspendable outputs.
+    utxo_size_inc_actual: i64,
+
+    /// Total output value in satoshis, excluding the coinbase.
+    total_out: u64,
+
+    /// Total size, excluding the coinbase.
+    total_size: u64,
+
+    /// Total weight, excluding the coinbase.
+    total_weight: u64,
+
+    /// Number of segwit transactions.
+    sw_txs: u64,
+
+    /// Total size of segwit transactions.
+    sw_total_size: u64,
+
+    /// Total weight of segwit transactions.
+    sw_total_weight: u64,
+
+    /// Fee in satoshis of every transaction.
+    fees: Vec<u64>,
+
+    /// Size of every transaction.
+    sizes: Vec<u64>,
+
+    /// Fee rate in sat/vB and weight of every transaction.
+    fee_rates: Vec<(u64, u64)>,
+}
+
+/// Median of the values, rounded down, or 0 if there are none.
+fn truncated_median(mut values: Vec<u64>) -> u64 {
+    values.sort_unstable();
+    let len = values.len();
+    match len {
+        0 => 0,
+        _ if len.is_multiple_of(2) => (values[len / 2 - 1] + values[len / 2]) / 2,
+        _ => values[len / 2],
+    }
+}
+
+/// Fee rates at the 10th, 25th, 50th, 75th and 90th percentile weight unit.
+fn fee_rate_percentiles(mut fee_rates: Vec<(u64, u64)>) -> [u64; 5] {
+    let mut percentiles = [0; 5];
+    fee_rates.sort_unstable();
+    let total_weight = fee_rates.iter().map(|(_, weight)| weight).sum::<u64>() as f64;
+    let thresholds = [0.1, 0.25, 0.5, 0.75, 0.9].map(|share| total_weight * share);
+    let mut next = 0;
+    let mut cumulative_weight = 0;
+    for (fee_rate, weight) in &fee_rates {
+        cumulative_weight += weight;
+        while next < percentiles.len() && cumulative_weight as f64 >= thresholds[next] {
+            percentiles[next] = *fee_rate;
+            next += 1;
+        }
+    }
+    if let Some((fee_rate, _)) = fee_rates.last() {
+        percentiles[next..].fill(*fee_rate);
+    }
+    percentiles
+}
+
+/// Block subsidy at the given height on regtest.
+fn block_subsidy(height: u64) -> Amount {
+    let halvings = height / SUBSIDY_HALVING_INTERVAL;
+    if halvings >= 64 {
+        return Amount::ZERO;
+    }
+    Amount::from_sat(Amount::from_int_btc(50).to_sat() >> halvings)
+}
+
+/// Estimates the virtual size of a transaction once its inputs are signed, assuming they all
+/// spend P2WPKH outputs.
+fn estimate_vsize(tx: &Transaction) -> u64 {
+    let outputs = tx
+        .output
+        .iter()
+        .map(|output| 9 + output.script_pubkey.len() as u64)
+        .sum::<u64>();
+    11 + 68 * tx.input.len() as u64 + outputs
+}
+
+/// Name of the script type, as in the `type` field of `bitcoind` results.
+fn script_type(script_pubkey: &Script) -> &'static str {
+    if script_pubkey.is_p2wpkh() {
+        "witness_v0_keyhash"
+    } else if script_pubkey.is_p2wsh() {
+        "witness_v0_scripthash"
+    } else if script_pubkey.is_p2tr() {
+        "witness_v1_taproot"
+    } else if script_pubkey.is_p2pkh() {
+        "pubkeyhash"
+    } else if script_pubkey.is_p2sh() {
+        "scripthash"
+    } else if script_pubkey.is_op_return() {
+        "nulldata"
+    } else {
+        "nonstandard"
+    }
+}
+
+/// Decodes a transaction, as in the result of `decoderawtransaction`.
+fn decode_tx(tx: &Transaction) -> DecodeRawTransaction {
+    let coinbase = tx.is_coinbase();
+    DecodeRawTransaction {
+        txid: tx.compute_txid(),
+        hash: tx.compute_wtxid(),
+        size: tx.total_size() as u64,
+        vsize: tx.vsize() as u64,
+        weight: tx.weight().to_wu(),
+        version: tx.version.0,
+        locktime: tx.lock_time.to_consensus_u32(),
+        vin: tx
+            .input
+            .iter()
+            .map(|input| DecodedTxIn {
+                coinbase: coinbase.then(|| input.script_sig.clone()),
+                txid: (!coinbase).then_some(input.previous_output.txid),
+                vout: (!coinbase).then_some(input.previous_output.vout),
+                script_sig: (!coinbase).then(|| input.script_sig.clone()),
+                witness: input.witness.clone(),
+                sequence: input.sequence,
+            })
+            .collect(),
+        vout: tx
+            .output
+            .iter()
+            .zip(0..)
+            .map(|(output, n)| DecodedTxOut {
+                value: output.value,
+                n,
+                script_pubkey: decode_script_pubkey(&output.script_pubkey),
+            })
+            .collect(),
+    }
+}
+
+/// Decodes an output script, as in the `scriptPubKey` objects of `bitcoind` results.
+fn decode_script_pubkey(script_pubkey: &Script) -> DecodedScriptPubKey {
+    DecodedScriptPubKey {
+        script: script_pubkey.to_owned(),
+        asm: script_pubkey.to_asm_string(),
+        desc: None,
+        script_type: script_type(script_pubkey).to_string(),
+        address: Address::from_script(script_pubkey, Network::Regtest)
+            .ok()
+            .map(|address| address.into_unchecked()),
+    }
+}
+
+/// Builds an unsigned transaction from `createrawtransaction`-style inputs and outputs.
+fn build_transaction(
+    inputs: &[CreateRawTransactionInput],
+    outputs: &[CreateRawTransactionOutput],
+    locktime: u32,
+    replaceable: bool,
+) -> ClientResult<Transaction> {
+    let sequence = if replaceable {
+        Sequence::ENABLE_RBF_NO_LOCKTIME
+    } else if locktime != 0 {
+        Sequence::ENABLE_LOCKTIME_NO_RBF
+    } else {
+        Sequence::MAX
+    };
+    let input = inputs
+        .iter()
+        .map(|input| {
+            let txid = input.txid.parse::<Txid>().map_err(|_| {
+                ClientError::rpc(
+                    RpcErrorCode::InvalidParameter,
+                    "txid must be hexadecimal string",
+                )
+            })?;
+            Ok(TxIn {
+                previous_output: OutPoint::new(txid, input.vout),
+                sequence,
+                ..Default::default()
+            })
+        })
+        .collect::<ClientResult<_>>()?;
+    let output = outputs
+        .iter()
+        .map(|output| match output {
+            CreateRawTransactionOutput::AddressAmount { address, amount } => {
+                let script_pubkey = address
+                    .parse::<Address<_>>()
+                    .ok()
+                    .and_then(|address| address.require_network(Network::Regtest).ok())
+                    .ok_or_else(|| {
+                        ClientError::rpc(
+                            RpcErrorCode::InvalidAddressOrKey,
+                            format!("Invalid Bitcoin address: {address}"),
+                        )
+                    })?
+                    .script_pubkey();
+                let value = Amount::from_btc(*amount)
+                    .map_err(|_| ClientError::rpc(RpcErrorCode::TypeError, "Invalid amount"))?;
+                Ok(TxOut {
+                    value,
+                    script_pubkey,
+                })
+            }
+            CreateRawTransactionOutput::Data { data } => {
+                let data = Vec::<u8>::from_hex(data)
+                    .ok()
+                    .and_then(|data| PushBytesBuf::try_from(data).ok())
+                    .ok_or_else(|| {
+                        ClientError::rpc(
+                            RpcErrorCode::InvalidParameter,
+                            "Data must be hexadecimal string",
+                        )
+                    })?;
+                Ok(TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: ScriptBuf::new_op_return(data),
+                })
+            }
+        })
+        .collect::<ClientResult<_>>()?;
+    Ok(Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::from_consensus(locktime),
+        input,
+        output,
+    })
+}
+
+/// Builds an unsigned PSBT with the witness UTXOs of the inputs that are known.
+fn unsigned_psbt(tx: Transaction, prevouts: Vec<Option<TxOut>>) -> ClientResult<Psbt> {
+    let mut psbt = Psbt::from_unsigned_tx(tx).map_err(|e| ClientError::Other(e.to_string()))?;
+    for (input, prevout) in psbt.inputs.iter_mut().zip(prevouts) {
+        input.witness_utxo = prevout;
+    }
+    Ok(psbt)
+}
+
+/// Returns the script an `addr()` or `raw()` descriptor of `scantxoutset` stands for, along
+/// with the descriptor without its checksum.
+fn scan_object_script(scan_object: &ScanObject) -> ClientResult<(ScriptBuf, String)> {
+    let unsupported = || {
+        ClientError::rpc(
+            RpcErrorCode::InvalidAddressOrKey,
+            "Only addr() and raw() descriptors are supported",
+        )
+    };
+    let ScanObject::Descriptor(descriptor) = scan_object else {
+        return Err(unsupported());
+    };
+    let desc = descriptor.split('#').next().unwrap_or_default();
+    let script_pubkey = if let Some(address) = desc
+        .strip_prefix("addr(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        address
+            .parse::<Address<_>>()
+            .ok()
+            .and_then(|address| address.require_network(Network::Regtest).ok())
+            .ok_or_else(|| {
+                ClientError::rpc(
+                    RpcErrorCode::InvalidAddressOrKey,
+                    format!("Address is not valid: {address}"),
+                )
+            })?
+            .script_pubkey()
+    } else if let Some(hex) = desc
+        .strip_prefix("raw(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        ScriptBuf::from_hex(hex).map_err(|_| {
+            ClientError::rpc(
+                RpcErrorCode::InvalidAddressOrKey,
+                format!("Raw script is not hex: {hex}"),
+            )
+        })?
+    } else {
+        return Err(unsupported());
+    };
+    Ok((script_pubkey, desc.to_string()))
+}
+
+/// Splits a descriptor from its checksum and checks the checksum if there is one, as in BIP 380.
+///
+/// Returns the descriptor without its checksum and the checksum it should have.
+fn check_descriptor(descriptor: &str) -> ClientResult<(&str, String)> {
+    const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+    const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+    const GENERATOR: [u64; 5] = [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ];
+    let invalid = |message: String| ClientError::rpc(RpcErrorCode::InvalidAddressOrKey, message);
+
+    let (desc, provided) = match descriptor.split_once('#') {
+        Some((desc, checksum)) => (desc, Some(checksum)),
+        None => (descriptor, None),
+    };
+    let mut checksum = 1u64;
+    let mut polymod = |value: u64| {
+        let top = checksum >> 35;
+        checksum = (checksum & 0x7ffffffff) << 5 ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    };
+    let mut groups = Vec::new();
+    for c in desc.chars() {
+        let position = INPUT_CHARSET
+            .find(c)
+            .ok_or_else(|| invalid(format!("Invalid character found: {c}")))?
+            as u64;
+        polymod(position & 31);
+        groups.push(position >> 5);
+        if groups.len() == 3 {
+            polymod(groups[0] * 9 + groups[1] * 3 + groups[2]);
+            groups.clear();
+        }
+    }
+    match groups[..] {
+        [first] => polymod(first),
+        [first, second] => polymod(first * 3 + second),
+        _ => {}
+    }
+    for _ in 0..8 {
+        polymod(0);
+    }
+    checksum ^= 1;
+    let checksum = (0..8)
+        .map(|i| CHECKSUM_CHARSET[((checksum >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect::<String>();
+
+    match provided {
+        Some(provided) if provided != checksum => Err(invalid(format!(
+            "Provided checksum '{provided}' does not match computed checksum '{checksum}'"
+        ))),
+        _ => Ok((desc, checksum)),
+    }
+}
+
+/// Checks a confirmation target of `estimatesmartfee` or `estimaterawfee`.
+fn check_conf_target(conf_target: u16) -> ClientResult<()> {
+    match conf_target {
+        1..=1008 => Ok(()),
+        _ => Err(ClientError::rpc(
+            RpcErrorCode::InvalidParameter,
+            "Invalid conf_target, must be between 1 and 1008",
+        )),
+    }
+}
+
+/// Converts a fee rate in sat/vB as given to `bitcoind` into a [`FeeRate`].
+fn fee_rate_from_sat_per_vb(sat_per_vb: f64) -> FeeRate {
+    FeeRate::from_sat_per_kwu((sat_per_vb * 250.0).round() as u64)
+}
+
+impl Reader for MockBitcoind {
+    async fn estimate_smart_fee(&self, _conf_target: u16) -> ClientResult<u64> {
+        self.with_state(&["estimatesmartfee"], |state| {
+            Ok(state.fee_rate.to_sat_per_vb_ceil())
+        })
+    }
+
+    async fn estimate_smart_fee_with_mode(
+        &self,
+        conf_target: u16,
+        _mode: EstimateMode,
+    ) -> ClientResult<SmartFeeEstimate> {
+        self.with_state(&["estimatesmartfee"], |state| {
+            check_conf_target(conf_target)?;
+            Ok(SmartFeeEstimate {
+                fee_rate: Some(state.fee_rate),
+                errors: Vec::new(),
+                blocks: conf_target.max(2),
+            })
+        })
+    }
+
+    async fn estimate_raw_fee(
+        &self,
+        conf_target: u16,
+        threshold: Option<f64>,
+    ) -> ClientResult<EstimateRawFee> {
+        self.with_state(&["estimaterawfee"], |state| {
+            check_conf_target(conf_target)?;
+            if threshold.is_some_and(|threshold| !(0.0..=1.0).contains(&threshold)) {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::InvalidParameter,
+                    "Invalid threshold",
+                ));
+            }
+            // Max target, scale and decay of the short, medium and long horizons.
+            let horizon = |max_target: u16, scale: u32, decay: f64| {
+                (conf_target <= max_target).then(|| RawFeeEstimate {
+                    fee_rate: Some(state.fee_rate),
+                    decay,
+                    scale,
+                    pass: None,
+                    fail: None,
+                    errors: Vec::new(),
+                })
+            };
+            Ok(EstimateRawFee {
+                short: horizon(12, 1, 0.962),
+                medium: horizon(48, 2, 0.9952),
+                long: horizon(1008, 24, 0.99931),
+            })
+        })
+    }
+
+    async fn get_block_header(&self, hash: &BlockHash) -> ClientResult<Header> {
+        self.with_state(&["getblockheader"], |state| Ok(*state.header(hash)?.0))
+    }
+
+    async fn get_block_header_verbose(
+        &self,
+        hash: &BlockHash,
+    ) -> ClientResult<GetBlockHeaderVerbose> {
+        self.with_state(&["getblockheader"], |state| state.header_verbose(hash))
+    }
+
+    async fn get_block(&self, hash: &BlockHash) -> ClientResult<Block> {
+        self.with_state(&["getblock"], |state| Ok(state.block(hash)?.0.clone()))
+    }
+
+    async fn get_block_verbose(
+        &self,
+        hash: &BlockHash,
+        include_prevouts: bool,
+    ) -> ClientResult<GetBlockVerbose> {
+        self.with_state(&["getblock"], |state| {
+            state.block_verbose(hash, include_prevouts)
+        })
+    }
+
+    async fn get_block_stats(
+        &self,
+        target: BlockStatsTarget,
+        fields: Option<&[BlockStatsField]>,
+    ) -> ClientResult<GetBlockStats> {
+        self.with_state(&["getblockstats"], |state| {
+            let hash = match target {
+                BlockStatsTarget::Hash(hash) => hash,
+                BlockStatsTarget::Height(height) if height > state.tip_height() => {
+                    return Err(ClientError::rpc(
+                        RpcErrorCode::InvalidParameter,
+                        format!(
+                            "Target block height {height} after current tip {}",
+                            state.tip_height()
+                        ),
+                    ))
+                }
+                BlockStatsTarget::Height(height) => state.block_hash(height)?,
+            };
+            let mut stats = state.block_stats(&hash)?;
+            if let Some(fields) = fields {
+                let fields = fields
+                    .iter()
+                    .map(|field| serde_json::to_value(field).expect("fields serialize"))
+                    .collect::<Vec<_>>();
+                stats.retain(|name, _| fields.iter().any(|field| field == name.as_str()));
+            }
+            serde_json::from_value(Value::Object(stats))
+                .map_err(|e| ClientError::Parse(e.to_string()))
+        })
+    }
+
+    async fn get_block_height(&self, hash: &BlockHash) -> ClientResult<u64> {
+        self.with_state(&["getblock"], |state| Ok(state.block(hash)?.1))
+    }
+
+    async fn get_block_header_at(&self, height: u64) -> ClientResult<Header> {
+        self.with_state(&["getblockhash", "getblockheader"], |state| {
+            Ok(state.block_at(height)?.header)
+        })
+    }
+
+    async fn get_block_at(&self, height: u64) -> ClientResult<Block> {
+        self.with_state(&["getblockhash", "getblock"], |state| {
+            Ok(state.block_at(height)?.clone())
+        })
+    }
+
+    async fn get_block_count(&self) -> ClientResult<u64> {
+        self.with_state(&["getblockcount"], |state| Ok(state.tip_height()))
+    }
+
+    async fn get_block_hash(&self, height: u64) -> ClientResult<BlockHash> {
+        self.with_state(&["getblockhash"], |state| state.block_hash(height))
+    }
+
+    async fn get_blockchain_info(&self) -> ClientResult<GetBlockchainInfo> {
+        self.with_state(&["getblockchaininfo"], |state| {
+            let tip = state.tip();
+            let chain_work = state
+                .chain
+                .iter()
+                .map(|hash| state.blocks[hash].0.header.work())
+                .fold(Work::from_be_bytes([0; 32]), |total, work| total + work);
+            let size_on_disk = state
+                .chain
+                .iter()
+                .map(|hash| state.blocks[hash].0.total_size() as u64)
+                .sum();
+            Ok(GetBlockchainInfo {
+                chain: "regtest".to_string(),
+                blocks: state.tip_height(),
+                headers: state.tip_height(),
+                best_block_hash: tip.block_hash().to_string(),
+                difficulty: tip.header.difficulty_float(),
+                median_time: state.median_time_past() as u64,
+                verification_progress: 1.0,
+                initial_block_download: false,
+                chain_work: chain_work.to_be_bytes().to_lower_hex_string(),
+                size_on_disk,
+                pruned: false,
+                prune_height: None,
+                automatic_pruning: None,
+                prune_target_size: None,
+            })
+        })
+    }
+
+    async fn get_current_timestamp(&self) -> ClientResult<u32> {
+        self.with_state(&["getbestblockhash", "getblock"], |state| {
+            Ok(state.tip().header.time)
+        })
+    }
+
+    async fn get_raw_mempool(&self) -> ClientResult<Vec<Txid>> {
+        self.with_state(&["getrawmempool"], |state| {
+            Ok(state.mempool.iter().map(|entry| entry.txid).collect())
+        })
+    }
+
+    async fn get_mempool_info(&self) -> ClientResult<GetMempoolInfo> {
+        self.with_state(&["getmempoolinfo"], |state| {
+            Ok(GetMempoolInfo {
+                loaded: true,
+                size: state.mempool.len(),
+                bytes: state.mempool.iter().map(|entry| entry.tx.vsize()).sum(),
+                usage: state
+                    .mempool
+                    .iter()
+                    .map(|entry| entry.tx.total_size())
+                    .sum(),
+                maxmempool: 300_000_000,
+                mempoolminfee: FeeRate::BROADCAST_MIN,
+                minrelaytxfee: FeeRate::BROADCAST_MIN,
+                unbroadcastcount: 0,
+            })
+        })
+    }
+
+    async fn get_raw_mempool_verbose(&self) -> ClientResult<BTreeMap<Txid, GetMempoolEntry>> {
+        self.with_state(&["getrawmempool"], |state| {
+            Ok(state
+                .mempool
+                .iter()
+                .map(|entry| (entry.txid, state.mempool_entry_info(entry)))
+                .collect())
+        })
+    }
+
+    async fn get_mempool_entry(&self, txid: &Txid) -> ClientResult<GetMempoolEntry> {
+        self.with_state(&["getmempoolentry"], |state| {
+            Ok(state.mempool_entry_info(state.mempool_entry(txid)?))
+        })
+    }
+
+    async fn get_mempool_ancestors(
+        &self,
+        txid: &Txid,
+    ) -> ClientResult<BTreeMap<Txid, GetMempoolEntry>> {
+        self.with_state(&["getmempoolancestors"], |state| {
+            let entry = state.mempool_entry(txid)?;
+            Ok(state
+                .mempool_closure(entry, false)
+                .into_iter()
+                .map(|entry| (entry.txid, state.mempool_entry_info(entry)))
+                .collect())
+        })
+    }
+
+    async fn get_mempool_descendants(
+        &self,
+        txid: &Txid,
+    ) -> ClientResult<BTreeMap<Txid, GetMempoolEntry>> {
+        self.with_state(&["getmempooldescendants"], |state| {
+            let entry = state.mempool_entry(txid)?;
+            Ok(state
+                .mempool_closure(entry, true)
+                .into_iter()
+                .map(|entry| (entry.txid, state.mempool_entry_info(entry)))
+                .collect())
+        })
+    }
+
+    async fn get_raw_transaction_verbosity_zero(
+        &self,
+        txid: &Txid,
+    ) -> ClientResult<GetRawTransactionVerbosityZero> {
+        self.with_state(&["getrawtransaction"], |state| {
+            let found = state
+                .find_tx(txid)
+                .ok_or_else(|| no_such_transaction(txid))?;
+            Ok(GetRawTransactionVerbosityZero(serialize_hex(found.tx)))
+        })
+    }
+
+    async fn get_raw_transaction_verbosity_one(
+        &self,
+        txid: &Txid,
+    ) -> ClientResult<GetRawTransactionVerbosityOne> {
+        self.with_state(&["getrawtransaction"], |state| {
+            let found = state
+                .find_tx(txid)
+                .ok_or_else(|| no_such_transaction(txid))?;
+            let tx = found.tx;
+            let block = found
+                .position
+                .map(|(height, _)| (state.chain[height as usize], state.confirmations(height)));
+            Ok(GetRawTransactionVerbosityOne {
+                in_active_chain: block.map(|_| true),
+                transaction: tx.clone(),
+                txid: *txid,
+                hash: tx.compute_wtxid(),
+                size: tx.total_size(),
+                vsize: tx.vsize(),
+                version: tx.version.0 as u32,
+                locktime: tx.lock_time.to_consensus_u32(),
+                blockhash: block.map(|(hash, _)| hash),
+                confirmations: block.map(|(_, confirmations)| confirmations as u32),
+                time: block.map(|_| found.time as usize),
+                blocktime: block.map(|_| found.time as usize),
+            })
+        })
+    }
+
+    async fn get_tx_out(
+        &self,
+        txid: &Txid,
+        vout: u32,
+        include_mempool: bool,
+    ) -> ClientResult<GetTxOut> {
+        self.with_state(&["gettxout"], |state| {
+            let outpoint = OutPoint::new(*txid, vout);
+            let coin = match state.utxos.get(&outpoint) {
+                Some(_) if include_mempool && state.mempool_spender(&outpoint).is_some() => None,
+                Some(coin) => Some(coin.clone()),
+                None if include_mempool => state
+                    .mempool
+                    .iter()
+                    .find(|entry| entry.txid == *txid)
+                    .and_then(|entry| entry.tx.output.get(vout as usize))
+                    .filter(|_| state.mempool_spender(&outpoint).is_none())
+                    .map(|output| Coin {
+                        output: output.clone(),
+                        height: state.tip_height() + 1,
+                        is_coinbase: false,
+                    }),
+                None => None,
+            };
+            // `bitcoind` returns `null` for spent or unknown outputs.
+            let coin = coin.ok_or_else(|| ClientError::Other("Empty data received".to_string()))?;
+            let script_pubkey = &coin.output.script_pubkey;
+            Ok(GetTxOut {
+                best_block: state.tip().block_hash().to_string(),
+                confirmations: state.confirmations(coin.height) as u32,
+                value: coin.output.value.to_btc(),
+                script_pubkey: Some(ScriptPubkey {
+                    asm: script_pubkey.to_asm_string(),
+                    hex: script_pubkey.to_hex_string(),
+                    req_sigs: 1,
+                    type_: script_type(script_pubkey).to_string(),
+                    address: Address::from_script(script_pubkey, Network::Regtest)
+                        .ok()
+                        .map(|address| address.to_string()),
+                }),
+                coinbase: coin.is_coinbase,
+            })
+        })
+    }
+
+    async fn get_tx_out_set_info(
+        &self,
+        _hash_type: Option<TxOutSetHashType>,
+    ) -> ClientResult<GetTxOutSetInfo> {
+        self.with_state(&["gettxoutsetinfo"], |state| {
+            let transactions = state
+                .utxos
+                .keys()
+                .map(|outpoint| outpoint.txid)
+                .collect::<HashSet<_>>();
+            Ok(GetTxOutSetInfo {
+                height: state.tip_height(),
+                best_block: state.tip().block_hash(),
+                tx_outs: state.utxos.len() as u64,
+                bogo_size: state
+                    .utxos
+                    .values()
+                    .map(|coin| 50 + coin.output.script_pubkey.len() as u64)
+                    .sum(),
+                hash_serialized_3: None,
+                muhash: None,
+                transactions: Some(transactions.len() as u64),
+                disk_size: None,
+                total_amount: state.utxos.values().map(|coin| coin.output.value).sum(),
+                total_unspendable_amount: None,
+            })
+        })
+    }
+
+    async fn scan_tx_out_set_start(
+        &self,
+        scan_objects: &[ScanObject],
+    ) -> ClientResult<ScanTxOutSet> {
+        self.with_state(&["scantxoutset"], |state| {
+            let scripts = scan_objects
+                .iter()
+                .map(scan_object_script)
+                .collect::<ClientResult<Vec<_>>>()?;
+            let mut unspents = state
+                .utxos
+                .iter()
+                .filter_map(|(outpoint, coin)| {
+                    let (_, desc) = scripts
+                        .iter()
+                        .find(|(script_pubkey, _)| *script_pubkey == coin.output.script_pubkey)?;
+                    Some(ScanTxOutSetUnspent {
+                        txid: outpoint.txid,
+                        vout: outpoint.vout,
+                        script_pubkey: coin.output.script_pubkey.clone(),
+                        desc: desc.clone(),
+                        amount: coin.output.value,
+                        coinbase: coin.is_coinbase,
+                        height: coin.height,
+                    })
+                })
+                .collect::<Vec<_>>();
+            unspents.sort_by_key(|unspent| (unspent.height, unspent.outpoint()));
+            Ok(ScanTxOutSet {
+                success: true,
+                tx_outs: state.utxos.len() as u64,
+                height: state.tip_height(),
+                best_block: state.tip().block_hash(),
+                total_amount: unspents.iter().map(|unspent| unspent.amount).sum(),
+                unspents,
+            })
+        })
+    }
+
+    async fn scan_tx_out_set_status(&self) -> ClientResult<Option<ScanTxOutSetStatus>> {
+        // Scans complete within the call, so none is ever running.
+        self.with_state(&["scantxoutset"], |_| Ok(None))
+    }
+
+    async fn scan_tx_out_set_abort(&self) -> ClientResult<bool> {
+        self.with_state(&["scantxoutset"], |_| Ok(false))
+    }
+
+    async fn network(&self) -> ClientResult<Network> {
+        self.with_state(&["getblockchaininfo"], |_| Ok(Network::Regtest))
+    }
+
+    async fn get_block_filter(&self, hash: &BlockHash) -> ClientResult<GetBlockFilter> {
+        self.with_state(&["getblockfilter"], |state| {
+            state.filters.get(hash).cloned().ok_or_else(|| {
+                ClientError::rpc(RpcErrorCode::InvalidAddressOrKey, "Block not found")
+            })
+        })
+    }
+
+    async fn get_tx_out_proof(
+        &self,
+        txids: &[Txid],
+        block_hash: Option<&BlockHash>,
+    ) -> ClientResult<MerkleBlock> {
+        self.with_state(&["gettxoutproof"], |state| {
+            let first = txids.first().ok_or_else(|| {
+                ClientError::rpc(
+                    RpcErrorCode::InvalidParameter,
+                    "Parameter 'txids' cannot be empty",
+                )
+            })?;
+            let mut seen = HashSet::new();
+            if let Some(duplicate) = txids.iter().find(|txid| !seen.insert(*txid)) {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::InvalidParameter,
+                    format!("Invalid parameter, duplicated txid: {duplicate}"),
+                ));
+            }
+            let block = match block_hash {
+                Some(hash) => &state.block(hash)?.0,
+                None => {
+                    let (height, _) = state.tx_index.get(first).ok_or_else(|| {
+                        ClientError::rpc(
+                            RpcErrorCode::InvalidAddressOrKey,
+                            "Transaction not yet in block",
+                        )
+                    })?;
+                    state.block_at(*height)?
+                }
+            };
+            let in_block = block
+                .txdata
+                .iter()
+                .map(Transaction::compute_txid)
+                .collect::<HashSet<_>>();
+            if !txids.iter().all(|txid| in_block.contains(txid)) {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::InvalidAddressOrKey,
+                    "Not all transactions found in specified or retrieved block",
+                ));
+            }
+            Ok(MerkleBlock::from_block_with_predicate(block, |txid| {
+                txids.contains(txid)
+            }))
+        })
+    }
+
+    async fn verify_tx_out_proof(&self, proof: &MerkleBlock) -> ClientResult<Vec<Txid>> {
+        self.with_state(&["verifytxoutproof"], |state| {
+            let mut matches = Vec::new();
+            let mut indexes = Vec::new();
+            if proof.extract_matches(&mut matches, &mut indexes).is_err() {
+                return Ok(Vec::new());
+            }
+            let hash = proof.header.block_hash();
+            match state.blocks.get(&hash) {
+                Some((_, height)) if state.chain.get(*height as usize) == Some(&hash) => {
+                    Ok(matches)
+                }
+                _ => Err(ClientError::rpc(
+                    RpcErrorCode::InvalidAddressOrKey,
+                    "Block not found in chain",
+                )),
+            }
+        })
+    }
+
+    async fn get_chain_tips(&self) -> ClientResult<Vec<ChainTip>> {
+        self.with_state(&["getchaintips"], |state| {
+            let headers = state
+                .blocks
+                .iter()
+                .map(|(hash, (block, height))| (hash, &block.header, *height))
+                .chain(
+                    state
+                        .headers
+                        .iter()
+                        .map(|(hash, (header, height))| (hash, header, *height)),
+                )
+                .collect::<Vec<_>>();
+            let parents = headers
+                .iter()
+                .map(|(_, header, _)| header.prev_blockhash)
+                .collect::<HashSet<_>>();
+            let mut tips = headers
+                .into_iter()
+                .filter(|(hash, _, _)| !parents.contains(*hash))
+                .map(|(hash, _, height)| {
+                    let fork_height = state
+                        .ancestors(hash)
+                        .zip((0..=height).rev())
+                        .find(|(header, height)| {
+                            state.chain.get(*height as usize) == Some(&header.block_hash())
+                        })
+                        .map_or(0, |(_, height)| height);
+                    // Headers-only blocks have no data, so neither do their descendants.
+                    let headers_only = state
+                        .ancestors(hash)
+                        .any(|header| state.headers.contains_key(&header.block_hash()));
+                    ChainTip {
+                        height,
+                        hash: *hash,
+                        branch_len: height - fork_height,
+                        status: if fork_height == height {
+                            ChainTipStatus::Active
+                        } else if headers_only {
+                            ChainTipStatus::HeadersOnly
+                        } else {
+                            ChainTipStatus::ValidFork
+                        },
+                    }
+                })
+                .collect::<Vec<_>>();
+            // `bitcoind` sorts the tips by descending height.
+            tips.sort_by_key(|tip| std::cmp::Reverse(tip.height));
+            Ok(tips)
+        })
+    }
+
+    async fn decode_raw_transaction(&self, tx: &Transaction) -> ClientResult<DecodeRawTransaction> {
+        self.with_state(&["decoderawtransaction"], |_| Ok(decode_tx(tx)))
+    }
+
+    async fn decode_script(&self, script: &Script) -> ClientResult<DecodeScript> {
+        self.with_state(&["decodescript"], |_| {
+            let address = |script: &Script| {
+                Address::from_script(script, Network::Regtest)
+                    .ok()
+                    .map(|address| address.into_unchecked())
+            };
+            let p2sh = |script: &Script| {
+                Address::p2sh(script, Network::Regtest)
+                    .ok()
+                    .map(|address| address.into_unchecked())
+            };
+            let wrappable = !script.is_p2sh() && !script.is_witness_program();
+            let segwit = wrappable.then(|| {
+                let script_pubkey = ScriptBuf::new_p2wsh(&script.wscript_hash());
+                DecodeScriptSegwit {
+                    asm: script_pubkey.to_asm_string(),
+                    desc: None,
+                    script_type: script_type(&script_pubkey).to_string(),
+                    address: address(&script_pubkey),
+                    p2sh_segwit: p2sh(&script_pubkey),
+                    script: script_pubkey,
+                }
+            });
+            Ok(DecodeScript {
+                asm: script.to_asm_string(),
+                desc: None,
+                script_type: script_type(script).to_string(),
+                address: address(script),
+                p2sh: wrappable.then(|| p2sh(script)).flatten(),
+                segwit,
+            })
+        })
+    }
+
+    async fn get_descriptor_info(&self, descriptor: &str) -> ClientResult<GetDescriptorInfo> {
+        self.with_state(&["getdescriptorinfo"], |_| {
+            let (desc, checksum) = check_descriptor(descriptor)?;
+            Ok(GetDescriptorInfo {
+                descriptor: format!("{desc}#{checksum}"),
+                checksum,
+                is_range: desc.contains('*'),
+                is_solvable: !desc.starts_with("addr(") && !desc.starts_with("raw("),
+                has_private_keys: desc.contains("prv"),
+            })
+        })
+    }
+
+    async fn derive_addresses(
+        &self,
+        descriptor: &str,
+        range: Option<(u32, u32)>,
+    ) -> ClientResult<Vec<Address>> {
+        self.with_state(&["deriveaddresses"], |_| {
+            if !descriptor.contains('#') {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::InvalidAddressOrKey,
+                    "Missing checksum",
+                ));
+            }
+            check_descriptor(descriptor)?;
+            if range.is_some() {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::InvalidParameter,
+                    "Range should not be specified for an un-ranged descriptor",
+                ));
+            }
+            let (script_pubkey, _) =
+                scan_object_script(&ScanObject::Descriptor(descriptor.to_string()))?;
+            let address = Address::from_script(&script_pubkey, Network::Regtest).map_err(|_| {
+                ClientError::rpc(
+                    RpcErrorCode::InvalidAddressOrKey,
+                    "Descriptor does not have a corresponding address",
+                )
+            })?;
+            Ok(vec![address])
+        })
+    }
+}
+
+/// The error `bitcoind` returns for unknown transactions.
+fn no_such_transaction(txid: &Txid) -> ClientError {
+    ClientError::rpc(
+        RpcErrorCode::InvalidAddressOrKey,
+        format!("No such mempool or blockchain transaction: {txid}"),
+    )
+}
+
+impl Broadcaster for MockBitcoind {
+    async fn send_raw_transaction(&self, tx: &Transaction) -> ClientResult<Txid> {
+        self.with_state(&["sendrawtransaction"], |state| match state.accept(tx) {
+            // Same as the `Client`: already confirmed transactions count as sent.
+            Err(e) if e.rpc_error_code() == Some(RpcErrorCode::VerifyAlreadyInUtxoSet) => {
+                Ok(tx.compute_txid())
+            }
+            result => result,
+        })
+    }
+
+    async fn test_mempool_accept(&self, tx: &Transaction) -> ClientResult<Vec<TestMempoolAccept>> {
+        self.with_state(&["testmempoolaccept"], |state| {
+            let reject_reason = match state.check_transaction(tx, true) {
+                Ok(_) => None,
+                Err(e) if e.rpc_error_code() == Some(RpcErrorCode::VerifyAlreadyInUtxoSet) => {
+                    Some("txn-already-known".to_string())
+                }
+                Err(ClientError::Server(_, reason)) => Some(reason),
+                Err(e) => return Err(e),
+            };
+            Ok(vec![TestMempoolAccept {
+                txid: tx.compute_txid(),
+                reject_reason,
+            }])
+        })
+    }
+
+    async fn submit_package(&self, txs: &[Transaction]) -> ClientResult<SubmitPackage> {
+        self.with_state(&["submitpackage"], |state| {
+            // Children may pay for their parents, so the minimum relay fee is checked for the
+            // package as a whole.
+            let mempool = state.mempool.clone();
+            let results = txs
+                .iter()
+                .map(|tx| {
+                    let (fee, replaces) = state.check_transaction(tx, false)?;
+                    state.remove_from_mempool(replaces);
+                    state.mempool.push(MempoolEntry {
+                        txid: tx.compute_txid(),
+                        tx: tx.clone(),
+                        fee,
+                        height: state.tip_height(),
+                        time: state.tip().header.time as u64,
+                    });
+                    Ok(fee)
+                })
+                .collect::<Vec<ClientResult<Amount>>>();
+            let (fees, vsize) = txs
+                .iter()
+                .zip(&results)
+                .filter_map(|(tx, result)| Some((*result.as_ref().ok()?, tx.vsize() as u64)))
+                .fold((Amount::ZERO, 0), |(fees, vsize), (fee, tx_vsize)| {
+                    (fees + fee, vsize + tx_vsize)
+                });
+            let min_fee = FeeRate::BROADCAST_MIN.fee_vb(vsize).unwrap_or(Amount::MAX);
+            let results = if fees < min_fee {
+                state.mempool = mempool;
+                results
+                    .into_iter()
+                    .map(|result| {
+                        result.and(Err(ClientError::rpc(
+                            RpcErrorCode::VerifyRejected,
+                            "min relay fee not met",
+                        )))
+                    })
+                    .collect()
+            } else {
+                results
+            };
+
+            let mut tx_results = BTreeMap::new();
+            for (tx, result) in txs.iter().zip(results) {
+                let (fees, error) = match result {
+                    Ok(fee) => (
+                        Some(SubmitPackageTxResultFees {
+                            base_fee: fee.to_btc(),
+                            effective_fee_rate: None,
+                            effective_includes: None,
+                        }),
+                        None,
+                    ),
+                    Err(ClientError::Server(_, reason)) => (None, Some(reason)),
+                    Err(e) => return Err(e),
+                };
+                tx_results.insert(
+                    tx.compute_wtxid().to_string(),
+                    SubmitPackageTxResult {
+                        txid: tx.compute_txid().to_string(),
+                        other_wtxid: None,
+                        vsize: tx.vsize() as i64,
+                        fees,
+                        error,
+                    },
+                );
+            }
+            let package_msg = if tx_results.values().all(|result| result.error.is_none()) {
+                "success"
+            } else {
+                "transaction failed"
+            };
+            Ok(SubmitPackage {
+                package_msg: package_msg.to_string(),
+                tx_results,
+                replaced_transactions: Vec::new(),
+            })
+        })
+    }
+}
+
+impl Wallet for MockBitcoind {
+    async fn get_new_address(&self) -> ClientResult<Address> {
+        self.with_state(&["getnewaddress"], |state| {
+            Ok(state.wallet.new_address(false))
+        })
+    }
+
+    async fn get_transaction(&self, txid: &Txid) -> ClientResult<GetTransaction> {
+        self.with_state(&["gettransaction"], |state| {
+            let not_found = || {
+                ClientError::rpc(
+                    RpcErrorCode::InvalidAddressOrKey,
+                    "Invalid or non-wallet transaction id",
+                )
+            };
+            let found = state.find_tx(txid).ok_or_else(not_found)?;
+            let tx = found.tx;
+            let confirmations = found
+                .position
+                .map_or(0, |(height, _)| state.confirmations(height));
+            let debit = state.debit(tx);
+            let credit = state.credit(tx);
+            if debit == Amount::ZERO && credit == Amount::ZERO {
+                return Err(not_found());
+            }
+
+            let value_out = tx.output.iter().map(|output| output.value).sum::<Amount>();
+            let amount = if debit > Amount::ZERO {
+                credit.to_signed().unwrap_or(SignedAmount::MAX)
+                    - value_out.to_signed().unwrap_or(SignedAmount::MAX)
+            } else {
+                credit.to_signed().unwrap_or(SignedAmount::MAX)
+            };
+            let details = state
+                .wallet_entries(tx, confirmations)
+                .into_iter()
+                .map(|entry| GetTransactionDetail {
+                    address: entry
+                        .address
+                        .map(|address| address.to_string())
+                        .unwrap_or_default(),
+                    category: match entry.category {
+                        TransactionCategory::Send => GetTransactionDetailCategory::Send,
+                        TransactionCategory::Receive => GetTransactionDetailCategory::Receive,
+                        TransactionCategory::Generate => GetTransactionDetailCategory::Generate,
+                        TransactionCategory::Immature => GetTransactionDetailCategory::Immature,
+                        TransactionCategory::Orphan => GetTransactionDetailCategory::Orphan,
+                    },
+                    amount: entry.amount.to_btc(),
+                    label: None,
+                    vout: entry.vout,
+                    fee: entry.fee.map(SignedAmount::to_btc),
+                    abandoned: (entry.category == TransactionCategory::Send).then_some(false),
+                })
+                .collect();
+            let replaceable = found.position.is_none() && tx.is_explicitly_rbf();
+            Ok(GetTransaction {
+                amount,
+                confirmations,
+                generated: tx.is_coinbase().then_some(true),
+                trusted: (confirmations == 0).then_some(debit > Amount::ZERO),
+                blockhash: found
+                    .position
+                    .map(|(height, _)| state.chain[height as usize].to_string()),
+                blockheight: found.position.map(|(height, _)| height),
+                blockindex: found.position.map(|(_, pos)| pos as u32),
+                blocktime: found.position.map(|_| found.time),
+                txid: *txid,
+                wtxid: tx.compute_wtxid().to_string(),
+                walletconflicts: Vec::new(),
+                replaced_by_txid: None,
+                replaces_txid: None,
+                comment: None,
+                to: None,
+                time: found.time,
+                timereceived: found.time,
+                bip125_replaceable: if replaceable { "yes" } else { "no" }.to_string(),
+                details,
+                hex: tx.clone(),
+            })
+        })
+    }
+
+    async fn get_utxos(&self) -> ClientResult<Vec<ListUnspent>> {
+        self.with_state(&["listunspent"], |state| {
+            Ok(state
+                .wallet_coins()
+                .iter()
+                .filter(|coin| coin.confirmations >= 1)
+                .map(|coin| state.list_unspent_entry(coin))
+                .collect())
+        })
+    }
+
+    async fn list_transactions(&self, count: Option<usize>) -> ClientResult<Vec<ListTransactions>> {
+        self.with_state(&["listtransactions"], |state| {
+            let confirmed = state.chain.iter().enumerate().flat_map(|(height, hash)| {
+                let block = &state.blocks[hash].0;
+                block.txdata.iter().enumerate().map(move |(pos, tx)| {
+                    (
+                        tx,
+                        Some((height as u64, pos, hash)),
+                        block.header.time as u64,
+                    )
+                })
+            });
+            let unconfirmed = state
+                .mempool
+                .iter()
+                .map(|entry| (&entry.tx, None, entry.time));
+
+            let mut transactions = Vec::new();
+            for (tx, position, time) in confirmed.chain(unconfirmed) {
+                let confirmations =
+                    position.map_or(0, |(height, _, _)| state.confirmations(height));
+                let debit = state.debit(tx);
+                for entry in state.wallet_entries(tx, confirmations) {
+                    let Some(address) = entry.address else {
+                        continue;
+                    };
+                    transactions.push(ListTransactions {
+                        address: address.into_unchecked(),
+                        category: entry.category,
+                        amount: entry.amount,
+                        label: None,
+                        confirmations: confirmations as u32,
+                        trusted: (confirmations == 0).then_some(debit > Amount::ZERO),
+                        generated: tx.is_coinbase().then_some(true),
+                        blockhash: position.map(|(_, _, hash)| hash.to_string()),
+                        blockheight: position.map(|(height, _, _)| height),
+                        blockindex: position.map(|(_, pos, _)| pos as u32),
+                        blocktime: position.map(|_| time),
+                        txid: tx.compute_txid(),
+                    });
+                }
+            }
+            let count = count.unwrap_or(10);
+            Ok(transactions.split_off(transactions.len().saturating_sub(count)))
+        })
+    }
+
+    async fn list_wallets(&self) -> ClientResult<Vec<String>> {
+        self.with_state(&["listwallets"], |state| Ok(state.wallet.wallets.clone()))
+    }
+
+    async fn create_raw_transaction(
+        &self,
+        raw_tx: CreateRawTransaction,
+    ) -> ClientResult<Transaction> {
+        self.with_state(&["createrawtransaction"], |_| {
+            build_transaction(&raw_tx.inputs, &raw_tx.outputs, 0, false)
+        })
+    }
+
+    async fn wallet_create_funded_psbt(
+        &self,
+        inputs: &[CreateRawTransactionInput],
+        outputs: &[CreateRawTransactionOutput],
+        locktime: Option<u32>,
+        options: Option<WalletCreateFundedPsbtOptions>,
+        _bip32_derivs: Option<bool>,
+    ) -> ClientResult<WalletCreateFundedPsbt> {
+        self.with_state(&["walletcreatefundedpsbt"], |state| {
+            let options = options.unwrap_or_default();
+            let fee_rate = options
+                .fee_rate
+                .map(fee_rate_from_sat_per_vb)
+                .unwrap_or(state.fee_rate);
+            let replaceable = options.replaceable.unwrap_or(true);
+            let mut tx = build_transaction(inputs, outputs, locktime.unwrap_or(0), replaceable)?;
+            let sequence = tx
+                .input
+                .first()
+                .map_or(Sequence::ENABLE_RBF_NO_LOCKTIME, |input| input.sequence);
+
+            let target = tx.output.iter().map(|output| output.value).sum::<Amount>();
+            let mut input_value = Amount::ZERO;
+            for input in &tx.input {
+                input_value += state
+                    .prevout(&input.previous_output)
+                    .ok_or_else(|| {
+                        ClientError::rpc(
+                            RpcErrorCode::WalletError,
+                            "Unable to find UTXO for external input",
+                        )
+                    })?
+                    .value;
+            }
+
+            let selected = tx
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .collect::<HashSet<_>>();
+            let mut coins = state
+                .wallet_coins()
+                .into_iter()
+                .filter(|coin| coin.safe && !selected.contains(&coin.outpoint));
+            let fee_with_change = |tx: &Transaction| {
+                fee_rate
+                    .fee_vb(estimate_vsize(tx) + P2WPKH_OUTPUT_VSIZE)
+                    .unwrap_or(Amount::MAX)
+            };
+            while input_value < target + fee_with_change(&tx) {
+                let coin = coins.next().ok_or_else(|| {
+                    ClientError::rpc(RpcErrorCode::WalletInsufficientFunds, "Insufficient funds")
+                })?;
+                tx.input.push(TxIn {
+                    previous_output: coin.outpoint,
+                    sequence,
+                    ..Default::default()
+                });
+                input_value += coin.output.value;
+            }
+
+            let change = input_value - target - fee_with_change(&tx);
+            let change_pos = if change >= DUST_LIMIT {
+                tx.output.push(TxOut {
+                    value: change,
+                    script_pubkey: state.wallet.new_address(true).script_pubkey(),
+                });
+                tx.output.len() as i32 - 1
+            } else {
+                -1
+            };
+            let fee = input_value - tx.output.iter().map(|output| output.value).sum::<Amount>();
+
+            let prevouts = state.prevouts(&tx, &[]);
+            Ok(WalletCreateFundedPsbt {
+                psbt: unsigned_psbt(tx, prevouts)?,
+                fee,
+                change_pos,
+            })
+        })
+    }
+
+    async fn get_address_info(&self, address: &Address) -> ClientResult<GetAddressInfo> {
+        self.with_state(&["getaddressinfo"], |state| {
+            let is_mine = state.wallet.is_mine(&address.script_pubkey());
+            Ok(GetAddressInfo {
+                address: address.as_unchecked().clone(),
+                is_mine: Some(is_mine),
+                is_watchonly: Some(false),
+                solvable: Some(is_mine),
+            })
+        })
+    }
+
+    async fn list_unspent(
+        &self,
+        min_conf: Option<u32>,
+        max_conf: Option<u32>,
+        addresses: Option<&[Address]>,
+        include_unsafe: Option<bool>,
+        query_options: Option<ListUnspentQueryOptions>,
+    ) -> ClientResult<Vec<ListUnspent>> {
+        self.with_state(&["listunspent"], |state| {
+            let min_conf = min_conf.unwrap_or(1) as u64;
+            let max_conf = max_conf.unwrap_or(9_999_999) as u64;
+            let include_unsafe = include_unsafe.unwrap_or(true);
+            let (minimum_amount, maximum_amount, maximum_count) =
+                query_options.map_or((None, None, None), |options| {
+                    (
+                        options.minimum_amount,
+                        options.maximum_amount,
+                        options.maximum_count,
+                    )
+                });
+            let scripts = addresses.map(|addresses| {
+                addresses
+                    .iter()
+                    .map(Address::script_pubkey)
+                    .collect::<HashSet<_>>()
+            });
+
+            let unspent = state
+                .wallet_coins()
+                .iter()
+                .filter(|coin| (min_conf..=max_conf).contains(&coin.confirmations))
+                .filter(|coin| include_unsafe || coin.safe)
+                .filter(|coin| {
+                    scripts
+                        .as_ref()
+                        .is_none_or(|scripts| scripts.contains(&coin.output.script_pubkey))
+                })
+                .filter(|coin| {
+                    minimum_amount.is_none_or(|minimum| coin.output.value >= minimum)
+                        && maximum_amount.is_none_or(|maximum| coin.output.value <= maximum)
+                })
+                .take(maximum_count.map_or(usize::MAX, |count| count as usize))
+                .map(|coin| state.list_unspent_entry(coin))
+                .collect();
+            Ok(unspent)
+        })
+    }
+}
+
+impl Signer for MockBitcoind {
+    async fn sign_raw_transaction_with_wallet(
+        &self,
+        tx: &Transaction,
+        prev_outputs: Option<Vec<PreviousTransactionOutput>>,
+    ) -> ClientResult<SignRawTransactionWithWallet> {
+        self.with_state(&["signrawtransactionwithwallet"], |state| {
+            let prevouts = state.prevouts(tx, &prev_outputs.unwrap_or_default());
+            let witnesses = state.sign(tx, &prevouts, EcdsaSighashType::All)?;
+            let mut signed = tx.clone();
+            for (input, witness) in signed.input.iter_mut().zip(witnesses) {
+                if let Some(witness) = witness {
+                    input.witness = witness;
+                }
+            }
+            let complete = signed
+                .input
+                .iter()
+                .all(|input| !input.witness.is_empty() || !input.script_sig.is_empty());
+            Ok(SignRawTransactionWithWallet {
+                hex: serialize_hex(&signed),
+                complete,
+                errors: None,
+            })
+        })
+    }
+
+    async fn get_xpriv(&self) -> ClientResult<Option<Xpriv>> {
+        self.with_state(&["listdescriptors"], |state| Ok(Some(state.wallet.master)))
+    }
+
+    async fn import_descriptors(
+        &self,
+        descriptors: Vec<ImportDescriptor>,
+        wallet_name: String,
+    ) -> ClientResult<Vec<ImportDescriptorResult>> {
+        self.with_state(&["importdescriptors"], |state| {
+            if !state.wallet.wallets.contains(&wallet_name) {
+                state.wallet.wallets.push(wallet_name.clone());
+            }
+            let results = descriptors
+                .iter()
+                .map(|_| ImportDescriptorResult { success: true })
+                .collect();
+            state.wallet.descriptors.extend(
+                descriptors
+                    .into_iter()
+                    .map(|descriptor| (wallet_name.clone(), descriptor)),
+            );
+            Ok(results)
+        })
+    }
+
+    async fn wallet_process_psbt(
+        &self,
+        psbt: &Psbt,
+        sign: Option<bool>,
+        sighashtype: Option<SighashType>,
+        _bip32_derivs: Option<bool>,
+    ) -> ClientResult<WalletProcessPsbtResult> {
+        self.with_state(&["walletprocesspsbt"], |state| {
+            let mut psbt = psbt.clone();
+            let tx = psbt.unsigned_tx.clone();
+            let prevouts = state
+                .prevouts(&tx, &[])
+                .into_iter()
+                .zip(&psbt.inputs)
+                .map(|(prevout, input)| prevout.or_else(|| input.witness_utxo.clone()))
+                .collect::<Vec<_>>();
+            for (input, prevout) in psbt.inputs.iter_mut().zip(&prevouts) {
+                if input.witness_utxo.is_none() {
+                    input.witness_utxo = prevout.clone();
+                }
+            }
+
+            if sign.unwrap_or(true) {
+                let sighash_type = match sighashtype.unwrap_or(SighashType::Default) {
+                    SighashType::Default | SighashType::All => EcdsaSighashType::All,
+                    SighashType::None => EcdsaSighashType::None,
+                    SighashType::Single => EcdsaSighashType::Single,
+                    SighashType::AllPlusAnyoneCanPay => EcdsaSighashType::AllPlusAnyoneCanPay,
+                    SighashType::NonePlusAnyoneCanPay => EcdsaSighashType::NonePlusAnyoneCanPay,
+                    SighashType::SinglePlusAnyoneCanPay => EcdsaSighashType::SinglePlusAnyoneCanPay,
+                };
+                let witnesses = state.sign(&tx, &prevouts, sighash_type)?;
+                for (input, witness) in psbt.inputs.iter_mut().zip(witnesses) {
+                    if witness.is_some() && input.final_script_witness.is_none() {
+                        input.final_script_witness = witness;
+                    }
+                }
+            }
+
+            let complete = psbt.inputs.iter().all(|input| {
+                input.final_script_witness.is_some() || input.final_script_sig.is_some()
+            });
+            let hex = complete.then(|| psbt.clone().extract_tx_unchecked_fee_rate());
+            Ok(WalletProcessPsbtResult {
+                psbt: Some(psbt),
+                complete,
+                hex,
+            })
+        })
+    }
+
+    async fn psbt_bump_fee(
+        &self,
+        txid: &Txid,
+        options: Option<PsbtBumpFeeOptions>,
+    ) -> ClientResult<PsbtBumpFee> {
+        self.with_state(&["psbtbumpfee"], |state| {
+            let options = options.unwrap_or_default();
+            let entry = state
+                .mempool
+                .iter()
+                .find(|entry| entry.txid == *txid)
+                .ok_or_else(|| {
+                    ClientError::rpc(
+                        RpcErrorCode::InvalidAddressOrKey,
+                        "Invalid or non-wallet transaction id",
+                    )
+                })?;
+            if state.debit(&entry.tx) == Amount::ZERO {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::WalletError,
+                    "Transaction does not belong to this wallet",
+                ));
+            }
+            if !entry.tx.is_explicitly_rbf() {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::WalletError,
+                    "Transaction is not BIP 125 replaceable",
+                ));
+            }
+
+            let mut tx = entry.tx.clone();
+            for input in &mut tx.input {
+                input.witness = Witness::new();
+                input.script_sig = ScriptBuf::new();
+            }
+            let change_index = match options.original_change_index {
+                Some(index) => index as usize,
+                None => tx
+                    .output
+                    .iter()
+                    .position(|output| state.wallet.is_change(&output.script_pubkey))
+                    .ok_or_else(|| {
+                        ClientError::rpc(
+                            RpcErrorCode::MiscError,
+                            "Transaction does not have a change output",
+                        )
+                    })?,
+            };
+
+            let vsize = estimate_vsize(&tx);
+            let orig_fee = entry.fee;
+            let orig_fee_rate = FeeRate::from_sat_per_kwu(orig_fee.to_sat() * 250 / vsize.max(1));
+            let fee_rate = options.fee_rate.unwrap_or_else(|| {
+                FeeRate::from_sat_per_kwu(
+                    orig_fee_rate.to_sat_per_kwu() + FeeRate::BROADCAST_MIN.to_sat_per_kwu(),
+                )
+            });
+            let fee = fee_rate.fee_vb(vsize).unwrap_or(Amount::MAX);
+            let min_fee = orig_fee + FeeRate::BROADCAST_MIN.fee_vb(vsize).unwrap_or(Amount::MAX);
+            if fee < min_fee {
+                return Err(ClientError::rpc(
+                    RpcErrorCode::InvalidParameter,
+                    "Insufficient total fee",
+                ));
+            }
+
+            let change = tx.output.get_mut(change_index).ok_or_else(|| {
+                ClientError::rpc(RpcErrorCode::MiscError, "Change position is out of range")
+            })?;
+            change.value = change
+                .value
+                .checked_sub(fee - orig_fee)
+                .filter(|value| *value >= DUST_LIMIT)
+                .ok_or_else(|| {
+                    ClientError::rpc(
+                        RpcErrorCode::WalletError,
+                        "Change output is too small to bump the fee",
+                    )
+                })?;
+
+            let prevouts = state.prevouts(&tx, &[]);
+            Ok(PsbtBumpFee {
+                psbt: unsigned_psbt(tx, prevouts)?,
+                origfee: orig_fee_rate,
+                fee: fee_rate,
+                errors: Some(Vec::new()),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{consensus::encode::deserialize_hex, opcodes::all::OP_CHECKMULTISIG};
+
+    use super::*;
+
+    /// An address the mock wallet does not own.
+    fn external_address() -> Address {
+        "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw"
+            .parse::<Address<_>>()
+            .unwrap()
+            .require_network(Network::Regtest)
+            .unwrap()
+    }
+
+    /// Funds, signs and broadcasts a transaction paying `amount` to `address`.
+    async fn send_to(bitcoind: &MockBitcoind, address: &Address, amount: Amount) -> Txid {
+        let outputs = [CreateRawTransactionOutput::AddressAmount {
+            address: address.to_string(),
+            amount: amount.to_btc(),
+        }];
+        let funded = bitcoind
+            .wallet_create_funded_psbt(&[], &outputs, None, None, None)
+            .await
+            .unwrap();
+        let processed = bitcoind
+            .wallet_process_psbt(&funded.psbt, None, None, None)
+            .await
+            .unwrap();
+        assert!(processed.complete);
+        bitcoind
+            .send_raw_transaction(&processed.hex.unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn mine_blocks() {
+        let bitcoind = MockBitcoind::new();
+        assert_eq!(bitcoind.get_block_count().await.unwrap(), 0);
+        assert_eq!(
+            bitcoind.get_block_hash(0).await.unwrap(),
+            genesis_block(Network::Regtest).block_hash()
+        );
+
+        let hashes = bitcoind.mine_blocks(101);
+        assert_eq!(hashes.len(), 101);
+        assert_eq!(bitcoind.get_block_count().await.unwrap(), 101);
+        assert_eq!(bitcoind.get_block_hash(101).await.unwrap(), hashes[100]);
+        assert_eq!(bitcoind.get_block_height(&hashes[41]).await.unwrap(), 42);
+
+        let block = bitcoind.get_block_at(20).await.unwrap();
+        assert_eq!(block.bip34_block_height().unwrap(), 20);
+        assert!(block.check_merkle_root());
+        assert!(block.check_witness_commitment());
+        assert_eq!(block.header.prev_blockhash, hashes[18]);
+        let headers = bitcoind.get_block_headers_at(&[20, 101]).await.unwrap();
+        assert_eq!(headers[0], block.header);
+        assert_eq!(headers[1].block_hash(), hashes[100]);
+        assert!(bitcoind.get_blocks_at(&[1, 102]).await.is_err());
+
+        let info = bitcoind.get_blockchain_info().await.unwrap();
+        assert_eq!(info.chain, "regtest");
+        assert_eq!(info.blocks, 101);
+        assert_eq!(info.best_block_hash, hashes[100].to_string());
+        assert_eq!(bitcoind.network().await.unwrap(), Network::Regtest);
+
+        // Only the first coinbase is mature.
+        let unspent = bitcoind
+            .list_unspent(None, None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(unspent.len(), 1);
+        assert_eq!(unspent[0].amount, Amount::from_int_btc(50));
+        assert_eq!(unspent[0].confirmations, 101);
+
+        assert!(matches!(
+            bitcoind.get_block_hash(102).await,
+            Err(ClientError::Server(-8, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fee_estimates() {
+        let bitcoind = MockBitcoind::new();
+        let fee_rate = FeeRate::from_sat_per_vb(7).unwrap();
+        bitcoind.set_fee_rate(fee_rate);
+
+        assert_eq!(bitcoind.estimate_smart_fee(6).await.unwrap(), 7);
+        let estimate = bitcoind
+            .estimate_smart_fee_with_mode(1, EstimateMode::Conservative)
+            .await
+            .unwrap();
+        assert_eq!(estimate.fee_rate, Some(fee_rate));
+        assert_eq!(estimate.blocks, 2);
+
+        let raw = bitcoind.estimate_raw_fee(20, None).await.unwrap();
+        assert!(raw.short.is_none());
+        assert_eq!(raw.medium.unwrap().fee_rate, Some(fee_rate));
+        assert_eq!(raw.long.unwrap().scale, 24);
+
+        assert!(matches!(
+            bitcoind.estimate_raw_fee(1009, None).await,
+            Err(ClientError::Server(-8, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn tx_out_proofs() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+
+        let address = external_address();
+        let txid = send_to(&bitcoind, &address, Amount::from_int_btc(1)).await;
+        assert!(bitcoind.get_tx_out_proof(&[txid], None).await.is_err());
+        let hash = bitcoind.mine_blocks(1)[0];
+
+        let proof = bitcoind.get_tx_out_proof(&[txid], None).await.unwrap();
+        assert_eq!(proof.header.block_hash(), hash);
+        assert_eq!(
+            bitcoind
+                .get_tx_out_proof(&[txid], Some(&hash))
+                .await
+                .unwrap(),
+            proof
+        );
+        assert_eq!(
+            bitcoind.verify_tx_out_proof(&proof).await.unwrap(),
+            vec![txid]
+        );
+        assert_eq!(
+            bitcoind.verify_tx_out_proof_locally(&proof).await.unwrap(),
+            vec![txid]
+        );
+
+        // Wrong block.
+        let previous = bitcoind.get_block_hash(101).await.unwrap();
+        assert!(bitcoind
+            .get_tx_out_proof(&[txid], Some(&previous))
+            .await
+            .is_err());
+
+        // Stale block.
+        bitcoind.reorg(1);
+        assert!(bitcoind.verify_tx_out_proof(&proof).await.is_err());
+        assert_eq!(
+            bitcoind.verify_tx_out_proof_locally(&proof).await.unwrap(),
+            vec![txid]
+        );
+    }
+
+    #[tokio::test]
+    async fn tx_out_set() {
+        let bitcoind = MockBitcoind::new();
+        let address = external_address();
+        bitcoind.generate_to_address(2, &address);
+        bitcoind.mine_blocks(100);
+
+        let info = bitcoind.get_tx_out_set_info(None).await.unwrap();
+        assert_eq!(info.height, 102);
+        assert_eq!(info.tx_outs, 102);
+        assert_eq!(info.total_amount, Amount::from_int_btc(50 * 102));
+
+        let scan = bitcoind
+            .scan_tx_out_set_start(&[format!("addr({address})#checksum").into()])
+            .await
+            .unwrap();
+        assert!(scan.success);
+        assert_eq!(scan.height, 102);
+        assert_eq!(
+            scan.unspents
+                .iter()
+                .map(|unspent| unspent.height)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(scan.unspents.iter().all(|unspent| unspent.coinbase));
+        assert_eq!(scan.total_amount, Amount::from_int_btc(100));
+
+        let raw = format!("raw({})", address.script_pubkey().to_hex_string());
+        let scan = bitcoind.scan_tx_out_set_start(&[raw.into()]).await.unwrap();
+        assert_eq!(scan.unspents.len(), 2);
+
+        assert!(bitcoind
+            .scan_tx_out_set_start(&["wpkh(02aa)".into()])
+            .await
+            .is_err());
+        assert_eq!(bitcoind.scan_tx_out_set_status().await.unwrap(), None);
+        assert!(!bitcoind.scan_tx_out_set_abort().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn send_and_confirm() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+
+        let address = external_address();
+        let txid = send_to(&bitcoind, &address, Amount::from_int_btc(1)).await;
+        assert_eq!(bitcoind.get_raw_mempool().await.unwrap(), vec![txid]);
+        assert_eq!(bitcoind.get_mempool_info().await.unwrap().size, 1);
+
+        let tx = bitcoind.get_transaction(&txid).await.unwrap();
+        assert_eq!(tx.confirmations, 0);
+        assert_eq!(tx.amount, SignedAmount::from_sat(-100_000_000));
+        assert_eq!(tx.details.len(), 1);
+        assert_eq!(tx.details[0].category, GetTransactionDetailCategory::Send);
+        assert_eq!(tx.details[0].address, address.to_string());
+
+        let tx_out = bitcoind.get_tx_out(&txid, 0, true).await.unwrap();
+        assert_eq!(tx_out.confirmations, 0);
+        assert!(bitcoind.get_tx_out(&txid, 0, false).await.is_err());
+
+        let hash = bitcoind.mine_blocks(1)[0];
+        assert!(bitcoind.get_raw_mempool().await.unwrap().is_empty());
+        let tx = bitcoind
+            .get_raw_transaction_verbosity_one(&txid)
+            .await
+            .unwrap();
+        assert_eq!(tx.blockhash, Some(hash));
+        assert_eq!(tx.confirmations, Some(1));
+        let tx_out = bitcoind.get_tx_out(&txid, 0, false).await.unwrap();
+        assert_eq!(tx_out.value, 1.0);
+        assert_eq!(
+            tx_out.script_pubkey.unwrap().address,
+            Some(address.to_string())
+        );
+
+        // The coinbase collected the fee.
+        let fee = bitcoind.get_block(&hash).await.unwrap().txdata[0].output[0].value
+            - Amount::from_int_btc(50);
+        assert!(fee > Amount::ZERO);
+
+        let block = bitcoind.get_block_verbose(&hash, true).await.unwrap();
+        assert_eq!(block.confirmations, 1);
+        assert_eq!(block.tx[0].fee, None);
+        assert_eq!(block.tx[1].txid, txid);
+        assert_eq!(block.tx[1].fee, Some(fee));
+        let output_value = block.tx[1]
+            .transaction
+            .output
+            .iter()
+            .map(|output| output.value)
+            .sum::<Amount>();
+        assert_eq!(block.tx[1].input_value(), Some(output_value + fee));
+        let prevout = block.tx[1].vin[0].prevout.as_ref().unwrap();
+        assert!(prevout.generated);
+        assert!(prevout.height <= 2);
+        let block = bitcoind.get_block_verbose(&hash, false).await.unwrap();
+        assert_eq!(block.tx[1].fee, Some(fee));
+        assert_eq!(block.tx[1].prevouts(), None);
+
+        let sent = bitcoind
+            .list_transactions(None)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|entry| entry.category == TransactionCategory::Send)
+            .collect::<Vec<_>>();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].txid, txid);
+        assert_eq!(sent[0].confirmations, 1);
+    }
+
+    #[tokio::test]
+    async fn block_stats() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+        let txid = send_to(&bitcoind, &external_address(), Amount::from_int_btc(1)).await;
+        let hash = bitcoind.mine_blocks(1)[0];
+
+        let block = bitcoind.get_block_verbose(&hash, false).await.unwrap();
+        let fee = block.tx[1].fee.unwrap();
+        let stats = bitcoind
+            .get_block_stats(BlockStatsTarget::Hash(hash), None)
+            .await
+            .unwrap();
+        assert_eq!(stats.block_hash, Some(hash));
+        assert_eq!(stats.height, Some(102));
+        assert_eq!(stats.txs, Some(2));
+        assert_eq!(stats.ins, Some(1));
+        assert_eq!(stats.total_fee, Some(fee));
+        assert_eq!(stats.median_fee, Some(fee));
+        assert_eq!(stats.subsidy, Some(Amount::from_int_btc(50)));
+        assert_eq!(stats.sw_txs, Some(1));
+        assert_eq!(stats.total_size, Some(block.tx[1].size as u64));
+        let fee_rate = stats.avg_fee_rate.unwrap();
+        assert!(fee_rate >= FeeRate::BROADCAST_MIN);
+        assert_eq!(stats.fee_rate_percentiles, Some([fee_rate; 5]));
+        assert_eq!(block.tx[1].txid, txid);
+
+        let stats = bitcoind
+            .get_block_stats(
+                BlockStatsTarget::Height(1),
+                Some(&[BlockStatsField::Height, BlockStatsField::TotalFee]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            GetBlockStats {
+                height: Some(1),
+                total_fee: Some(Amount::ZERO),
+                ..Default::default()
+            }
+        );
+
+        let err = bitcoind
+            .get_block_stats(BlockStatsTarget::Height(103), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.rpc_error_code(), Some(RpcErrorCode::InvalidParameter));
+    }
+
+    #[tokio::test]
+    async fn mempool_ancestors_and_descendants() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+
+        // The only mature coin is spent by the parent, so the child spends its change.
+        let parent = send_to(&bitcoind, &external_address(), Amount::from_int_btc(1)).await;
+        let child = send_to(&bitcoind, &external_address(), Amount::from_int_btc(2)).await;
+
+        let mempool = bitcoind.get_raw_mempool_verbose().await.unwrap();
+        assert_eq!(mempool.len(), 2);
+        let parent_entry = &mempool[&parent];
+        let child_entry = &mempool[&child];
+        assert_eq!(parent_entry.height, 101);
+        assert_eq!(parent_entry.spent_by, vec![child]);
+        assert_eq!(parent_entry.descendant_count, 2);
+        assert_eq!(child_entry.depends, vec![parent]);
+        assert_eq!(child_entry.ancestor_count, 2);
+        assert_eq!(
+            child_entry.ancestor_size,
+            parent_entry.vsize + child_entry.vsize
+        );
+        assert_eq!(
+            child_entry.fees.ancestor,
+            parent_entry.fees.base + child_entry.fees.base
+        );
+        assert_eq!(
+            bitcoind.get_mempool_entry(&child).await.unwrap(),
+            *child_entry
+        );
+
+        let ancestors = bitcoind.get_mempool_ancestors(&child).await.unwrap();
+        assert_eq!(ancestors.keys().collect::<Vec<_>>(), vec![&parent]);
+        let descendants = bitcoind.get_mempool_descendants(&parent).await.unwrap();
+        assert_eq!(descendants.keys().collect::<Vec<_>>(), vec![&child]);
+        assert!(bitcoind
+            .get_mempool_descendants(&child)
+            .await
+            .unwrap()
+            .is_empty());
+
+        bitcoind.mine_blocks(1);
+        let err = bitcoind.get_mempool_entry(&child).await.unwrap_err();
+        assert_eq!(
+            err.rpc_error_code(),
+            Some(RpcErrorCode::InvalidAddressOrKey)
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_transactions() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+
+        // Immature coinbase.
+        let immature = bitcoind.get_block_at(3).await.unwrap().txdata[0].compute_txid();
+        let raw_tx = CreateRawTransaction {
+            inputs: vec![CreateRawTransactionInput {
+                txid: immature.to_string(),
+                vout: 0,
+            }],
+            outputs: vec![CreateRawTransactionOutput::AddressAmount {
+                address: external_address().to_string(),
+                amount: 49.0,
+            }],
+        };
+        let tx = bitcoind.create_raw_transaction(raw_tx).await.unwrap();
+        let signed = bitcoind
+            .sign_raw_transaction_with_wallet(&tx, None)
+            .await
+            .unwrap();
+        assert!(signed.complete);
+        let tx = deserialize_hex::<Transaction>(&signed.hex).unwrap();
+        let got = bitcoind.test_mempool_accept(&tx).await.unwrap();
+        assert_eq!(
+            got[0].reject_reason.as_deref(),
+            Some("bad-txns-premature-spend-of-coinbase")
+        );
+
+        // Double spend of a non-replaceable transaction.
+        let mature = bitcoind.get_block_at(1).await.unwrap().txdata[0].compute_txid();
+        let spend = |amount: f64| CreateRawTransaction {
+            inputs: vec![CreateRawTransactionInput {
+                txid: mature.to_string(),
+                vout: 0,
+            }],
+            outputs: vec![CreateRawTransactionOutput::AddressAmount {
+                address: external_address().to_string(),
+                amount,
+            }],
+        };
+        let first = bitcoind.create_raw_transaction(spend(49.0)).await.unwrap();
+        let second = bitcoind.create_raw_transaction(spend(48.0)).await.unwrap();
+        let txid = bitcoind.send_raw_transaction(&first).await.unwrap();
+        assert_eq!(
+            bitcoind.send_raw_transaction(&second).await,
+            Err(ClientError::Server(-26, "txn-mempool-conflict".to_string()))
+        );
+
+        // Already confirmed transactions count as sent.
+        bitcoind.mine_blocks(1);
+        assert_eq!(bitcoind.send_raw_transaction(&first).await, Ok(txid));
+        assert!(matches!(
+            bitcoind.send_raw_transaction(&second).await,
+            Err(ClientError::Server(-25, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn scripted_errors() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+
+        let rejected = ClientError::Server(-26, "min relay fee not met".to_string());
+        bitcoind.fail_next("sendrawtransaction", rejected.clone());
+        bitcoind.fail_next("getblockhash", ClientError::Timeout);
+
+        let outputs = [CreateRawTransactionOutput::AddressAmount {
+            address: external_address().to_string(),
+            amount: 1.0,
+        }];
+        let funded = bitcoind
+            .wallet_create_funded_psbt(&[], &outputs, None, None, None)
+            .await
+            .unwrap();
+        let tx = bitcoind
+            .wallet_process_psbt(&funded.psbt, None, None, None)
+            .await
+            .unwrap()
+            .hex
+            .unwrap();
+
+        assert_eq!(bitcoind.send_raw_transaction(&tx).await, Err(rejected));
+        assert_eq!(bitcoind.get_block_at(1).await, Err(ClientError::Timeout));
+
+        // Scripted errors are consumed.
+        assert!(bitcoind.send_raw_transaction(&tx).await.is_ok());
+        assert!(bitcoind.get_block_at(1).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn reorg() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+        let txid = send_to(&bitcoind, &external_address(), Amount::from_int_btc(1)).await;
+        let old_tip = bitcoind.mine_blocks(1)[0];
+
+        let new_blocks = bitcoind.reorg(1);
+        assert_eq!(new_blocks.len(), 2);
+        assert_eq!(bitcoind.get_block_count().await.unwrap(), 103);
+        assert_ne!(bitcoind.get_block_hash(102).await.unwrap(), old_tip);
+
+        // The stale block is still known, and the transaction is confirmed again.
+        assert_eq!(bitcoind.get_block_height(&old_tip).await.unwrap(), 102);
+        let tx = bitcoind.get_transaction(&txid).await.unwrap();
+        assert_eq!(tx.blockhash, Some(new_blocks[0].to_string()));
+        assert_eq!(tx.confirmations, 2);
+
+        let tips = bitcoind.get_chain_tips().await.unwrap();
+        assert_eq!(tips.len(), 2);
+        assert_eq!(tips[0].hash, new_blocks[1]);
+        assert_eq!(tips[0].status, ChainTipStatus::Active);
+        assert_eq!(tips[1].hash, old_tip);
+        assert_eq!(tips[1].status, ChainTipStatus::ValidFork);
+        assert_eq!(tips[1].branch_len, 1);
+
+        let fork = bitcoind.find_fork(&old_tip, &new_blocks[1]).await.unwrap();
+        assert_eq!(fork.fork_height, 101);
+        assert_eq!(fork.fork_point, bitcoind.get_block_hash(101).await.unwrap());
+        assert_eq!(fork.disconnected, vec![old_tip]);
+        assert_eq!(fork.connected, new_blocks);
+
+        let disconnected = bitcoind.disconnect_blocks(2);
+        assert_eq!(disconnected, vec![new_blocks[1], new_blocks[0]]);
+        assert_eq!(bitcoind.get_block_count().await.unwrap(), 101);
+        assert_eq!(bitcoind.get_raw_mempool().await.unwrap(), vec![txid]);
+    }
+
+    #[tokio::test]
+    async fn headers_only_fork() {
+        let bitcoind = MockBitcoind::new();
+        let fork_point = bitcoind.mine_blocks(101)[100];
+        let headers = bitcoind.receive_headers(3);
+        let blocks = bitcoind.mine_blocks(2);
+
+        let tips = bitcoind.get_chain_tips().await.unwrap();
+        assert_eq!(tips.len(), 2);
+        assert_eq!(tips[0].hash, headers[2]);
+        assert_eq!(tips[0].status, ChainTipStatus::HeadersOnly);
+        assert_eq!(tips[0].branch_len, 3);
+        assert_eq!(tips[1].hash, blocks[1]);
+        assert_eq!(tips[1].status, ChainTipStatus::Active);
+
+        // Only the header is known.
+        assert!(matches!(
+            bitcoind.get_block_height(&headers[2]).await,
+            Err(ClientError::Server(-1, _))
+        ));
+        let header = bitcoind
+            .get_block_header_verbose(&headers[2])
+            .await
+            .unwrap();
+        assert_eq!(header.height, 104);
+        assert_eq!(header.confirmations, -1);
+        assert_eq!(header.n_tx, 0);
+        assert_eq!(header.previous_block_hash, Some(headers[1]));
+
+        let fork = bitcoind.find_fork(&headers[2], &blocks[1]).await.unwrap();
+        assert_eq!(fork.fork_point, fork_point);
+        assert_eq!(fork.fork_height, 101);
+        assert_eq!(fork.disconnected, vec![headers[2], headers[1], headers[0]]);
+        assert_eq!(fork.connected, blocks);
+    }
+
+    #[tokio::test]
+    async fn decoding() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+        let address = external_address();
+        let outputs = [CreateRawTransactionOutput::AddressAmount {
+            address: address.to_string(),
+            amount: 1.0,
+        }];
+        let funded = bitcoind
+            .wallet_create_funded_psbt(&[], &outputs, None, None, None)
+            .await
+            .unwrap();
+
+        let decoded = bitcoind
+            .decode_raw_transaction(&funded.psbt.unsigned_tx)
+            .await
+            .unwrap();
+        assert_eq!(decoded.txid, funded.psbt.unsigned_tx.compute_txid());
+        assert_eq!(decoded.vin.len(), funded.psbt.inputs.len());
+        assert!(decoded.vin.iter().all(|input| input.coinbase.is_none()));
+        let payment = decoded
+            .vout
+            .iter()
+            .find(|output| output.script_pubkey.script == address.script_pubkey())
+            .unwrap();
+        assert_eq!(payment.value, Amount::from_int_btc(1));
+        assert_eq!(payment.script_pubkey.script_type, "witness_v0_keyhash");
+        assert_eq!(
+            payment.script_pubkey.address,
+            Some(address.as_unchecked().clone())
+        );
+
+        let processed = bitcoind
+            .wallet_process_psbt(&funded.psbt, None, None, None)
+            .await
+            .unwrap();
+        let tx = processed.hex.unwrap();
+        let decoded = bitcoind.decode_raw_transaction(&tx).await.unwrap();
+        assert_eq!(decoded.hash, tx.compute_wtxid());
+        assert_eq!(decoded.vsize, tx.vsize() as u64);
+        assert!(decoded.vin.iter().all(|input| !input.witness.is_empty()));
+
+        let coinbase = bitcoind
+            .get_block(&bitcoind.mine_blocks(1)[0])
+            .await
+            .unwrap()
+            .txdata[0]
+            .clone();
+        let decoded = bitcoind.decode_raw_transaction(&coinbase).await.unwrap();
+        assert_eq!(
+            decoded.vin[0].coinbase,
+            Some(coinbase.input[0].script_sig.clone())
+        );
+        assert_eq!(decoded.vin[0].previous_output(), None);
+
+        let decoded = bitcoind
+            .decode_script(&address.script_pubkey())
+            .await
+            .unwrap();
+        assert_eq!(decoded.address, Some(address.into_unchecked()));
+        assert_eq!(decoded.p2sh, None);
+        assert_eq!(decoded.segwit, None);
+
+        let multisig = Builder::new()
+            .push_int(1)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script();
+        let decoded = bitcoind.decode_script(&multisig).await.unwrap();
+        assert_eq!(decoded.address, None);
+        assert!(decoded.p2sh.is_some());
+        let segwit = decoded.segwit.unwrap();
+        assert_eq!(
+            segwit.script,
+            ScriptBuf::new_p2wsh(&multisig.wscript_hash())
+        );
+        assert_eq!(segwit.script_type, "witness_v0_scripthash");
+    }
+
+    #[tokio::test]
+    async fn descriptors() {
+        let bitcoind = MockBitcoind::new();
+
+        // Test vector from BIP 380.
+        let info = bitcoind.get_descriptor_info("raw(deadbeef)").await.unwrap();
+        assert_eq!(info.descriptor, "raw(deadbeef)#89f8spxm");
+        assert_eq!(info.checksum, "89f8spxm");
+        assert!(!info.is_range);
+        assert!(!info.is_solvable);
+        assert!(!info.has_private_keys);
+        let got = bitcoind
+            .get_descriptor_info("raw(deadbeef)#89f8spxm")
+            .await
+            .unwrap();
+        assert_eq!(got, info);
+        let got = bitcoind
+            .get_descriptor_info("raw(deadbeef)#89f8spxn")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            got.rpc_error_code(),
+            Some(RpcErrorCode::InvalidAddressOrKey)
+        );
+
+        let address = external_address();
+        let descriptor = format!("addr({address})");
+        assert_eq!(
+            bitcoind
+                .derive_addresses(&descriptor, None)
+                .await
+                .unwrap_err()
+                .rpc_error_code(),
+            Some(RpcErrorCode::InvalidAddressOrKey)
+        );
+        let descriptor = bitcoind
+            .get_descriptor_info(&descriptor)
+            .await
+            .unwrap()
+            .descriptor;
+        assert_eq!(descriptor, format!("addr({address})#v9e9qrqq"));
+        assert_eq!(
+            bitcoind.derive_addresses(&descriptor, None).await.unwrap(),
+            vec![address]
+        );
+        assert_eq!(
+            bitcoind
+                .derive_addresses(&descriptor, Some((0, 1)))
+                .await
+                .unwrap_err()
+                .rpc_error_code(),
+            Some(RpcErrorCode::InvalidParameter)
+        );
+    }
+
+    #[tokio::test]
+    async fn bump_fee() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+        let txid = send_to(&bitcoind, &external_address(), Amount::from_int_btc(1)).await;
+
+        let bumped = bitcoind.psbt_bump_fee(&txid, None).await.unwrap();
+        assert!(bumped.fee > bumped.origfee);
+        let processed = bitcoind
+            .wallet_process_psbt(&bumped.psbt, None, None, None)
+            .await
+            .unwrap();
+        let replacement = processed.hex.unwrap();
+        let new_txid = bitcoind.send_raw_transaction(&replacement).await.unwrap();
+        assert_eq!(bitcoind.get_raw_mempool().await.unwrap(), vec![new_txid]);
+    }
+
+    #[tokio::test]
+    async fn submit_package() {
+        let bitcoind = MockBitcoind::new();
+        bitcoind.mine_blocks(101);
+        let coinbase = bitcoind.get_block_at(1).await.unwrap().txdata[0].clone();
+        let address = bitcoind.get_new_address().await.unwrap();
+
+        // A zero-fee parent paid for by its child.
+        let parent = CreateRawTransaction {
+            inputs: vec![CreateRawTransactionInput {
+                txid: coinbase.compute_txid().to_string(),
+                vout: 0,
+            }],
+            outputs: vec![CreateRawTransactionOutput::AddressAmount {
+                address: address.to_string(),
+                amount: 50.0,
+            }],
+        };
+        let parent = bitcoind.create_raw_transaction(parent).await.unwrap();
+        assert_eq!(
+            bitcoind.send_raw_transaction(&parent).await,
+            Err(ClientError::Server(
+                -26,
+                "min relay fee not met".to_string()
+            ))
+        );
+        let child = CreateRawTransaction {
+            inputs: vec![CreateRawTransactionInput {
+                txid: parent.compute_txid().to_string(),
+                vout: 0,
+            }],
+            outputs: vec![CreateRawTransactionOutput::AddressAmount {
+                address: external_address().to_string(),
+                amount: 49.999,
+            }],
+        };
+        let child = bitcoind.create_raw_transaction(child).await.unwrap();
+
+        let result = bitcoind
+            .submit_package(&[parent.clone(), child.clone()])
+            .await
+            .unwrap();
+        assert_eq!(result.package_msg, "success");
+        assert_eq!(
+            bitcoind.get_raw_mempool().await.unwrap(),
+            vec![parent.compute_txid(), child.compute_txid()]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Local verification of transaction inclusion proofs.
+//!
+//! `gettxoutproof` returns a [`MerkleBlock`]: a block header and a partial merkle tree that
+//! commits to some of the transactions of the block. [`verify_tx_out_proof`] checks such a
+//! proof against a header obtained independently, so the node's `verifytxoutproof` does not
+//! have to be trusted.
+use bitcoin::{block::Header, MerkleBlock, Txid};
+
+use crate::{client::ClientResult, error::ClientError};
+
+/// Verifies a transaction inclusion proof against the header of the block it claims to be in.
+///
+/// Returns the transactions the proof commits to, in block order.
+///
+/// # Errors
+///
+/// [`ClientError::InvalidTxOutProof`] if the proof is for another block or its partial merkle
+/// tree is malformed or does not hash to the merkle root of the header.
+///
+/// # Note
+///
+/// Only proves that the transactions are in the block of `header`. Whether that block is
+/// part of the active chain, such as with [`Reader::get_block_height`](crate::traits::Reader)
+/// and [`Reader::get_block_hash`](crate::traits::Reader), is up to the caller.
+pub fn verify_tx_out_proof(proof: &MerkleBlock, header: &Header) -> ClientResult<Vec<Txid>> {
+    if proof.header != *header {
+        return Err(ClientError::InvalidTxOutProof(format!(
+            "proof is for block {}, expected {}",
+            proof.header.block_hash(),
+            header.block_hash()
+        )));
+    }
+
+    let mut matches = Vec::new();
+    let mut indexes = Vec::new();
+    proof
+        .extract_matches(&mut matches, &mut indexes)
+        .map_err(|err| ClientError::InvalidTxOutProof(err.to_string()))?;
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, constants::genesis_block, hashes::Hash, Network, Transaction,
+        TxMerkleNode,
+    };
+
+    use super::*;
+
+    /// The regtest genesis block with a few more transactions.
+    fn block() -> bitcoin::Block {
+        let mut block = genesis_block(Network::Regtest);
+        let coinbase = block.txdata[0].clone();
+        block.txdata.extend((1..5).map(|lock_time| Transaction {
+            lock_time: LockTime::from_consensus(lock_time),
+            ..coinbase.clone()
+        }));
+        block.header.merkle_root = block.compute_merkle_root().unwrap();
+        block
+    }
+
+    #[test]
+    fn verify_proofs() {
+        let block = block();
+        let txids = [
+            block.txdata[1].compute_txid(),
+            block.txdata[3].compute_txid(),
+        ];
+        let proof = MerkleBlock::from_block_with_predicate(&block, |txid| txids.contains(txid));
+
+        assert_eq!(
+            verify_tx_out_proof(&proof, &block.header).unwrap(),
+            txids.to_vec()
+        );
+
+        // A proof for another block.
+        let other = genesis_block(Network::Regtest);
+        assert!(matches!(
+            verify_tx_out_proof(&proof, &other.header),
+            Err(ClientError::InvalidTxOutProof(_))
+        ));
+
+        // A header whose merkle root the proof does not hash to.
+        let mut forged = proof.clone();
+        forged.header.merkle_root = TxMerkleNode::all_zeros();
+        assert!(matches!(
+            verify_tx_out_proof(&forged, &forged.header),
+            Err(ClientError::InvalidTxOutProof(_))
+        ));
+    }
+}
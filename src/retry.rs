@@ -0,0 +1,245 @@
+//! Retry policies for the RPC client.
+//!
+//! A [`RetryPolicy`] decides which errors are worth retrying and how long to wait between
+//! attempts. The built-in policies are [`ConstantBackoff`], [`ExponentialBackoff`] and
+//! [`Jittered`], which adds random jitter on top of any other policy.
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::ClientError;
+
+/// The default maximum number of retries for a request.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// The default interval between retries for a request in ms.
+pub const DEFAULT_RETRY_INTERVAL_MS: u64 = 1_000;
+
+/// Decides whether and when a failed request is retried.
+///
+/// # Note
+///
+/// When a policy gives up on a retryable error, the [`Client`](crate::Client) returns
+/// [`ClientError::MaxRetriesExceeded`] wrapping the last underlying error.
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// Maximum number of retries after the initial attempt.
+    fn max_retries(&self) -> u32;
+
+    /// How long to wait before the given retry, starting at 1 for the first retry.
+    fn delay(&self, retry: u32) -> Duration;
+
+    /// Whether the given error can be retried.
+    ///
+    /// Defaults to [`ClientError::is_retryable`].
+    fn is_retryable(&self, error: &ClientError) -> bool {
+        error.is_retryable()
+    }
+}
+
+/// Retries with the same interval between every attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantBackoff {
+    /// Maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+
+    /// Interval between retries.
+    pub interval: Duration,
+}
+
+impl ConstantBackoff {
+    /// Creates a new [`ConstantBackoff`].
+    pub fn new(max_retries: u32, interval: Duration) -> Self {
+        Self {
+            max_retries,
+            interval,
+        }
+    }
+}
+
+impl Default for ConstantBackoff {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_MAX_RETRIES,
+            Duration::from_millis(DEFAULT_RETRY_INTERVAL_MS),
+        )
+    }
+}
+
+impl RetryPolicy for ConstantBackoff {
+    fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    fn delay(&self, _retry: u32) -> Duration {
+        self.interval
+    }
+}
+
+/// Retries with an interval that is multiplied after every attempt, up to a maximum.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExponentialBackoff {
+    /// Maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+
+    /// Interval before the first retry.
+    pub initial_interval: Duration,
+
+    /// Factor the interval is multiplied by after every retry.
+    pub multiplier: f64,
+
+    /// Upper bound for the interval between retries.
+    pub max_interval: Duration,
+}
+
+impl ExponentialBackoff {
+    /// Creates a new [`ExponentialBackoff`] that doubles the interval after every retry.
+    pub fn new(max_retries: u32, initial_interval: Duration, max_interval: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_interval,
+            multiplier: 2.0,
+            max_interval,
+        }
+    }
+
+    /// Sets the factor the interval is multiplied by after every retry.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_MAX_RETRIES,
+            Duration::from_millis(DEFAULT_RETRY_INTERVAL_MS),
+            Duration::from_secs(30),
+        )
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let factor = self.multiplier.max(1.0).powi(exponent);
+        // Clamp in floating point first. A huge `max_interval` such as `Duration::MAX` rounds up
+        // to more seconds than a `Duration` holds, so fall back to `max_interval` itself.
+        let secs =
+            (self.initial_interval.as_secs_f64() * factor).min(self.max_interval.as_secs_f64());
+        Duration::try_from_secs_f64(secs).unwrap_or(self.max_interval)
+    }
+}
+
+/// Adds "full jitter" to another policy: every delay is drawn uniformly between zero and the
+/// delay of the wrapped policy.
+///
+/// Spreads out retries from many clients hitting the same `bitcoind`, such as after a restart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jittered<P> {
+    /// The wrapped policy.
+    pub inner: P,
+}
+
+impl<P: RetryPolicy> Jittered<P> {
+    /// Wraps the given policy.
+    pub fn new(inner: P) -> Self {
+        Self { inner }
+    }
+}
+
+impl<P: RetryPolicy> RetryPolicy for Jittered<P> {
+    fn max_retries(&self) -> u32 {
+        self.inner.max_retries()
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        self.inner.delay(retry).mul_f64(random_fraction())
+    }
+
+    fn is_retryable(&self, error: &ClientError) -> bool {
+        self.inner.is_retryable(error)
+    }
+}
+
+/// Returns a pseudo-random number in `[0, 1)`.
+///
+/// # Implementation Details
+///
+/// Uses the randomly seeded [`RandomState`] from the standard library. Good enough for
+/// jitter, not for anything security related.
+fn random_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(nanos);
+    // Keep the 53 bits that fit in the mantissa of an `f64`.
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_backoff() {
+        let policy = ConstantBackoff::new(2, Duration::from_millis(100));
+        assert_eq!(policy.max_retries(), 2);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn exponential_backoff() {
+        let policy =
+            ExponentialBackoff::new(10, Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(5), Duration::from_secs(1));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(1));
+
+        let policy = policy.with_multiplier(3.0);
+        assert_eq!(policy.delay(3), Duration::from_millis(900));
+
+        // No upper bound.
+        let policy = ExponentialBackoff::new(10, Duration::from_millis(100), Duration::MAX);
+        assert_eq!(policy.delay(4), Duration::from_millis(800));
+        assert_eq!(policy.delay(u32::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn jittered() {
+        let policy = Jittered::new(ConstantBackoff::new(3, Duration::from_millis(100)));
+        assert_eq!(policy.max_retries(), 3);
+        for retry in 1..=100 {
+            assert!(policy.delay(retry) <= Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn default_retryable_errors() {
+        let policy = ConstantBackoff::default();
+
+        assert!(policy.is_retryable(&ClientError::Connection("refused".to_string())));
+        assert!(policy.is_retryable(&ClientError::Timeout));
+        assert!(policy.is_retryable(&ClientError::Server(
+            -28,
+            "Loading block index...".to_string()
+        )));
+        assert!(policy.is_retryable(&ClientError::Status(503, "Service Unavailable".to_string())));
+
+        assert!(!policy.is_retryable(&ClientError::Server(-5, "not found".to_string())));
+        assert!(!policy.is_retryable(&ClientError::Status(401, "Unauthorized".to_string())));
+        assert!(!policy.is_retryable(&ClientError::Body("body".to_string())));
+    }
+}
@@ -48,3 +48,57 @@ pub mod corepc_node_helpers {
         (bitcoind, client)
     }
 }
+
+#[cfg(test)]
+pub mod transport_helpers {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde_json::Value;
+
+    use crate::{transport::Transport, ClientResult};
+
+    /// A [`Transport`] that answers requests with a closure, counting the requests it sees.
+    pub struct FnTransport<F> {
+        handler: F,
+        requests: AtomicUsize,
+    }
+
+    impl<F> std::fmt::Debug for FnTransport<F> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("FnTransport")
+                .field("requests", &self.requests)
+                .finish()
+        }
+    }
+
+    impl<F> FnTransport<F>
+    where
+        F: Fn(usize, &Value, Option<&str>) -> ClientResult<String> + Send + Sync,
+    {
+        pub fn new(handler: F) -> Self {
+            Self {
+                handler,
+                requests: AtomicUsize::new(0),
+            }
+        }
+
+        /// Number of requests seen so far.
+        pub fn requests(&self) -> usize {
+            self.requests.load(Ordering::Acquire)
+        }
+    }
+
+    impl<F> Transport for FnTransport<F>
+    where
+        F: Fn(usize, &Value, Option<&str>) -> ClientResult<String> + Send + Sync,
+    {
+        async fn send_request(
+            &self,
+            request: &Value,
+            wallet: Option<&str>,
+        ) -> ClientResult<String> {
+            let n = self.requests.fetch_add(1, Ordering::AcqRel);
+            (self.handler)(n, request, wallet)
+        }
+    }
+}
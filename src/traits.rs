@@ -1,16 +1,27 @@
-use bitcoin::{bip32::Xpriv, block::Header, Address, Block, BlockHash, Network, Transaction, Txid};
-use std::future::Future;
+use bitcoin::{
+    bip32::Xpriv, block::Header, Address, Block, BlockHash, MerkleBlock, Network, Psbt, Script,
+    Transaction, Txid,
+};
+use std::{collections::BTreeMap, future::Future};
 
 use crate::{
     client::ClientResult,
+    error::ClientError,
+    proof::verify_tx_out_proof,
     types::{
-        CreateRawTransaction, CreateRawTransactionInput, CreateRawTransactionOutput,
-        GetAddressInfo, GetBlockchainInfo, GetMempoolInfo, GetRawTransactionVerbosityOne,
-        GetRawTransactionVerbosityZero, GetTransaction, GetTxOut, ImportDescriptor,
-        ImportDescriptorResult, ListTransactions, ListUnspent, ListUnspentQueryOptions,
-        PreviousTransactionOutput, PsbtBumpFee, PsbtBumpFeeOptions, SignRawTransactionWithWallet,
-        SubmitPackage, TestMempoolAccept, WalletCreateFundedPsbt, WalletCreateFundedPsbtOptions,
-        WalletProcessPsbtResult,
+        AddNodeCommand, AnalyzePsbt, BannedSubnet, BlockStatsField, BlockStatsTarget, ChainFork,
+        ChainTip, CreateRawTransaction, CreateRawTransactionInput, CreateRawTransactionOutput,
+        DecodePsbt, DecodeRawTransaction, DecodeScript, DisconnectTarget, EstimateMode,
+        EstimateRawFee, GetAddressInfo, GetBlockFilter, GetBlockHeaderVerbose, GetBlockStats,
+        GetBlockTemplate, GetBlockVerbose, GetBlockchainInfo, GetDeploymentInfo, GetDescriptorInfo,
+        GetMempoolEntry, GetMempoolInfo, GetMiningInfo, GetNetTotals, GetNetworkInfo,
+        GetRawTransactionVerbosityOne, GetRawTransactionVerbosityZero, GetRpcInfo, GetTransaction,
+        GetTxOut, GetTxOutSetInfo, ImportDescriptor, ImportDescriptorResult, IndexInfo,
+        ListTransactions, ListUnspent, ListUnspentQueryOptions, PeerInfo,
+        PreviousTransactionOutput, PsbtBumpFee, PsbtBumpFeeOptions, ScanObject, ScanTxOutSet,
+        ScanTxOutSetStatus, SetBanCommand, SignRawTransactionWithWallet, SmartFeeEstimate,
+        SubmitBlockReason, SubmitPackage, TemplateRule, TestMempoolAccept, TxOutSetHashType,
+        WalletCreateFundedPsbt, WalletCreateFundedPsbtOptions, WalletProcessPsbtResult,
     },
 };
 
@@ -41,20 +52,99 @@ pub trait Reader {
     ///
     /// By default uses the estimate mode of `CONSERVATIVE` which is the
     /// default in Bitcoin Core v27.
+    ///
+    /// Returns the fee rate in sat/vB, rounded down, and falls back to 1 sat/vB if `bitcoind`
+    /// has no estimate. Use [`Reader::estimate_smart_fee_with_mode`] to tell these apart.
     fn estimate_smart_fee(
         &self,
         conf_target: u16,
     ) -> impl Future<Output = ClientResult<u64>> + Send;
 
+    /// Estimates the fee rate needed for a transaction to begin confirmation within
+    /// `conf_target` blocks.
+    ///
+    /// # Parameters
+    ///
+    /// - `conf_target`: Confirmation target in blocks.
+    /// - `mode`: The [`EstimateMode`].
+    ///
+    /// # Note
+    ///
+    /// Unlike [`Reader::estimate_smart_fee`], returns no fee rate when `bitcoind` has no
+    /// estimate, together with its reasons in [`SmartFeeEstimate::errors`].
+    fn estimate_smart_fee_with_mode(
+        &self,
+        conf_target: u16,
+        mode: EstimateMode,
+    ) -> impl Future<Output = ClientResult<SmartFeeEstimate>> + Send;
+
+    /// Gets the raw fee estimates of every time horizon that tracks the given confirmation
+    /// target.
+    ///
+    /// # Parameters
+    ///
+    /// - `conf_target`: Confirmation target in blocks.
+    /// - `threshold`: Share of transactions of a bucket that must have confirmed within the
+    ///   target for the bucket to pass, 0.95 if `None`.
+    fn estimate_raw_fee(
+        &self,
+        conf_target: u16,
+        threshold: Option<f64>,
+    ) -> impl Future<Output = ClientResult<EstimateRawFee>> + Send;
+
     /// Gets a [`Header`] with the given hash.
     fn get_block_header(
         &self,
         hash: &BlockHash,
     ) -> impl Future<Output = ClientResult<Header>> + Send;
 
+    /// Gets the header of the block with the given hash, with its height and its neighbours
+    /// in the block tree.
+    ///
+    /// Unlike [`Reader::get_block_height`], only needs the header, so also works for
+    /// headers-only and pruned blocks.
+    fn get_block_header_verbose(
+        &self,
+        hash: &BlockHash,
+    ) -> impl Future<Output = ClientResult<GetBlockHeaderVerbose>> + Send;
+
     /// Gets a [`Block`] with the given hash.
     fn get_block(&self, hash: &BlockHash) -> impl Future<Output = ClientResult<Block>> + Send;
 
+    /// Gets a block with the given hash, with decoded transactions and their fees.
+    ///
+    /// # Parameters
+    ///
+    /// - `hash`: The block hash.
+    /// - `include_prevouts`: Whether to include the outputs spent by the inputs, see
+    ///   [`BlockTransaction::prevouts`](crate::types::BlockTransaction::prevouts).
+    ///
+    /// # Note
+    ///
+    /// Calls `getblock` with verbosity 3 if `include_prevouts` is set, 2 otherwise. Fees and
+    /// prevouts need the undo data of the block, which pruned nodes may have deleted.
+    fn get_block_verbose(
+        &self,
+        hash: &BlockHash,
+        include_prevouts: bool,
+    ) -> impl Future<Output = ClientResult<GetBlockVerbose>> + Send;
+
+    /// Gets statistics about a block, such as its fees and fee rates.
+    ///
+    /// # Parameters
+    ///
+    /// - `target`: The hash or the height of the block.
+    /// - `fields`: The statistics to compute, all of them if `None`.
+    ///
+    /// # Note
+    ///
+    /// Most statistics need the undo data of the block, which pruned nodes may have deleted.
+    fn get_block_stats(
+        &self,
+        target: BlockStatsTarget,
+        fields: Option<&[BlockStatsField]>,
+    ) -> impl Future<Output = ClientResult<GetBlockStats>> + Send;
+
     /// Gets a block height with the given hash.
     fn get_block_height(&self, hash: &BlockHash) -> impl Future<Output = ClientResult<u64>> + Send;
 
@@ -65,6 +155,54 @@ pub trait Reader {
     /// Gets a [`Block`] at given height.
     fn get_block_at(&self, height: u64) -> impl Future<Output = ClientResult<Block>> + Send;
 
+    /// Gets the [`Header`]s at the given heights.
+    ///
+    /// Fails if any of the heights is not in the active chain.
+    ///
+    /// # Note
+    ///
+    /// Calls [`Reader::get_block_header_at`] once per height by default.
+    /// [`Client`](crate::client::Client) fetches all the headers in two batched requests.
+    fn get_block_headers_at(
+        &self,
+        heights: &[u64],
+    ) -> impl Future<Output = ClientResult<Vec<Header>>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let mut headers = Vec::with_capacity(heights.len());
+            for &height in heights {
+                headers.push(self.get_block_header_at(height).await?);
+            }
+            Ok(headers)
+        }
+    }
+
+    /// Gets the [`Block`]s at the given heights.
+    ///
+    /// Fails if any of the heights is not in the active chain.
+    ///
+    /// # Note
+    ///
+    /// Calls [`Reader::get_block_at`] once per height by default.
+    /// [`Client`](crate::client::Client) fetches all the blocks in two batched requests.
+    fn get_blocks_at(
+        &self,
+        heights: &[u64],
+    ) -> impl Future<Output = ClientResult<Vec<Block>>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let mut blocks = Vec::with_capacity(heights.len());
+            for &height in heights {
+                blocks.push(self.get_block_at(height).await?);
+            }
+            Ok(blocks)
+        }
+    }
+
     /// Gets the height of the most-work fully-validated chain.
     ///
     /// # Note
@@ -91,6 +229,31 @@ pub trait Reader {
     /// Returns details on the active state of the mempool.
     fn get_mempool_info(&self) -> impl Future<Output = ClientResult<GetMempoolInfo>> + Send;
 
+    /// Gets all transactions in the mempool with their fees, sizes and in-mempool relatives.
+    fn get_raw_mempool_verbose(
+        &self,
+    ) -> impl Future<Output = ClientResult<BTreeMap<Txid, GetMempoolEntry>>> + Send;
+
+    /// Gets the fees, sizes and in-mempool relatives of a mempool transaction.
+    fn get_mempool_entry(
+        &self,
+        txid: &Txid,
+    ) -> impl Future<Output = ClientResult<GetMempoolEntry>> + Send;
+
+    /// Gets all in-mempool ancestors of a mempool transaction, excluding the transaction
+    /// itself.
+    fn get_mempool_ancestors(
+        &self,
+        txid: &Txid,
+    ) -> impl Future<Output = ClientResult<BTreeMap<Txid, GetMempoolEntry>>> + Send;
+
+    /// Gets all in-mempool descendants of a mempool transaction, excluding the transaction
+    /// itself.
+    fn get_mempool_descendants(
+        &self,
+        txid: &Txid,
+    ) -> impl Future<Output = ClientResult<BTreeMap<Txid, GetMempoolEntry>>> + Send;
+
     /// Gets a raw transaction by its [`Txid`].
     fn get_raw_transaction_verbosity_zero(
         &self,
@@ -111,8 +274,203 @@ pub trait Reader {
         include_mempool: bool,
     ) -> impl Future<Output = ClientResult<GetTxOut>> + Send;
 
+    /// Gets statistics about the UTXO set at the chain tip.
+    ///
+    /// # Parameters
+    ///
+    /// - `hash_type`: The hash of the UTXO set to compute, [`TxOutSetHashType::HashSerialized3`]
+    ///   if `None`.
+    fn get_tx_out_set_info(
+        &self,
+        hash_type: Option<TxOutSetHashType>,
+    ) -> impl Future<Output = ClientResult<GetTxOutSetInfo>> + Send;
+
+    /// Scans the UTXO set for outputs matching the given descriptors.
+    ///
+    /// # Note
+    ///
+    /// Only one scan can run at a time. The call returns once the scan is done, which can take
+    /// minutes on mainnet. It can be monitored with [`Reader::scan_tx_out_set_status`] and
+    /// cancelled with [`Reader::scan_tx_out_set_abort`] from another task, in which case the
+    /// result has [`ScanTxOutSet::success`] set to false.
+    fn scan_tx_out_set_start(
+        &self,
+        scan_objects: &[ScanObject],
+    ) -> impl Future<Output = ClientResult<ScanTxOutSet>> + Send;
+
+    /// Gets the progress of the running UTXO set scan, or `None` if no scan is running.
+    fn scan_tx_out_set_status(
+        &self,
+    ) -> impl Future<Output = ClientResult<Option<ScanTxOutSetStatus>>> + Send;
+
+    /// Aborts the running UTXO set scan.
+    ///
+    /// Returns whether a scan was aborted.
+    fn scan_tx_out_set_abort(&self) -> impl Future<Output = ClientResult<bool>> + Send;
+
     /// Gets the underlying [`Network`] information.
     fn network(&self) -> impl Future<Output = ClientResult<Network>> + Send;
+
+    /// Gets the BIP 158 basic block filter and its filter header for the block with the given
+    /// hash.
+    ///
+    /// # Note
+    ///
+    /// Needs `bitcoind` to run with `-blockfilterindex=1`. See
+    /// [`FilterScanner`](crate::filter::FilterScanner) to match filters against scripts.
+    fn get_block_filter(
+        &self,
+        hash: &BlockHash,
+    ) -> impl Future<Output = ClientResult<GetBlockFilter>> + Send;
+
+    /// Gets a proof that the given transactions are included in a block.
+    ///
+    /// # Parameters
+    ///
+    /// - `txids`: The transactions to prove, all in the same block.
+    /// - `block_hash`: The block to look in. If `None`, `bitcoind` looks up the block of the
+    ///   first transaction, which needs `-txindex=1` unless it has an unspent output.
+    fn get_tx_out_proof(
+        &self,
+        txids: &[Txid],
+        block_hash: Option<&BlockHash>,
+    ) -> impl Future<Output = ClientResult<MerkleBlock>> + Send;
+
+    /// Verifies a transaction inclusion proof with `bitcoind`.
+    ///
+    /// Returns the transactions the proof commits to, or an empty list if the proof is
+    /// invalid. Fails if the block of the proof is not in the active chain.
+    ///
+    /// # Note
+    ///
+    /// This trusts `bitcoind` to verify the proof. See
+    /// [`Reader::verify_tx_out_proof_locally`] to verify it without doing so.
+    fn verify_tx_out_proof(
+        &self,
+        proof: &MerkleBlock,
+    ) -> impl Future<Output = ClientResult<Vec<Txid>>> + Send;
+
+    /// Verifies a transaction inclusion proof locally against the [`Header`] of its block
+    /// from [`Reader::get_block_header`].
+    ///
+    /// Returns the transactions the proof commits to. See
+    /// [`verify_tx_out_proof`] for the errors.
+    ///
+    /// # Note
+    ///
+    /// Only checks that `bitcoind` knows the block. The block may be stale, use
+    /// [`Reader::get_block_height`] and [`Reader::get_block_hash`] to check that it is part
+    /// of the active chain.
+    fn verify_tx_out_proof_locally(
+        &self,
+        proof: &MerkleBlock,
+    ) -> impl Future<Output = ClientResult<Vec<Txid>>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let header = self.get_block_header(&proof.header.block_hash()).await?;
+            verify_tx_out_proof(proof, &header)
+        }
+    }
+
+    /// Gets the tips of all known branches of the block tree, including the active chain,
+    /// stale branches and headers-only forks.
+    fn get_chain_tips(&self) -> impl Future<Output = ClientResult<Vec<ChainTip>>> + Send;
+
+    /// Finds the fork point of two blocks and the blocks to disconnect and connect to get from
+    /// the first one to the second one.
+    ///
+    /// Useful to follow a reorg from the last block seen to the new chain tip.
+    ///
+    /// # Note
+    ///
+    /// Walks back from both blocks one header at a time, so takes one
+    /// [`Reader::get_block_header_verbose`] call per block on either side of the fork, plus one
+    /// for the fork point. Both blocks must be known to the node, but neither has to be in the
+    /// active chain nor have its block data, as on headers-only forks.
+    fn find_fork(
+        &self,
+        from: &BlockHash,
+        to: &BlockHash,
+    ) -> impl Future<Output = ClientResult<ChainFork>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let parent = |header: &GetBlockHeaderVerbose| {
+                header
+                    .previous_block_hash
+                    .ok_or_else(|| ClientError::Other("Blocks have no common ancestor".to_string()))
+            };
+            let mut from = self.get_block_header_verbose(from).await?;
+            let mut to = self.get_block_header_verbose(to).await?;
+            let mut disconnected = Vec::new();
+            let mut connected = Vec::new();
+            while from.hash != to.hash {
+                if from.height >= to.height {
+                    disconnected.push(from.hash);
+                    from = self.get_block_header_verbose(&parent(&from)?).await?;
+                }
+                if to.height > from.height {
+                    connected.push(to.hash);
+                    to = self.get_block_header_verbose(&parent(&to)?).await?;
+                }
+            }
+            connected.reverse();
+            Ok(ChainFork {
+                fork_point: from.hash,
+                fork_height: from.height,
+                disconnected,
+                connected,
+            })
+        }
+    }
+
+    /// Decodes a transaction with `bitcoind`.
+    ///
+    /// Adds what is not in the [`Transaction`] itself, such as its sizes and the types and
+    /// addresses of its output scripts.
+    fn decode_raw_transaction(
+        &self,
+        tx: &Transaction,
+    ) -> impl Future<Output = ClientResult<DecodeRawTransaction>> + Send;
+
+    /// Decodes a script with `bitcoind`, such as a redeem or witness script.
+    fn decode_script(
+        &self,
+        script: &Script,
+    ) -> impl Future<Output = ClientResult<DecodeScript>> + Send;
+
+    /// Gets information about an output descriptor, such as its checksum.
+    ///
+    /// The descriptor does not need a checksum. [`GetDescriptorInfo::descriptor`] has one and
+    /// can be passed to [`Reader::derive_addresses`], but has no private keys. To pass a
+    /// descriptor with private keys to [`Signer::import_descriptors`], append
+    /// [`GetDescriptorInfo::checksum`] to it instead.
+    fn get_descriptor_info(
+        &self,
+        descriptor: &str,
+    ) -> impl Future<Output = ClientResult<GetDescriptorInfo>> + Send;
+
+    /// Derives the addresses of an output descriptor.
+    ///
+    /// # Parameters
+    ///
+    /// - `descriptor`: The descriptor, with its checksum.
+    /// - `range`: The first and last index to derive, both included. Only for ranged
+    ///   descriptors.
+    ///
+    /// # Note
+    ///
+    /// The addresses are checked against the network of `bitcoind`, and
+    /// [`ClientError::WrongNetworkAddress`](crate::error::ClientError::WrongNetworkAddress)
+    /// is returned if one of them does not belong to it.
+    fn derive_addresses(
+        &self,
+        descriptor: &str,
+        range: Option<(u32, u32)>,
+    ) -> impl Future<Output = ClientResult<Vec<Address>>> + Send;
 }
 
 /// Broadcasting functionality that any Bitcoin client that interacts with the
@@ -236,8 +594,9 @@ pub trait Wallet {
     ///
     /// # Note
     ///
-    /// The returned PSBT is not signed and requires further processing with `wallet_process_psbt`
-    /// or `finalize_psbt` before it can be broadcast to the network.
+    /// The returned PSBT is not signed and requires further processing with
+    /// [`Signer::wallet_process_psbt`] or [`PsbtRpc::finalize_psbt`] before it can be broadcast
+    /// to the network.
     fn wallet_create_funded_psbt(
         &self,
         inputs: &[CreateRawTransactionInput],
@@ -344,7 +703,9 @@ pub trait Signer {
     /// Gets the underlying [`Xpriv`] from the wallet.
     fn get_xpriv(&self) -> impl Future<Output = ClientResult<Option<Xpriv>>> + Send;
 
-    /// Imports the descriptors into the wallet.
+    /// Imports the descriptors into the wallet named `wallet_name`.
+    ///
+    /// The wallet is created and loaded first if needed.
     fn import_descriptors(
         &self,
         descriptors: Vec<ImportDescriptor>,
@@ -355,7 +716,7 @@ pub trait Signer {
     ///
     /// # Parameters
     ///
-    /// - `psbt`: The PSBT to process as a base64 string.
+    /// - `psbt`: The PSBT to process.
     /// - `sign`: Whether to sign the transaction (default: true).
     /// - `sighashtype`: Optional signature hash type to use.
     /// - `bip32_derivs`: Whether to include BIP32 derivation paths.
@@ -365,7 +726,7 @@ pub trait Signer {
     /// Returns a [`WalletProcessPsbtResult`] with the processed PSBT and completion status.
     fn wallet_process_psbt(
         &self,
-        psbt: &str,
+        psbt: &Psbt,
         sign: Option<bool>,
         sighashtype: Option<crate::types::SighashType>,
         bip32_derivs: Option<bool>,
@@ -398,3 +759,344 @@ pub trait Signer {
         options: Option<PsbtBumpFeeOptions>,
     ) -> impl Future<Output = ClientResult<PsbtBumpFee>> + Send;
 }
+
+/// Peer-to-peer networking functionality of a Bitcoin client, to monitor and manage the
+/// connections of the node.
+///
+/// # Note
+///
+/// This is a fully `async` trait. The user should be responsible for
+/// handling the `async` nature of the trait methods. And if implementing
+/// this trait for a specific type that is not `async`, the user should
+/// consider wrapping with [`tokio`](https://tokio.rs)'s
+/// [`spawn_blocking`](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html)
+/// or any other method.
+pub trait NetworkControl {
+    /// Gets the state of the P2P networking of the node.
+    fn get_network_info(&self) -> impl Future<Output = ClientResult<GetNetworkInfo>> + Send;
+
+    /// Gets the connected peers.
+    fn get_peer_info(&self) -> impl Future<Output = ClientResult<Vec<PeerInfo>>> + Send;
+
+    /// Gets the number of connections to peers.
+    fn get_connection_count(&self) -> impl Future<Output = ClientResult<u64>> + Send;
+
+    /// Gets the network traffic statistics of the node.
+    fn get_net_totals(&self) -> impl Future<Output = ClientResult<GetNetTotals>> + Send;
+
+    /// Adds, removes or tries a node to connect to.
+    ///
+    /// # Parameters
+    ///
+    /// - `node`: The address of the node, such as `203.0.113.7:8333`.
+    /// - `command`: The [`AddNodeCommand`].
+    fn add_node(
+        &self,
+        node: &str,
+        command: AddNodeCommand,
+    ) -> impl Future<Output = ClientResult<()>> + Send;
+
+    /// Disconnects a peer.
+    fn disconnect_node(
+        &self,
+        target: &DisconnectTarget,
+    ) -> impl Future<Output = ClientResult<()>> + Send;
+
+    /// Bans or unbans a subnet.
+    ///
+    /// # Parameters
+    ///
+    /// - `subnet`: The IP address or subnet to ban, such as `192.168.0.6` or `192.168.0.0/24`.
+    /// - `command`: The [`SetBanCommand`].
+    /// - `ban_time`: How long to ban for in seconds, 24 hours or `-bantime` if `None`. Ignored
+    ///   when unbanning.
+    fn set_ban(
+        &self,
+        subnet: &str,
+        command: SetBanCommand,
+        ban_time: Option<u64>,
+    ) -> impl Future<Output = ClientResult<()>> + Send;
+
+    /// Gets the banned subnets.
+    fn list_banned(&self) -> impl Future<Output = ClientResult<Vec<BannedSubnet>>> + Send;
+
+    /// Enables or disables all P2P networking.
+    ///
+    /// Returns whether networking is active afterwards.
+    fn set_network_active(&self, active: bool) -> impl Future<Output = ClientResult<bool>> + Send;
+}
+
+/// Mining functionality of a Bitcoin client, to build blocks on top of the chain tip and
+/// submit them.
+///
+/// # Note
+///
+/// This is a fully `async` trait. The user should be responsible for
+/// handling the `async` nature of the trait methods. And if implementing
+/// this trait for a specific type that is not `async`, the user should
+/// consider wrapping with [`tokio`](https://tokio.rs)'s
+/// [`spawn_blocking`](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html)
+/// or any other method.
+pub trait Mining {
+    /// Gets a template to build the next block from.
+    ///
+    /// # Parameters
+    ///
+    /// - `rules`: The rules supported by the caller. `bitcoind` requires
+    ///   [`TemplateRule::Segwit`], and [`TemplateRule::Signet`] on signet.
+    ///
+    /// # Note
+    ///
+    /// See [`GetBlockTemplate::block`] to assemble a block from the template.
+    fn get_block_template(
+        &self,
+        rules: &[TemplateRule],
+    ) -> impl Future<Output = ClientResult<GetBlockTemplate>> + Send;
+
+    /// Gets mining-related information, such as the difficulty and network hash rate.
+    fn get_mining_info(&self) -> impl Future<Output = ClientResult<GetMiningInfo>> + Send;
+
+    /// Estimates the hashes per second of the network.
+    ///
+    /// # Parameters
+    ///
+    /// - `blocks`: The number of blocks to average over, 120 if `None`.
+    /// - `height`: The height to estimate at, the chain tip if `None`.
+    fn get_network_hashps(
+        &self,
+        blocks: Option<u64>,
+        height: Option<u64>,
+    ) -> impl Future<Output = ClientResult<f64>> + Send;
+
+    /// Submits a block.
+    ///
+    /// Returns `None` if the block was accepted, or why not.
+    fn submit_block(
+        &self,
+        block: &Block,
+    ) -> impl Future<Output = ClientResult<Option<SubmitBlockReason>>> + Send;
+
+    /// Submits a block header, to make the block known before it is fully validated.
+    ///
+    /// Returns `None` if the header was accepted, or why not.
+    fn submit_header(
+        &self,
+        header: &Header,
+    ) -> impl Future<Output = ClientResult<Option<SubmitBlockReason>>> + Send;
+}
+
+/// Control over the chain of a regtest node, to set up deterministic test scenarios.
+///
+/// # Note
+///
+/// Every call first checks with [`Reader::network`] that `bitcoind` runs on
+/// [`Network::Regtest`], and fails with
+/// [`ClientError::NotRegtest`](crate::error::ClientError::NotRegtest) otherwise, so a
+/// misconfigured test cannot mine on or invalidate blocks of a real network.
+///
+/// This is a fully `async` trait. The user should be responsible for
+/// handling the `async` nature of the trait methods. And if implementing
+/// this trait for a specific type that is not `async`, the user should
+/// consider wrapping with [`tokio`](https://tokio.rs)'s
+/// [`spawn_blocking`](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html)
+/// or any other method.
+pub trait RegtestControl {
+    /// Mines blocks paying to the given address.
+    ///
+    /// Returns the hashes of the mined blocks.
+    fn generate_to_address(
+        &self,
+        count: u64,
+        address: &Address,
+    ) -> impl Future<Output = ClientResult<Vec<BlockHash>>> + Send;
+
+    /// Mines blocks paying to the given output descriptor.
+    ///
+    /// Returns the hashes of the mined blocks.
+    fn generate_to_descriptor(
+        &self,
+        count: u64,
+        descriptor: &str,
+    ) -> impl Future<Output = ClientResult<Vec<BlockHash>>> + Send;
+
+    /// Mines a block with exactly the given transactions, paying to the given address.
+    ///
+    /// The transactions do not have to be in the mempool, but must be valid in order.
+    fn generate_block(
+        &self,
+        address: &Address,
+        transactions: &[Transaction],
+    ) -> impl Future<Output = ClientResult<BlockHash>> + Send;
+
+    /// Sets the time of `bitcoind` to the given Unix timestamp, or back to the system time
+    /// if 0.
+    fn set_mock_time(&self, timestamp: u64) -> impl Future<Output = ClientResult<()>> + Send;
+
+    /// Marks a block and its descendants as invalid, reorganizing the chain away from them.
+    fn invalidate_block(&self, hash: &BlockHash) -> impl Future<Output = ClientResult<()>> + Send;
+
+    /// Removes the invalid mark of a block and its ancestors set by
+    /// [`RegtestControl::invalidate_block`], reorganizing the chain back if it has more work.
+    fn reconsider_block(&self, hash: &BlockHash) -> impl Future<Output = ClientResult<()>> + Send;
+}
+
+/// Administration functionality of a Bitcoin client, as used by operations tooling.
+///
+/// # Note
+///
+/// This is a fully `async` trait. The user should be responsible for
+/// handling the `async` nature of the trait methods. And if implementing
+/// this trait for a specific type that is not `async`, the user should
+/// consider wrapping with [`tokio`](https://tokio.rs)'s
+/// [`spawn_blocking`](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html)
+/// or any other method.
+pub trait NodeControl {
+    /// Gets how long `bitcoind` has been running in seconds.
+    fn uptime(&self) -> impl Future<Output = ClientResult<u64>> + Send;
+
+    /// Gets the commands being executed by the RPC server.
+    fn get_rpc_info(&self) -> impl Future<Output = ClientResult<GetRpcInfo>> + Send;
+
+    /// Turns debug logging categories on or off.
+    ///
+    /// Returns whether each category is logged afterwards. Call with empty lists to only get
+    /// the current state.
+    ///
+    /// # Parameters
+    ///
+    /// - `include`: The categories to log, such as `net` or `mempool`. `all` turns on every
+    ///   category.
+    /// - `exclude`: The categories to stop logging, taking precedence over `include`.
+    fn logging(
+        &self,
+        include: &[&str],
+        exclude: &[&str],
+    ) -> impl Future<Output = ClientResult<BTreeMap<String, bool>>> + Send;
+
+    /// Requests `bitcoind` to shut down.
+    ///
+    /// # Note
+    ///
+    /// Returns once the shutdown is requested, not once it is done.
+    fn stop(&self) -> impl Future<Output = ClientResult<()>> + Send;
+
+    /// Prunes the block and undo files up to the given height.
+    ///
+    /// Returns the height of the last block pruned. Needs `bitcoind` to run with
+    /// `-prune`.
+    ///
+    /// # Parameters
+    ///
+    /// - `height`: The height to prune up to. Values above 1000000000 are taken as a Unix
+    ///   timestamp, pruning the blocks more than 2 hours older.
+    fn prune_blockchain(&self, height: u64) -> impl Future<Output = ClientResult<u64>> + Send;
+
+    /// Gets the sync state of the optional indexes, such as `txindex` or
+    /// `basic block filter index`.
+    ///
+    /// # Parameters
+    ///
+    /// - `index_name`: Only get the state of this index, if given.
+    fn get_index_info(
+        &self,
+        index_name: Option<&str>,
+    ) -> impl Future<Output = ClientResult<BTreeMap<String, IndexInfo>>> + Send;
+
+    /// Gets the state of the softfork deployments at the given block, the chain tip if
+    /// `None`.
+    fn get_deployment_info(
+        &self,
+        block_hash: Option<&BlockHash>,
+    ) -> impl Future<Output = ClientResult<GetDeploymentInfo>> + Send;
+}
+
+/// PSBT functionality of a Bitcoin client, covering the BIP 174 roles that do not need a
+/// wallet.
+///
+/// Together with [`Wallet::wallet_create_funded_psbt`] and [`Signer::wallet_process_psbt`],
+/// this covers the whole PSBT workflow, such as collecting signatures from several signers.
+///
+/// # Note
+///
+/// This is a fully `async` trait. The user should be responsible for
+/// handling the `async` nature of the trait methods. And if implementing
+/// this trait for a specific type that is not `async`, the user should
+/// consider wrapping with [`tokio`](https://tokio.rs)'s
+/// [`spawn_blocking`](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html)
+/// or any other method.
+pub trait PsbtRpc {
+    /// Creates an unsigned PSBT spending the given inputs to the given outputs.
+    ///
+    /// # Parameters
+    ///
+    /// - `locktime`: The lock time of the transaction. Defaults to 0.
+    /// - `replaceable`: Whether the transaction signals BIP 125 replaceability. Defaults to
+    ///   `bitcoind`'s default.
+    fn create_psbt(
+        &self,
+        inputs: &[CreateRawTransactionInput],
+        outputs: &[CreateRawTransactionOutput],
+        locktime: Option<u32>,
+        replaceable: Option<bool>,
+    ) -> impl Future<Output = ClientResult<Psbt>> + Send;
+
+    /// Combines PSBTs of the same transaction into one, merging their signatures and other
+    /// data.
+    fn combine_psbt(&self, psbts: &[Psbt]) -> impl Future<Output = ClientResult<Psbt>> + Send;
+
+    /// Finalizes the inputs of a PSBT that have all their signatures.
+    ///
+    /// If all the inputs are finalized and `extract` is `true` or `None`, the signed
+    /// transaction is extracted into [`WalletProcessPsbtResult::hex`] and the PSBT is not
+    /// returned.
+    fn finalize_psbt(
+        &self,
+        psbt: &Psbt,
+        extract: Option<bool>,
+    ) -> impl Future<Output = ClientResult<WalletProcessPsbtResult>> + Send;
+
+    /// Joins PSBTs of different transactions into one, with the inputs and outputs of all of
+    /// them.
+    fn join_psbts(&self, psbts: &[Psbt]) -> impl Future<Output = ClientResult<Psbt>> + Send;
+
+    /// Adds the UTXOs spent by a PSBT from the UTXO set and the mempool.
+    ///
+    /// # Parameters
+    ///
+    /// - `descriptors`: Descriptors to add the scripts and key origins of the inputs from,
+    ///   as in [`Reader::scan_tx_out_set_start`].
+    fn utxo_update_psbt(
+        &self,
+        psbt: &Psbt,
+        descriptors: Option<&[ScanObject]>,
+    ) -> impl Future<Output = ClientResult<Psbt>> + Send;
+
+    /// Converts a transaction into a PSBT.
+    ///
+    /// # Parameters
+    ///
+    /// - `permit_sig_data`: Whether to drop the signatures of the transaction instead of
+    ///   failing if it has any. Defaults to `false`.
+    fn convert_to_psbt(
+        &self,
+        tx: &Transaction,
+        permit_sig_data: Option<bool>,
+    ) -> impl Future<Output = ClientResult<Psbt>> + Send;
+
+    /// Decodes a PSBT with `bitcoind`.
+    ///
+    /// Gets the decoded unsigned transaction and the fee, if the value of every input is
+    /// known.
+    fn decode_psbt(&self, psbt: &Psbt) -> impl Future<Output = ClientResult<DecodePsbt>> + Send;
+
+    /// Analyzes a PSBT with `bitcoind`.
+    ///
+    /// Gets the next role of the PSBT and of each input, the keys and scripts missing to sign
+    /// them, and the estimated size and fee rate of the signed transaction.
+    ///
+    /// # Note
+    ///
+    /// Useful to find out why [`WalletProcessPsbtResult::complete`] is `false`. Only the
+    /// inputs are analyzed, so the PSBT does not have to belong to the wallet.
+    fn analyze_psbt(&self, psbt: &Psbt) -> impl Future<Output = ClientResult<AnalyzePsbt>> + Send;
+}
@@ -0,0 +1,656 @@
+//! Transports that carry JSON-RPC requests to `bitcoind`.
+//!
+//! The [`Client`](crate::Client) builds JSON-RPC requests, matches responses, and retries
+//! failures. A [`Transport`] only moves a serialized request to `bitcoind` and hands back the
+//! raw response. [`HttpTransport`] is the default and talks to `bitcoind` over HTTP.
+//!
+//! [`RecordingTransport`] and [`ReplayTransport`] capture a session against a real `bitcoind`
+//! into a [`Fixture`] and play it back offline, for deterministic tests.
+use std::{
+    fs,
+    future::Future,
+    io,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+
+use reqwest::{
+    header::{HeaderValue, AUTHORIZATION},
+    Client as ReqwestClient,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::*;
+
+use crate::{
+    client::{Auth, ClientResult, Response},
+    error::ClientError,
+};
+
+/// Carries JSON-RPC requests to a `bitcoind` instance.
+///
+/// # Note
+///
+/// This is a fully `async` trait. Implementations only move bytes around: retries happen in the
+/// [`Client`](crate::Client) according to its [`RetryPolicy`](crate::retry::RetryPolicy), so a
+/// transport should make a single attempt and report failures to deliver the request with the
+/// matching [`ClientError`] variant, such as [`ClientError::Connection`] or
+/// [`ClientError::Timeout`].
+///
+/// RPC errors are not transport failures. They stay in the response body, and the
+/// [`Client`](crate::Client) turns them into [`ClientError::Server`] and retries them the same
+/// way whatever the transport.
+pub trait Transport: std::fmt::Debug + Send + Sync {
+    /// Sends a JSON-RPC request and returns the raw response body.
+    ///
+    /// # Parameters
+    ///
+    /// - `request`: A single JSON-RPC request object, or an array of them for batches.
+    /// - `wallet`: The wallet the request is scoped to, if any. Over HTTP this is the
+    ///   `/wallet/<name>` endpoint.
+    ///
+    /// # Returns
+    ///
+    /// The raw JSON-RPC response body, including responses that hold an RPC error.
+    fn send_request(
+        &self,
+        request: &Value,
+        wallet: Option<&str>,
+    ) -> impl Future<Output = ClientResult<String>> + Send;
+}
+
+/// The default [`Transport`], talking to `bitcoind` over HTTP.
+///
+/// Usually built through the [`ClientBuilder`](crate::ClientBuilder).
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    /// The URL of the `bitcoind` instance.
+    url: String,
+
+    /// The underlying `async` HTTP client.
+    client: ReqwestClient,
+
+    /// The authentication method used to connect to `bitcoind`.
+    auth: Auth,
+
+    /// The current `Authorization` header.
+    ///
+    /// # Implementation Details
+    ///
+    /// Shared behind an [`Arc`] so that every clone of the transport picks up a reloaded
+    /// cookie.
+    authorization: Arc<RwLock<HeaderValue>>,
+}
+
+impl HttpTransport {
+    /// Creates a new [`HttpTransport`] from a pre-configured [`reqwest::Client`].
+    ///
+    /// Reads the cookie file right away when using [`Auth::CookieFile`].
+    pub fn new(url: String, client: ReqwestClient, auth: Auth) -> ClientResult<Self> {
+        let authorization = auth.authorization()?;
+        Ok(Self {
+            url,
+            client,
+            auth,
+            authorization: Arc::new(RwLock::new(authorization)),
+        })
+    }
+
+    /// Returns the URL that requests are sent to.
+    ///
+    /// This is the `bitcoind` URL, followed by `/wallet/<name>` if the request is scoped
+    /// to a wallet.
+    pub(crate) fn endpoint(&self, wallet: Option<&str>) -> String {
+        match wallet {
+            Some(wallet) => format!(
+                "{}/wallet/{}",
+                self.url.trim_end_matches('/'),
+                percent_encode(wallet)
+            ),
+            None => self.url.clone(),
+        }
+    }
+
+    /// Re-reads the credentials from the [`Auth`] method and updates the `Authorization`
+    /// header shared by all clones of this transport.
+    fn reload_authorization(&self) -> ClientResult<()> {
+        let authorization = self.auth.authorization()?;
+        *self
+            .authorization
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = authorization;
+        Ok(())
+    }
+
+    /// Posts a JSON-RPC request body to `bitcoind` once and returns the raw response body.
+    async fn post(&self, body: &Value, wallet: Option<&str>) -> ClientResult<String> {
+        let authorization = self
+            .authorization
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let response = self
+            .client
+            .post(self.endpoint(wallet))
+            .header(AUTHORIZATION, authorization)
+            .json(body)
+            .send()
+            .await;
+        trace!(?response, "Response received");
+        match response {
+            Ok(resp) => {
+                // Check HTTP status code first before parsing body
+                let status = resp.status();
+                let raw_response = resp
+                    .text()
+                    .await
+                    .map_err(|e| ClientError::Parse(e.to_string()))?;
+                trace!(%raw_response, "Raw response received");
+
+                if status.is_client_error() || status.is_server_error() {
+                    // `bitcoind` reports RPC errors of JSON-RPC 1.0 requests with an HTTP error
+                    // status, the RPC error itself is in the body and left to the `Client`.
+                    if let Ok(Response { error: Some(_), .. }) =
+                        serde_json::from_str::<Response<Value>>(&raw_response)
+                    {
+                        return Ok(raw_response);
+                    }
+                    let reason = status.canonical_reason().unwrap_or("Unknown").to_string();
+                    return Err(ClientError::Status(status.as_u16(), reason));
+                }
+                Ok(raw_response)
+            }
+            Err(err) => {
+                warn!(err = %err, "Error calling bitcoin client");
+
+                let e = if err.is_body() {
+                    ClientError::Body(err.to_string())
+                } else if err.is_status() {
+                    match err.status() {
+                        Some(code) => ClientError::Status(code.as_u16(), err.to_string()),
+                        _ => ClientError::Other(err.to_string()),
+                    }
+                } else if err.is_decode() {
+                    ClientError::MalformedResponse(err.to_string())
+                } else if err.is_connect() {
+                    ClientError::Connection(err.to_string())
+                } else if err.is_timeout() {
+                    ClientError::Timeout
+                } else if err.is_request() {
+                    ClientError::Request(err.to_string())
+                } else if err.is_builder() {
+                    ClientError::ReqBuilder(err.to_string())
+                } else if err.is_redirect() {
+                    ClientError::HttpRedirect(err.to_string())
+                } else {
+                    ClientError::Other("Unknown error".to_string())
+                };
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Transport for HttpTransport {
+    /// Posts the request to `bitcoind`.
+    ///
+    /// When using [`Auth::CookieFile`], a `401 Unauthorized` response re-reads the cookie
+    /// file and retries the request once.
+    async fn send_request(&self, request: &Value, wallet: Option<&str>) -> ClientResult<String> {
+        match self.post(request, wallet).await {
+            Err(ClientError::Status(401, reason)) if matches!(self.auth, Auth::CookieFile(_)) => {
+                warn!(%reason, "Unauthorized, reloading cookie file and retrying");
+                self.reload_authorization()?;
+                self.post(request, wallet).await
+            }
+            result => result,
+        }
+    }
+}
+
+/// A single request seen by a [`RecordingTransport`], with its outcome.
+///
+/// JSON-RPC ids are stripped from both the request and the response, so that a replay does not
+/// depend on the ids handed out by the [`Client`](crate::Client).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// The wallet the request was scoped to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+
+    /// The request: an object with the `method` and `params`, or an array of them for batches.
+    pub request: Value,
+
+    /// The response, or a JSON string holding the raw body if it was not valid JSON.
+    ///
+    /// Batch responses are sorted in request order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+
+    /// The error returned by the transport instead of a response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ClientError>,
+}
+
+/// A recorded session, as saved by a [`RecordingTransport`] and played back by a
+/// [`ReplayTransport`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// The interactions in the order they happened.
+    pub interactions: Vec<Interaction>,
+}
+
+impl Fixture {
+    /// Reads a [`Fixture`] from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Writes the [`Fixture`] to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// A [`Transport`] that records every request going through another transport.
+///
+/// ```no_run
+/// # use bitcoind_async_client::{
+/// #     retry::ConstantBackoff, traits::Reader, transport::RecordingTransport, Auth, Client,
+/// # };
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let transport = Client::builder("http://localhost:18443")
+///     .auth(Auth::CookieFile("/tmp/regtest/.cookie".into()))
+///     .build_transport()?;
+/// let client = Client::from_transport(
+///     RecordingTransport::new(transport),
+///     ConstantBackoff::default(),
+/// );
+///
+/// client.get_blockchain_info().await?;
+/// client.transport().fixture().save("tests/fixtures/getblockchaininfo.json")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RecordingTransport<T> {
+    /// The transport that actually sends the requests.
+    inner: T,
+
+    /// The interactions recorded so far.
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    /// Records the requests sent through `inner`.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            interactions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns the interactions recorded so far.
+    pub fn fixture(&self) -> Fixture {
+        Fixture {
+            interactions: self
+                .interactions
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+        }
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn send_request(&self, request: &Value, wallet: Option<&str>) -> ClientResult<String> {
+        let result = self.inner.send_request(request, wallet).await;
+        let (response, error) = match &result {
+            Ok(raw_response) => {
+                let response = match serde_json::from_str::<Value>(raw_response) {
+                    Ok(response) => sort_and_strip_ids(request, response),
+                    Err(_) => Value::String(raw_response.clone()),
+                };
+                (Some(response), None)
+            }
+            Err(error) => (None, Some(error.clone())),
+        };
+        self.interactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Interaction {
+                wallet: wallet.map(str::to_string),
+                request: strip_ids(request.clone()),
+                response,
+                error,
+            });
+        result
+    }
+}
+
+/// A [`Transport`] that answers requests from a [`Fixture`] instead of a `bitcoind`.
+///
+/// Requests that are not in the fixture fail with [`ClientError::Replay`]. By default,
+/// requests are matched in any order, and a request that was already replayed as often as it
+/// was recorded gets its last recorded response again, which suits polling loops. A
+/// [strict](ReplayTransport::strict) replay requires the exact same requests in the exact same
+/// order.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    /// The recorded session.
+    fixture: Fixture,
+
+    /// Whether requests must follow the recording in order.
+    strict: bool,
+
+    /// Which interactions have been replayed.
+    replayed: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    /// Replays the fixture, matching requests in any order.
+    pub fn new(fixture: Fixture) -> Self {
+        let replayed = Mutex::new(vec![false; fixture.interactions.len()]);
+        Self {
+            fixture,
+            strict: false,
+            replayed,
+        }
+    }
+
+    /// Replays the fixture, failing as soon as a request diverges from the recording.
+    pub fn strict(fixture: Fixture) -> Self {
+        Self {
+            strict: true,
+            ..Self::new(fixture)
+        }
+    }
+
+    /// Reads a fixture from a JSON file and replays it, matching requests in any order.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(Fixture::load(path)?))
+    }
+
+    /// Number of recorded interactions that have not been replayed yet.
+    ///
+    /// A replay that went exactly like the recording ends with none left.
+    pub fn remaining(&self) -> usize {
+        self.replayed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|replayed| !**replayed)
+            .count()
+    }
+
+    /// Finds the interaction answering the given request and marks it as replayed.
+    fn find(&self, request: &Value, wallet: Option<&str>) -> ClientResult<&Interaction> {
+        let mut replayed = self.replayed.lock().unwrap_or_else(PoisonError::into_inner);
+        let matches = |interaction: &Interaction| {
+            interaction.wallet.as_deref() == wallet && interaction.request == *request
+        };
+        let interactions = &self.fixture.interactions;
+
+        let index = if self.strict {
+            let next = replayed.iter().take_while(|replayed| **replayed).count();
+            match interactions.get(next) {
+                Some(interaction) if matches(interaction) => Some(next),
+                Some(interaction) => {
+                    return Err(ClientError::Replay(format!(
+                        "expected {} but got {request}",
+                        interaction.request
+                    )))
+                }
+                None => None,
+            }
+        } else {
+            (0..interactions.len())
+                .find(|&index| !replayed[index] && matches(&interactions[index]))
+                .or_else(|| (0..interactions.len()).rfind(|&index| matches(&interactions[index])))
+        };
+
+        let index = index
+            .ok_or_else(|| ClientError::Replay(format!("no recorded response for {request}")))?;
+        replayed[index] = true;
+        Ok(&interactions[index])
+    }
+}
+
+impl Transport for ReplayTransport {
+    async fn send_request(&self, request: &Value, wallet: Option<&str>) -> ClientResult<String> {
+        let interaction = self.find(&strip_ids(request.clone()), wallet)?;
+        if let Some(error) = &interaction.error {
+            return Err(error.clone());
+        }
+        match &interaction.response {
+            Some(Value::String(raw_response)) => Ok(raw_response.clone()),
+            Some(response) => Ok(restore_ids(request, response.clone()).to_string()),
+            None => Err(ClientError::Replay(format!(
+                "recording for {request} has neither a response nor an error"
+            ))),
+        }
+    }
+}
+
+/// Removes the JSON-RPC ids from a request or response, or from each one in a batch.
+fn strip_ids(mut value: Value) -> Value {
+    match &mut value {
+        Value::Object(object) => {
+            object.remove("id");
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| {
+            if let Value::Object(object) = value {
+                object.remove("id");
+            }
+        }),
+        _ => {}
+    }
+    value
+}
+
+/// Sorts a batch response in request order, then removes the JSON-RPC ids.
+fn sort_and_strip_ids(request: &Value, mut response: Value) -> Value {
+    if let (Value::Array(requests), Value::Array(responses)) = (request, &mut response) {
+        responses.sort_by_key(|response| {
+            requests
+                .iter()
+                .position(|request| request["id"] == response["id"])
+                .unwrap_or(usize::MAX)
+        });
+    }
+    strip_ids(response)
+}
+
+/// Puts the JSON-RPC ids of a request back into a recorded response.
+fn restore_ids(request: &Value, mut response: Value) -> Value {
+    match (request, &mut response) {
+        (Value::Array(requests), Value::Array(responses)) => {
+            for (request, response) in requests.iter().zip(responses) {
+                if let Value::Object(object) = response {
+                    object.insert("id".to_string(), request["id"].clone());
+                }
+            }
+        }
+        (_, Value::Object(object)) => {
+            object.insert("id".to_string(), request["id"].clone());
+        }
+        _ => {}
+    }
+    response
+}
+
+/// Percent-encodes a URL path segment, such as a wallet name.
+///
+/// Only RFC 3986 unreserved characters are left as-is.
+fn percent_encode(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        retry::ConstantBackoff, test_utils::transport_helpers::FnTransport, traits::Reader, Client,
+    };
+
+    /// A fake `bitcoind` with 101 blocks that answers batches in reverse order.
+    fn fake_bitcoind() -> impl Fn(usize, &Value, Option<&str>) -> ClientResult<String> {
+        |_, request, _| {
+            let answer = |request: &Value| match request["method"].as_str().unwrap() {
+                "getblockcount" => json!({"result": 101, "error": null, "id": request["id"]}),
+                "getblockhash" => json!({
+                    "result": format!("{:064x}", request["params"][0].as_u64().unwrap()),
+                    "error": null,
+                    "id": request["id"],
+                }),
+                _ => json!({
+                    "result": null,
+                    "error": {"code": -32601, "message": "Method not found"},
+                    "id": request["id"],
+                }),
+            };
+            let response = match request {
+                Value::Array(requests) => requests.iter().rev().map(answer).collect(),
+                request => answer(request),
+            };
+            Ok(response.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let client = Client::from_transport(
+            RecordingTransport::new(FnTransport::new(fake_bitcoind())),
+            ConstantBackoff::default(),
+        );
+        assert_eq!(client.get_block_count().await.unwrap(), 101);
+        let hashes = client
+            .call_batch::<String>(&[
+                ("getblockhash", vec![json!(1)]),
+                ("getblockhash", vec![json!(2)]),
+            ])
+            .await
+            .unwrap();
+
+        let fixture = client.transport().fixture();
+        assert_eq!(fixture.interactions.len(), 2);
+        assert_eq!(
+            fixture.interactions[0].request,
+            json!({"jsonrpc": "1.0", "method": "getblockcount", "params": []})
+        );
+        assert_eq!(
+            fixture.interactions[0].response,
+            Some(json!({"result": 101, "error": null}))
+        );
+        // Batch responses are stored in request order.
+        assert_eq!(
+            fixture.interactions[1].response.as_ref().unwrap()[0]["result"],
+            format!("{:064x}", 1)
+        );
+
+        let path = env::temp_dir().join(format!("bitcoind-fixture-{}.json", process::id()));
+        fixture.save(&path).unwrap();
+        let loaded = Fixture::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, fixture);
+
+        // A fresh client hands out different ids, and asks in a different order.
+        let replay =
+            Client::from_transport(ReplayTransport::new(loaded), ConstantBackoff::default());
+        replay.get_block_count().await.unwrap();
+        let replayed = replay
+            .call_batch::<String>(&[
+                ("getblockhash", vec![json!(1)]),
+                ("getblockhash", vec![json!(2)]),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(replayed, hashes);
+        assert_eq!(replay.get_block_count().await.unwrap(), 101);
+        assert_eq!(replay.transport().remaining(), 0);
+
+        assert!(matches!(
+            replay.get_block_hash(3).await,
+            Err(ClientError::Replay(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn strict_replay_fails_on_divergence() {
+        let client = Client::from_transport(
+            RecordingTransport::new(FnTransport::new(fake_bitcoind())),
+            ConstantBackoff::default(),
+        );
+        client.get_block_count().await.unwrap();
+        client.get_block_hash(1).await.unwrap();
+        let err = client.get_mempool_info().await.unwrap_err();
+        assert_eq!(
+            err,
+            ClientError::Server(-32601, "Method not found".to_string())
+        );
+
+        let replay = || {
+            Client::from_transport(
+                ReplayTransport::strict(client.transport().fixture()),
+                ConstantBackoff::default(),
+            )
+        };
+
+        let same = replay();
+        assert_eq!(same.get_block_count().await.unwrap(), 101);
+        same.get_block_hash(1).await.unwrap();
+        assert_eq!(same.get_mempool_info().await.unwrap_err(), err);
+        assert_eq!(same.transport().remaining(), 0);
+
+        let diverging = replay();
+        assert!(matches!(
+            diverging.get_block_hash(1).await,
+            Err(ClientError::Replay(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn record_transport_errors() {
+        let transport = FnTransport::new(|n, request, _| match n {
+            0 => Err(ClientError::Connection("refused".to_string())),
+            _ => Ok(json!({"result": 101, "error": null, "id": request["id"]}).to_string()),
+        });
+        let client = Client::from_transport(
+            RecordingTransport::new(transport),
+            ConstantBackoff::new(1, Default::default()),
+        );
+        assert_eq!(client.get_block_count().await.unwrap(), 101);
+
+        let fixture = client.transport().fixture();
+        assert_eq!(
+            fixture.interactions[0].error,
+            Some(ClientError::Connection("refused".to_string()))
+        );
+
+        // The retry is replayed too.
+        let replay = Client::from_transport(
+            ReplayTransport::strict(fixture),
+            ConstantBackoff::new(1, Default::default()),
+        );
+        assert_eq!(replay.get_block_count().await.unwrap(), 101);
+        assert_eq!(replay.transport().remaining(), 0);
+    }
+}
@@ -1,11 +1,18 @@
 use std::collections::BTreeMap;
 
 use bitcoin::{
-    absolute::Height,
+    absolute::{self, Height},
     address::{self, NetworkUnchecked},
-    block::Header,
+    bip158::{BlockFilter, FilterHeader},
+    block::{self, Header},
     consensus::{self, encode},
-    Address, Amount, Block, BlockHash, FeeRate, Psbt, SignedAmount, Transaction, Txid, Wtxid,
+    hashes::Hash,
+    hex::{DisplayHex, FromHex},
+    p2p::ServiceFlags,
+    script::{self, PushBytesBuf},
+    transaction, Address, Amount, Block, BlockHash, CompactTarget, FeeRate, MerkleBlock, OutPoint,
+    Psbt, PubkeyHash, ScriptBuf, ScriptHash, Sequence, SignedAmount, Target, Transaction, TxIn,
+    TxMerkleNode, TxOut, Txid, WScriptHash, Witness, Wtxid,
 };
 use serde::{
     de::{self, IntoDeserializer, Visitor},
@@ -13,7 +20,7 @@ use serde::{
 };
 use tracing::*;
 
-use crate::error::SignRawTransactionWithWalletError;
+use crate::error::{ClientError, SignRawTransactionWithWalletError};
 
 /// The category of a transaction.
 ///
@@ -98,6 +105,173 @@ impl GetBlockHeaderVerbosityZero {
     }
 }
 
+/// Result of JSON-RPC method `getblockheader` with verbosity set to `true`.
+///
+/// Method call: `getblockheader "blockhash" ( verbosity )`
+///
+/// # Note
+///
+/// Only needs the header of the block, so also works for headers-only and pruned blocks.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct GetBlockHeaderVerbose {
+    /// The block hash (same as provided) in RPC call.
+    pub hash: BlockHash,
+    /// The number of confirmations, or -1 if the block is not on the main chain.
+    pub confirmations: i32,
+    /// The block height or index.
+    pub height: u64,
+    /// The block version.
+    pub version: i32,
+    /// The block version formatted in hexadecimal.
+    #[serde(rename = "versionHex")]
+    pub version_hex: String,
+    /// The merkle root
+    #[serde(rename = "merkleroot")]
+    pub merkle_root: TxMerkleNode,
+    /// The block time expressed in UNIX epoch time.
+    pub time: usize,
+    /// The median block time expressed in UNIX epoch time.
+    #[serde(rename = "mediantime")]
+    pub median_time: usize,
+    /// The nonce
+    pub nonce: u32,
+    /// The bits.
+    pub bits: String,
+    /// The difficulty.
+    pub difficulty: f64,
+    /// Expected number of hashes required to produce the chain up to this block (in hex).
+    #[serde(rename = "chainwork")]
+    pub chain_work: String,
+    /// The number of transactions in the block, 0 if the node does not have the block data.
+    #[serde(rename = "nTx")]
+    pub n_tx: u32,
+    /// The hash of the previous block (if available).
+    #[serde(rename = "previousblockhash")]
+    pub previous_block_hash: Option<BlockHash>,
+    /// The hash of the next block (if available).
+    #[serde(rename = "nextblockhash")]
+    pub next_block_hash: Option<BlockHash>,
+}
+
+/// Result of JSON-RPC method `gettxoutproof`.
+///
+/// A string that is serialized, hex-encoded data for the proof.
+///
+/// Method call: `gettxoutproof ["txid",...] ( "blockhash" )`
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct GetTxOutProof(pub String);
+
+impl GetTxOutProof {
+    /// Converts json straight to a [`MerkleBlock`].
+    pub fn merkle_block(self) -> Result<MerkleBlock, encode::FromHexError> {
+        let merkle_block: MerkleBlock = encode::deserialize_hex(&self.0)?;
+        Ok(merkle_block)
+    }
+}
+
+/// The fee estimate mode of JSON-RPC method `estimatesmartfee`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum EstimateMode {
+    /// Leaves the choice to `bitcoind`, economical since Bitcoin Core v28.
+    #[default]
+    Unset,
+    /// Responds faster to short-term drops in fee rates, at the risk of underpaying.
+    Economical,
+    /// Considers a longer history of blocks, at the risk of overpaying.
+    Conservative,
+}
+
+/// Result of JSON-RPC method `estimatesmartfee`.
+///
+/// Method call: `estimatesmartfee conf_target ( "estimate_mode" )`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SmartFeeEstimate {
+    /// The estimated fee rate, if `bitcoind` has enough data for an estimate.
+    #[serde(
+        rename = "feerate",
+        default,
+        deserialize_with = "deserialize_option_btc_per_kvb"
+    )]
+    pub fee_rate: Option<FeeRate>,
+    /// Errors encountered while estimating, such as insufficient data.
+    #[serde(default)]
+    pub errors: Vec<String>,
+    /// The confirmation target in blocks the estimate is for.
+    ///
+    /// May differ from the requested target, when `bitcoind` has no data for it.
+    pub blocks: u16,
+}
+
+/// Result of JSON-RPC method `estimaterawfee`.
+///
+/// Method call: `estimaterawfee conf_target ( threshold )`
+///
+/// # Note
+///
+/// A horizon is missing when the confirmation target is beyond what it tracks: 12 blocks for
+/// the short horizon, 48 for the medium one and 1008 for the long one.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct EstimateRawFee {
+    /// Estimate for the short time horizon.
+    pub short: Option<RawFeeEstimate>,
+    /// Estimate for the medium time horizon.
+    pub medium: Option<RawFeeEstimate>,
+    /// Estimate for the long time horizon.
+    pub long: Option<RawFeeEstimate>,
+}
+
+/// The estimate of a time horizon in [`EstimateRawFee`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RawFeeEstimate {
+    /// The estimated fee rate, if any bucket passed the threshold.
+    #[serde(
+        rename = "feerate",
+        default,
+        deserialize_with = "deserialize_option_btc_per_kvb"
+    )]
+    pub fee_rate: Option<FeeRate>,
+    /// Exponential decay per block of the historical moving average of confirmation data.
+    pub decay: f64,
+    /// The resolution of the confirmation targets of the horizon, in blocks.
+    pub scale: u32,
+    /// The lowest range of buckets passing the threshold.
+    pub pass: Option<FeeRateBucket>,
+    /// The highest range of buckets failing the threshold.
+    pub fail: Option<FeeRateBucket>,
+    /// Errors encountered while estimating, such as insufficient data.
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+/// A range of fee rate buckets in [`RawFeeEstimate`].
+///
+/// # Note
+///
+/// The counts are decayed moving averages, hence fractional.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FeeRateBucket {
+    /// Start of the fee rate range, in sat/kvB.
+    #[serde(rename = "startrange")]
+    pub start_range: f64,
+    /// End of the fee rate range, in sat/kvB.
+    #[serde(rename = "endrange")]
+    pub end_range: f64,
+    /// Number of transactions in the range confirmed within the target.
+    #[serde(rename = "withintarget")]
+    pub within_target: f64,
+    /// Number of transactions in the range confirmed at any point.
+    #[serde(rename = "totalconfirmed")]
+    pub total_confirmed: f64,
+    /// Number of transactions in the range in the mempool for longer than the target.
+    #[serde(rename = "inmempool")]
+    pub in_mempool: f64,
+    /// Number of transactions in the range that left the mempool unconfirmed after the
+    /// target.
+    #[serde(rename = "leftmempool")]
+    pub left_mempool: f64,
+}
+
 /// Result of JSON-RPC method `getblock` with verbosity set to 0.
 ///
 /// A string that is serialized, hex-encoded data for block 'hash'.
@@ -165,6 +339,412 @@ pub struct GetBlockVerbosityOne {
     pub next_block_hash: Option<String>,
 }
 
+/// Result of JSON-RPC method `getblock` with verbosity set to 2 or 3.
+///
+/// Method call: `getblock "blockhash" ( verbosity )`
+///
+/// # Note
+///
+/// The transactions carry their fees, and with verbosity 3 also the outputs spent by their
+/// inputs, see [`BlockTransaction::prevouts`].
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct GetBlockVerbose {
+    /// The block hash (same as provided) in RPC call.
+    pub hash: BlockHash,
+    /// The number of confirmations, or -1 if the block is not on the main chain.
+    pub confirmations: i32,
+    /// The block size.
+    pub size: usize,
+    /// The block size excluding witness data.
+    #[serde(rename = "strippedsize")]
+    pub stripped_size: Option<usize>,
+    /// The block weight as defined in BIP-141.
+    pub weight: u64,
+    /// The block height or index.
+    pub height: u64,
+    /// The block version.
+    pub version: i32,
+    /// The block version formatted in hexadecimal.
+    #[serde(rename = "versionHex")]
+    pub version_hex: String,
+    /// The merkle root
+    #[serde(rename = "merkleroot")]
+    pub merkle_root: TxMerkleNode,
+    /// The decoded transactions.
+    pub tx: Vec<BlockTransaction>,
+    /// The block time expressed in UNIX epoch time.
+    pub time: usize,
+    /// The median block time expressed in UNIX epoch time.
+    #[serde(rename = "mediantime")]
+    pub median_time: Option<usize>,
+    /// The nonce
+    pub nonce: u32,
+    /// The bits.
+    pub bits: String,
+    /// The difficulty.
+    pub difficulty: f64,
+    /// Expected number of hashes required to produce the chain up to this block (in hex).
+    #[serde(rename = "chainwork")]
+    pub chain_work: String,
+    /// The number of transactions in the block.
+    #[serde(rename = "nTx")]
+    pub n_tx: u32,
+    /// The hash of the previous block (if available).
+    #[serde(rename = "previousblockhash")]
+    pub previous_block_hash: Option<BlockHash>,
+    /// The hash of the next block (if available).
+    #[serde(rename = "nextblockhash")]
+    pub next_block_hash: Option<BlockHash>,
+}
+
+/// A transaction in the result of JSON-RPC method `getblock` with verbosity set to 2 or 3.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct BlockTransaction {
+    /// The transaction id.
+    pub txid: Txid,
+    /// The witness transaction id.
+    pub hash: Wtxid,
+    /// The serialized transaction size.
+    pub size: usize,
+    /// The virtual transaction size.
+    pub vsize: usize,
+    /// The transaction weight as defined in BIP-141.
+    pub weight: u64,
+    /// The transaction.
+    #[serde(deserialize_with = "deserialize_tx")]
+    #[serde(rename = "hex")]
+    pub transaction: Transaction,
+    /// The fee paid by the transaction.
+    ///
+    /// Missing for coinbase transactions and when the undo data of the block is not available,
+    /// such as for pruned blocks.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_bitcoin",
+        serialize_with = "serialize_option_bitcoin"
+    )]
+    pub fee: Option<Amount>,
+    /// The inputs, in the same order as in the [`transaction`](Self::transaction).
+    pub vin: Vec<BlockTransactionInput>,
+}
+
+impl BlockTransaction {
+    /// Returns the outputs spent by the inputs of the transaction, in input order.
+    ///
+    /// Returns `None` for coinbase transactions and unless the block was fetched with verbosity
+    /// 3, which is what [`Reader::get_block_verbose`](crate::traits::Reader::get_block_verbose)
+    /// does with `include_prevouts` set.
+    pub fn prevouts(&self) -> Option<Vec<TxOut>> {
+        if self.transaction.is_coinbase() {
+            return None;
+        }
+        self.vin
+            .iter()
+            .map(|input| input.prevout.as_ref().map(Prevout::tx_out))
+            .collect()
+    }
+
+    /// Returns the total value of the outputs spent by the transaction.
+    ///
+    /// Returns `None` whenever [`prevouts`](Self::prevouts) does.
+    pub fn input_value(&self) -> Option<Amount> {
+        self.prevouts()?
+            .iter()
+            .map(|prevout| prevout.value)
+            .try_fold(Amount::ZERO, Amount::checked_add)
+    }
+}
+
+/// An input of a [`BlockTransaction`].
+///
+/// # Note
+///
+/// Only the spent output is kept, the input itself is part of the
+/// [`BlockTransaction::transaction`].
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct BlockTransactionInput {
+    /// The output spent by the input, only returned with verbosity 3 and not for coinbase
+    /// inputs.
+    #[serde(default)]
+    pub prevout: Option<Prevout>,
+}
+
+/// An output spent by a transaction input.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct Prevout {
+    /// Whether the output was created by a coinbase transaction.
+    pub generated: bool,
+    /// Height of the block that created the output.
+    pub height: u64,
+    /// The value of the output.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub value: Amount,
+    /// The script pubkey of the output.
+    #[serde(
+        rename = "scriptPubKey",
+        deserialize_with = "deserialize_script_pubkey"
+    )]
+    pub script_pubkey: ScriptBuf,
+}
+
+impl Prevout {
+    /// Converts to the spent [`TxOut`].
+    pub fn tx_out(&self) -> TxOut {
+        TxOut {
+            value: self.value,
+            script_pubkey: self.script_pubkey.clone(),
+        }
+    }
+}
+
+/// Result of JSON-RPC method `getblockfilter`.
+///
+/// Method call: `getblockfilter "blockhash" ( "filtertype" )`
+///
+/// # Note
+///
+/// Needs `bitcoind` to run with `-blockfilterindex=1`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GetBlockFilter {
+    /// The BIP 158 basic filter of the block.
+    #[serde(
+        deserialize_with = "deserialize_block_filter",
+        serialize_with = "serialize_block_filter"
+    )]
+    pub filter: BlockFilter,
+    /// The BIP 157 header of the filter.
+    pub header: FilterHeader,
+}
+
+/// The status of a chain tip.
+///
+/// This is one of the results of `getchaintips` RPC method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChainTipStatus {
+    /// The tip of the active chain.
+    Active,
+    /// A fully validated branch that is not part of the active chain.
+    ValidFork,
+    /// All blocks of the branch are available but were never fully validated.
+    ValidHeaders,
+    /// Not all blocks of the branch are available, but the headers are valid.
+    HeadersOnly,
+    /// The branch contains at least one invalid block.
+    Invalid,
+}
+
+/// Result of JSON-RPC method `getchaintips`.
+///
+/// Method call: `getchaintips`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ChainTip {
+    /// The height of the tip.
+    pub height: u64,
+    /// The block hash of the tip.
+    pub hash: BlockHash,
+    /// The number of blocks between the tip and the active chain, 0 for the active tip.
+    #[serde(rename = "branchlen")]
+    pub branch_len: u64,
+    /// The status of the tip.
+    pub status: ChainTipStatus,
+}
+
+/// How to get from one block to another, as found by
+/// [`Reader::find_fork`](crate::traits::Reader::find_fork).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainFork {
+    /// The last block both blocks descend from.
+    pub fork_point: BlockHash,
+    /// The height of the fork point.
+    pub fork_height: u64,
+    /// The blocks to disconnect, from the first block down to the fork point, excluded.
+    pub disconnected: Vec<BlockHash>,
+    /// The blocks to connect, from the fork point, excluded, up to the second block.
+    pub connected: Vec<BlockHash>,
+}
+
+/// The block to compute statistics for with JSON-RPC method `getblockstats`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum BlockStatsTarget {
+    /// The block with the given hash.
+    Hash(BlockHash),
+    /// The block at the given height of the active chain.
+    Height(u64),
+}
+
+impl From<BlockHash> for BlockStatsTarget {
+    fn from(hash: BlockHash) -> Self {
+        Self::Hash(hash)
+    }
+}
+
+impl From<u64> for BlockStatsTarget {
+    fn from(height: u64) -> Self {
+        Self::Height(height)
+    }
+}
+
+/// A statistic that JSON-RPC method `getblockstats` can compute.
+///
+/// Each variant selects the [`GetBlockStats`] field of the same name. Selecting only the
+/// fields needed saves `bitcoind` work, the fee and size statistics for instance need the undo
+/// data of the block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockStatsField {
+    AvgFee,
+    AvgFeeRate,
+    AvgTxSize,
+    BlockHash,
+    #[serde(rename = "feerate_percentiles")]
+    FeeRatePercentiles,
+    Height,
+    Ins,
+    MaxFee,
+    MaxFeeRate,
+    MaxTxSize,
+    MedianFee,
+    MedianTime,
+    MedianTxSize,
+    MinFee,
+    MinFeeRate,
+    MinTxSize,
+    Outs,
+    Subsidy,
+    #[serde(rename = "swtotal_size")]
+    SwTotalSize,
+    #[serde(rename = "swtotal_weight")]
+    SwTotalWeight,
+    SwTxs,
+    Time,
+    #[serde(rename = "total_out")]
+    TotalOut,
+    #[serde(rename = "total_size")]
+    TotalSize,
+    #[serde(rename = "total_weight")]
+    TotalWeight,
+    TotalFee,
+    Txs,
+    #[serde(rename = "utxo_increase")]
+    UtxoIncrease,
+    #[serde(rename = "utxo_size_inc")]
+    UtxoSizeInc,
+    #[serde(rename = "utxo_increase_actual")]
+    UtxoIncreaseActual,
+    #[serde(rename = "utxo_size_inc_actual")]
+    UtxoSizeIncActual,
+}
+
+/// Result of JSON-RPC method `getblockstats`.
+///
+/// Method call: `getblockstats hash_or_height ( stats )`
+///
+/// # Note
+///
+/// Every field is `None` unless it was selected, see [`BlockStatsField`]. Statistics on fees,
+/// sizes and inputs exclude the coinbase transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GetBlockStats {
+    /// Average fee in the block.
+    #[serde(rename = "avgfee")]
+    pub avg_fee: Option<Amount>,
+    /// Average fee rate.
+    #[serde(
+        rename = "avgfeerate",
+        default,
+        deserialize_with = "deserialize_option_sat_per_vb"
+    )]
+    pub avg_fee_rate: Option<FeeRate>,
+    /// Average transaction size.
+    #[serde(rename = "avgtxsize")]
+    pub avg_tx_size: Option<u64>,
+    /// The block hash.
+    #[serde(rename = "blockhash")]
+    pub block_hash: Option<BlockHash>,
+    /// Fee rates at the 10th, 25th, 50th, 75th and 90th percentile weight unit.
+    #[serde(
+        rename = "feerate_percentiles",
+        default,
+        deserialize_with = "deserialize_option_sat_per_vb_percentiles"
+    )]
+    pub fee_rate_percentiles: Option<[FeeRate; 5]>,
+    /// The height of the block.
+    pub height: Option<u64>,
+    /// The number of inputs.
+    pub ins: Option<u64>,
+    /// Maximum fee in the block.
+    #[serde(rename = "maxfee")]
+    pub max_fee: Option<Amount>,
+    /// Maximum fee rate.
+    #[serde(
+        rename = "maxfeerate",
+        default,
+        deserialize_with = "deserialize_option_sat_per_vb"
+    )]
+    pub max_fee_rate: Option<FeeRate>,
+    /// Maximum transaction size.
+    #[serde(rename = "maxtxsize")]
+    pub max_tx_size: Option<u64>,
+    /// Truncated median fee in the block.
+    #[serde(rename = "medianfee")]
+    pub median_fee: Option<Amount>,
+    /// The median block time expressed in UNIX epoch time.
+    #[serde(rename = "mediantime")]
+    pub median_time: Option<u64>,
+    /// Truncated median transaction size.
+    #[serde(rename = "mediantxsize")]
+    pub median_tx_size: Option<u64>,
+    /// Minimum fee in the block.
+    #[serde(rename = "minfee")]
+    pub min_fee: Option<Amount>,
+    /// Minimum fee rate.
+    #[serde(
+        rename = "minfeerate",
+        default,
+        deserialize_with = "deserialize_option_sat_per_vb"
+    )]
+    pub min_fee_rate: Option<FeeRate>,
+    /// Minimum transaction size.
+    #[serde(rename = "mintxsize")]
+    pub min_tx_size: Option<u64>,
+    /// The number of outputs, including the coinbase outputs.
+    pub outs: Option<u64>,
+    /// The block subsidy.
+    pub subsidy: Option<Amount>,
+    /// Total size of all segwit transactions.
+    #[serde(rename = "swtotal_size")]
+    pub sw_total_size: Option<u64>,
+    /// Total weight of all segwit transactions.
+    #[serde(rename = "swtotal_weight")]
+    pub sw_total_weight: Option<u64>,
+    /// The number of segwit transactions.
+    #[serde(rename = "swtxs")]
+    pub sw_txs: Option<u64>,
+    /// The block time expressed in UNIX epoch time.
+    pub time: Option<u64>,
+    /// Total amount in all outputs.
+    pub total_out: Option<Amount>,
+    /// Total size of all transactions.
+    pub total_size: Option<u64>,
+    /// Total weight of all transactions.
+    pub total_weight: Option<u64>,
+    /// The fees collected by the block.
+    #[serde(rename = "totalfee")]
+    pub total_fee: Option<Amount>,
+    /// The number of transactions, including the coinbase transaction.
+    pub txs: Option<u64>,
+    /// The increase or decrease in the number of unspent outputs.
+    pub utxo_increase: Option<i64>,
+    /// The increase or decrease in size of the UTXO set.
+    pub utxo_size_inc: Option<i64>,
+    /// Like [`utxo_increase`](Self::utxo_increase), ignoring unspendable outputs.
+    pub utxo_increase_actual: Option<i64>,
+    /// Like [`utxo_size_inc`](Self::utxo_size_inc), ignoring unspendable outputs.
+    pub utxo_size_inc_actual: Option<i64>,
+}
+
 /// Result of JSON-RPC method `getrawtransaction` with verbosity set to 0.
 ///
 /// A string that is serialized, hex-encoded data for transaction.
@@ -191,11 +771,108 @@ pub struct GetMempoolInfo {
     pub bytes: usize,
     pub usage: usize,
     pub maxmempool: usize,
-    pub mempoolminfee: f64,
-    pub minrelaytxfee: f64,
+    /// Minimum fee rate for a transaction to be accepted, the higher of
+    /// [`minrelaytxfee`](Self::minrelaytxfee) and the dynamic minimum of a full mempool.
+    #[serde(deserialize_with = "deserialize_btc_per_kvb")]
+    pub mempoolminfee: FeeRate,
+    /// Minimum fee rate for a transaction to be relayed.
+    #[serde(deserialize_with = "deserialize_btc_per_kvb")]
+    pub minrelaytxfee: FeeRate,
     pub unbroadcastcount: usize,
 }
 
+/// Result of JSON-RPC method `getmempoolentry`.
+///
+/// Method call: `getmempoolentry "txid"`
+///
+/// Also the values of the maps returned by `getmempoolancestors`, `getmempooldescendants` and
+/// `getrawmempool` when called with `verbose` set.
+///
+/// # Note
+///
+/// Ancestor and descendant counts, sizes and fees include the transaction itself.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GetMempoolEntry {
+    /// The virtual transaction size as defined in BIP-141.
+    pub vsize: u64,
+    /// The transaction weight as defined in BIP-141.
+    pub weight: u64,
+    /// Time the transaction entered the mempool, in UNIX epoch time.
+    pub time: u64,
+    /// Height of the chain tip when the transaction entered the mempool.
+    pub height: u64,
+    /// Number of in-mempool descendant transactions.
+    #[serde(rename = "descendantcount")]
+    pub descendant_count: u64,
+    /// Virtual size of in-mempool descendants.
+    #[serde(rename = "descendantsize")]
+    pub descendant_size: u64,
+    /// Number of in-mempool ancestor transactions.
+    #[serde(rename = "ancestorcount")]
+    pub ancestor_count: u64,
+    /// Virtual size of in-mempool ancestors.
+    #[serde(rename = "ancestorsize")]
+    pub ancestor_size: u64,
+    /// The witness transaction id.
+    pub wtxid: Wtxid,
+    /// The fees of the transaction and its in-mempool ancestors and descendants.
+    pub fees: MempoolEntryFees,
+    /// Unconfirmed transactions spent by this transaction.
+    pub depends: Vec<Txid>,
+    /// Unconfirmed transactions spending outputs of this transaction.
+    #[serde(rename = "spentby")]
+    pub spent_by: Vec<Txid>,
+    /// Whether this transaction or one of its unconfirmed ancestors signals BIP125
+    /// replaceability.
+    #[serde(rename = "bip125-replaceable")]
+    pub bip125_replaceable: bool,
+    /// Whether this transaction was submitted locally and not yet seen by peers.
+    pub unbroadcast: bool,
+}
+
+impl GetMempoolEntry {
+    /// The fee rate of the transaction alone, using the [`modified`](MempoolEntryFees::modified)
+    /// fee.
+    pub fn fee_rate(&self) -> Option<FeeRate> {
+        fee_rate_by_vsize(self.fees.modified, self.vsize)
+    }
+
+    /// The fee rate of the transaction with all its in-mempool ancestors, the fee rate a miner
+    /// sees when selecting it for a block.
+    pub fn ancestor_fee_rate(&self) -> Option<FeeRate> {
+        fee_rate_by_vsize(self.fees.ancestor, self.ancestor_size)
+    }
+
+    /// The fee rate of the transaction with all its in-mempool descendants.
+    pub fn descendant_fee_rate(&self) -> Option<FeeRate> {
+        fee_rate_by_vsize(self.fees.descendant, self.descendant_size)
+    }
+}
+
+/// Divides a fee by a virtual size, rounding up.
+fn fee_rate_by_vsize(fee: Amount, vsize: u64) -> Option<FeeRate> {
+    let weight = vsize.checked_mul(4).filter(|weight| *weight > 0)?;
+    let sat_per_kwu = fee.to_sat().checked_mul(1000)?.div_ceil(weight);
+    Some(FeeRate::from_sat_per_kwu(sat_per_kwu))
+}
+
+/// The fees of a [`GetMempoolEntry`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MempoolEntryFees {
+    /// The fee paid by the transaction.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub base: Amount,
+    /// The fee with fee deltas used for mining priority, see `prioritisetransaction`.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub modified: Amount,
+    /// The modified fees of the transaction and its in-mempool ancestors.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub ancestor: Amount,
+    /// The modified fees of the transaction and its in-mempool descendants.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub descendant: Amount,
+}
+
 /// Result of JSON-RPC method `getrawtransaction` with verbosity set to 1.
 ///
 /// Method call: `getrawtransaction "txid" ( verbosity )`
@@ -242,13 +919,168 @@ pub struct GetTxOut {
     pub coinbase: bool,
 }
 
-/// A script pubkey.
+/// The hash of the UTXO set computed by JSON-RPC method `gettxoutsetinfo`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum TxOutSetHashType {
+    /// The legacy hash of the serialized UTXO set, as used by `assumeutxo` snapshots.
+    #[default]
+    #[serde(rename = "hash_serialized_3")]
+    HashSerialized3,
+    /// The MuHash of the UTXO set, which can be maintained incrementally by the
+    /// `-coinstatsindex`.
+    #[serde(rename = "muhash")]
+    MuHash,
+    /// No hash, the fastest option.
+    #[serde(rename = "none")]
+    None,
+}
+
+/// Result of JSON-RPC method `gettxoutsetinfo`.
+///
+/// Method call: `gettxoutsetinfo ( "hash_type" hash_or_height use_index )`
+///
+/// # Note
+///
+/// The statistics are of the UTXO set at the chain tip. Computing them without the
+/// `-coinstatsindex` goes through the whole UTXO set and can take minutes on mainnet.
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
-pub struct ScriptPubkey {
-    /// Script assembly.
-    pub asm: String,
-    /// Script hex.
-    pub hex: String,
+pub struct GetTxOutSetInfo {
+    /// The height of the block the statistics are for.
+    pub height: u64,
+    /// The hash of the block the statistics are for.
+    #[serde(rename = "bestblock")]
+    pub best_block: BlockHash,
+    /// The number of unspent transaction outputs.
+    #[serde(rename = "txouts")]
+    pub tx_outs: u64,
+    /// A database-independent metric for the size of the UTXO set.
+    #[serde(rename = "bogosize")]
+    pub bogo_size: u64,
+    /// The serialized hash, if requested with [`TxOutSetHashType::HashSerialized3`].
+    pub hash_serialized_3: Option<String>,
+    /// The MuHash, if requested with [`TxOutSetHashType::MuHash`].
+    pub muhash: Option<String>,
+    /// The number of transactions with unspent outputs, if not computed with the
+    /// `-coinstatsindex`.
+    pub transactions: Option<u64>,
+    /// The estimated size of the chainstate on disk, if not computed with the
+    /// `-coinstatsindex`.
+    pub disk_size: Option<u64>,
+    /// The total amount of all unspent outputs.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub total_amount: Amount,
+    /// The total amount of coins permanently excluded from the UTXO set, if computed with the
+    /// `-coinstatsindex`.
+    #[serde(default, deserialize_with = "deserialize_option_bitcoin")]
+    pub total_unspendable_amount: Option<Amount>,
+}
+
+/// An object to scan the UTXO set for with JSON-RPC method `scantxoutset`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ScanObject {
+    /// An output descriptor, such as `addr(bcrt1q...)` or `wpkh(xpub.../0/*)`.
+    ///
+    /// Ranged descriptors are scanned for the first 1000 indices.
+    Descriptor(String),
+    /// A ranged output descriptor and the range of indices to scan, both included.
+    Ranged {
+        /// The output descriptor.
+        desc: String,
+        /// The first and last index to scan.
+        range: (u32, u32),
+    },
+}
+
+impl From<&str> for ScanObject {
+    fn from(descriptor: &str) -> Self {
+        Self::Descriptor(descriptor.to_string())
+    }
+}
+
+impl From<String> for ScanObject {
+    fn from(descriptor: String) -> Self {
+        Self::Descriptor(descriptor)
+    }
+}
+
+/// Result of JSON-RPC method `scantxoutset` with action `start`.
+///
+/// Method call: `scantxoutset "start" [scanobjects,...]`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ScanTxOutSet {
+    /// Whether the scan completed. False if it was aborted.
+    pub success: bool,
+    /// The number of unspent transaction outputs scanned.
+    #[serde(rename = "txouts")]
+    pub tx_outs: u64,
+    /// The height of the block the UTXO set was scanned at.
+    pub height: u64,
+    /// The hash of the block the UTXO set was scanned at.
+    #[serde(rename = "bestblock")]
+    pub best_block: BlockHash,
+    /// The unspent outputs matching the scan objects.
+    pub unspents: Vec<ScanTxOutSetUnspent>,
+    /// The total amount of the unspent outputs found.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub total_amount: Amount,
+}
+
+/// An unspent output found by JSON-RPC method `scantxoutset`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ScanTxOutSetUnspent {
+    /// The transaction id of the output.
+    pub txid: Txid,
+    /// The index of the output.
+    pub vout: u32,
+    /// The script of the output.
+    #[serde(rename = "scriptPubKey")]
+    pub script_pubkey: ScriptBuf,
+    /// The descriptor of the script, with key origin information.
+    pub desc: String,
+    /// The value of the output.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub amount: Amount,
+    /// Whether the output is from a coinbase transaction.
+    ///
+    /// Reported since Bitcoin Core v25.
+    #[serde(default)]
+    pub coinbase: bool,
+    /// The height of the block the output was created in.
+    pub height: u64,
+}
+
+impl ScanTxOutSetUnspent {
+    /// Returns the [`OutPoint`] of the unspent output.
+    pub fn outpoint(&self) -> OutPoint {
+        OutPoint::new(self.txid, self.vout)
+    }
+
+    /// Returns the unspent output as a [`TxOut`].
+    pub fn tx_out(&self) -> TxOut {
+        TxOut {
+            value: self.amount,
+            script_pubkey: self.script_pubkey.clone(),
+        }
+    }
+}
+
+/// Result of JSON-RPC method `scantxoutset` with action `status`.
+///
+/// Method call: `scantxoutset "status"`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ScanTxOutSetStatus {
+    /// The progress of the scan in percent.
+    pub progress: f64,
+}
+
+/// A script pubkey.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ScriptPubkey {
+    /// Script assembly.
+    pub asm: String,
+    /// Script hex.
+    pub hex: String,
     #[serde(rename = "reqSigs")]
     pub req_sigs: i64,
     /// The type, eg pubkeyhash.
@@ -376,6 +1208,13 @@ pub struct SubmitPackageTxResult {
     pub error: Option<String>,
 }
 
+impl SubmitPackageTxResult {
+    /// Parses the [`error`](Self::error) into a [`RejectReason`].
+    pub fn rejection(&self) -> Option<RejectReason> {
+        self.error.as_deref().map(RejectReason::from)
+    }
+}
+
 /// Models the fees included in the per-transaction result of the JSON-RPC method `submitpackage`.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct SubmitPackageTxResultFees {
@@ -546,7 +1385,7 @@ pub struct ListTransactions {
     #[serde(deserialize_with = "deserialize_address")]
     pub address: Address<NetworkUnchecked>,
     /// Category of the transaction.
-    category: TransactionCategory,
+    pub(crate) category: TransactionCategory,
     /// The signed amount in BTC.
     #[serde(deserialize_with = "deserialize_signed_bitcoin")]
     pub amount: SignedAmount,
@@ -572,9 +1411,126 @@ pub struct TestMempoolAccept {
     #[serde(deserialize_with = "deserialize_txid")]
     pub txid: Txid,
     /// Rejection reason, if any.
+    #[serde(alias = "reject-reason")]
     pub reject_reason: Option<String>,
 }
 
+impl TestMempoolAccept {
+    /// Parses the [`reject_reason`](Self::reject_reason) into a [`RejectReason`].
+    pub fn rejection(&self) -> Option<RejectReason> {
+        self.reject_reason.as_deref().map(RejectReason::from)
+    }
+}
+
+/// Why the mempool rejected a transaction.
+///
+/// Parsed from the reject strings of Bitcoin Core, as found in
+/// [`TestMempoolAccept::reject_reason`], [`SubmitPackageTxResult::error`] and the messages of
+/// [`ClientError::Server`](crate::error::ClientError::Server) errors returned by
+/// `sendrawtransaction`. Details following the reject code, such as the fee rates in
+/// `"min relay fee not met, 0 < 110"`, are dropped.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RejectReason {
+    /// The fee is below the minimum relay fee (`min relay fee not met`).
+    MinRelayFeeNotMet,
+    /// The fee rate is below the dynamic mempool minimum (`mempool min fee not met`).
+    MempoolMinFeeNotMet,
+    /// A replacement does not pay enough fees over the transactions it replaces
+    /// (`insufficient fee`).
+    InsufficientFee,
+    /// The fee exceeds the maximum fee rate of `sendrawtransaction` or `testmempoolaccept`
+    /// (`max-fee-exceeded`).
+    MaxFeeExceeded,
+    /// The mempool is full and the fee is too low to evict other transactions (`mempool full`).
+    MempoolFull,
+    /// Some inputs are missing or already spent (`bad-txns-inputs-missingorspent`,
+    /// `missing-inputs`).
+    MissingInputs,
+    /// The outputs are worth more than the inputs (`bad-txns-in-belowout`).
+    InputsBelowOutputs,
+    /// A coinbase output is spent before maturity (`bad-txns-premature-spend-of-coinbase`).
+    PrematureCoinbaseSpend,
+    /// The transaction is in the mempool already (`txn-already-in-mempool`).
+    AlreadyInMempool,
+    /// The transaction is known already, usually because it is confirmed
+    /// (`txn-already-known`).
+    AlreadyKnown,
+    /// The transaction conflicts with a mempool transaction that cannot be replaced
+    /// (`txn-mempool-conflict`).
+    MempoolConflict,
+    /// A replacement would evict too many transactions (`too many potential replacements`).
+    TooManyReplacements,
+    /// A replacement spends new unconfirmed inputs (`replacement-adds-unconfirmed`).
+    ReplacementAddsUnconfirmed,
+    /// The transaction exceeds the ancestor or descendant limits (`too-long-mempool-chain`).
+    TooLongMempoolChain,
+    /// The lock time of the transaction is not final (`non-final`).
+    NonFinal,
+    /// A relative lock time of the transaction is not final (`non-BIP68-final`).
+    NonBip68Final,
+    /// An output is below the dust threshold (`dust`).
+    Dust,
+    /// The transaction breaks a standardness rule, holds the reject code, such as
+    /// `scriptpubkey`, `tx-size` or `bad-txns-nonstandard-inputs`.
+    NonStandard(String),
+    /// A script fails consensus validation (`mandatory-script-verify-flag-failed`).
+    ScriptVerifyFailed,
+    /// Any other reason, holds the whole reject string.
+    Other(String),
+}
+
+impl From<&str> for RejectReason {
+    fn from(reason: &str) -> Self {
+        // Details follow the reject code after a comma or in parentheses.
+        let code = reason
+            .split([',', '(', ';'])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        match code {
+            "min relay fee not met" => Self::MinRelayFeeNotMet,
+            "mempool min fee not met" => Self::MempoolMinFeeNotMet,
+            "insufficient fee" => Self::InsufficientFee,
+            "max-fee-exceeded" | "Fee exceeds maximum configured by user" => Self::MaxFeeExceeded,
+            "mempool full" => Self::MempoolFull,
+            "bad-txns-inputs-missingorspent"
+            | "missing-inputs"
+            | "Missing inputs"
+            | "Inputs missing or spent" => Self::MissingInputs,
+            "bad-txns-in-belowout" => Self::InputsBelowOutputs,
+            "bad-txns-premature-spend-of-coinbase" => Self::PrematureCoinbaseSpend,
+            "txn-already-in-mempool" => Self::AlreadyInMempool,
+            "txn-already-known" => Self::AlreadyKnown,
+            "txn-mempool-conflict" => Self::MempoolConflict,
+            "too many potential replacements" => Self::TooManyReplacements,
+            "replacement-adds-unconfirmed" => Self::ReplacementAddsUnconfirmed,
+            "too-long-mempool-chain" => Self::TooLongMempoolChain,
+            "non-final" => Self::NonFinal,
+            "non-BIP68-final" => Self::NonBip68Final,
+            "dust" => Self::Dust,
+            "version"
+            | "tx-size"
+            | "tx-size-small"
+            | "scriptsig-size"
+            | "scriptsig-not-pushonly"
+            | "scriptpubkey"
+            | "bare-multisig"
+            | "multi-op-return"
+            | "bad-txns-nonstandard-inputs"
+            | "bad-witness-nonstandard"
+            | "non-mandatory-script-verify-flag" => Self::NonStandard(code.to_string()),
+            "mandatory-script-verify-flag-failed" => Self::ScriptVerifyFailed,
+            _ => Self::Other(reason.to_string()),
+        }
+    }
+}
+
+impl From<String> for RejectReason {
+    fn from(reason: String) -> Self {
+        Self::from(reason.as_str())
+    }
+}
+
 /// Models the result of JSON-RPC method `signrawtransactionwithwallet`.
 ///
 /// # Note
@@ -720,6 +1676,66 @@ where
     }
 }
 
+/// Deserializes the optional amount in BTC into proper [`Amount`]s.
+fn deserialize_option_bitcoin<'d, D>(deserializer: D) -> Result<Option<Amount>, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let f: Option<f64> = Option::deserialize(deserializer)?;
+    match f {
+        Some(v) => deserialize_bitcoin(v.into_deserializer()).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Deserializes the hex of a `scriptPubKey` object into a proper [`ScriptBuf`].
+fn deserialize_script_pubkey<'d, D>(deserializer: D) -> Result<ScriptBuf, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    #[derive(Deserialize)]
+    struct ScriptPubKeyHex {
+        hex: String,
+    }
+
+    let script_pubkey = ScriptPubKeyHex::deserialize(deserializer)?;
+    ScriptBuf::from_hex(&script_pubkey.hex)
+        .map_err(|e| de::Error::custom(format!("failed to deserialize script pubkey: {e}")))
+}
+
+/// Deserializes the hex of an optional `scriptSig` object into a proper [`ScriptBuf`].
+fn deserialize_option_script_sig<'d, D>(deserializer: D) -> Result<Option<ScriptBuf>, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    #[derive(Deserialize)]
+    struct ScriptSigHex {
+        hex: String,
+    }
+
+    Option::<ScriptSigHex>::deserialize(deserializer)?
+        .map(|script_sig| {
+            ScriptBuf::from_hex(&script_sig.hex)
+                .map_err(|e| de::Error::custom(format!("failed to deserialize script sig: {e}")))
+        })
+        .transpose()
+}
+
+/// Deserializes a list of hex witness elements into a proper [`Witness`].
+fn deserialize_witness<'d, D>(deserializer: D) -> Result<Witness, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|element| {
+            Vec::<u8>::from_hex(element)
+                .map_err(|e| de::Error::custom(format!("failed to deserialize witness: {e}")))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|elements| Witness::from_slice(&elements))
+}
+
 /// Deserializes the fee rate from sat/vB into proper [`FeeRate`].
 ///
 /// Note: Bitcoin Core 0.21+ uses sat/vB for fee rates for most RPC methods/results.
@@ -753,6 +1769,140 @@ where
     deserializer.deserialize_any(FeeRateVisitor)
 }
 
+/// Deserializes an optional integer fee rate in sat/vB into proper [`FeeRate`]s.
+///
+/// Note: `getblockstats` reports fee rates as whole sat/vB.
+fn deserialize_option_sat_per_vb<'d, D>(deserializer: D) -> Result<Option<FeeRate>, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let sat_per_vb: Option<u64> = Option::deserialize(deserializer)?;
+    sat_per_vb
+        .map(|sat_per_vb| {
+            FeeRate::from_sat_per_vb(sat_per_vb)
+                .ok_or_else(|| de::Error::custom("Invalid fee rate"))
+        })
+        .transpose()
+}
+
+/// Deserializes the optional fee rate percentiles in sat/vB of `getblockstats`.
+fn deserialize_option_sat_per_vb_percentiles<'d, D>(
+    deserializer: D,
+) -> Result<Option<[FeeRate; 5]>, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let percentiles: Option<[u64; 5]> = Option::deserialize(deserializer)?;
+    let Some(percentiles) = percentiles else {
+        return Ok(None);
+    };
+    let mut fee_rates = [FeeRate::ZERO; 5];
+    for (fee_rate, sat_per_vb) in fee_rates.iter_mut().zip(percentiles) {
+        *fee_rate = FeeRate::from_sat_per_vb(sat_per_vb)
+            .ok_or_else(|| de::Error::custom("Invalid fee rate"))?;
+    }
+    Ok(Some(fee_rates))
+}
+
+/// Deserializes the filter hex string into a proper [`BlockFilter`].
+fn deserialize_block_filter<'d, D>(deserializer: D) -> Result<BlockFilter, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let filter = String::deserialize(deserializer)?;
+    Vec::<u8>::from_hex(&filter)
+        .map(|content| BlockFilter::new(&content))
+        .map_err(|e| de::Error::custom(format!("failed to deserialize block filter: {e}")))
+}
+
+/// Serializes the [`BlockFilter`] into a hex string.
+fn serialize_block_filter<S>(filter: &BlockFilter, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&filter.content.to_lower_hex_string())
+}
+
+/// Deserializes the fee rate from BTC/kvB into proper [`FeeRate`].
+fn deserialize_btc_per_kvb<'d, D>(deserializer: D) -> Result<FeeRate, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let sat_per_kvb = deserialize_bitcoin(deserializer)?.to_sat();
+    Ok(FeeRate::from_sat_per_kwu(sat_per_kvb / 4))
+}
+
+/// Deserializes the optional fee rate from BTC/kvB into proper [`FeeRate`].
+fn deserialize_option_btc_per_kvb<'d, D>(deserializer: D) -> Result<Option<FeeRate>, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let f: Option<f64> = Option::deserialize(deserializer)?;
+    match f {
+        Some(v) => deserialize_btc_per_kvb(v.into_deserializer()).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Deserializes the hex of service flags, such as `0000000000000409`, into proper
+/// [`ServiceFlags`].
+fn deserialize_service_flags<'d, D>(deserializer: D) -> Result<ServiceFlags, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let hex = String::deserialize(deserializer)?;
+    u64::from_str_radix(&hex, 16)
+        .map(ServiceFlags::from)
+        .map_err(|e| de::Error::custom(format!("failed to deserialize service flags: {e}")))
+}
+
+/// Serializes [`ServiceFlags`] into hex as returned by `bitcoind`.
+fn serialize_service_flags<S>(flags: &ServiceFlags, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&format!("{:016x}", flags.to_u64()))
+}
+
+/// Deserializes warnings, a string before Bitcoin Core v28 and a list of strings since.
+fn deserialize_warnings<'d, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Warnings {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Warnings::deserialize(deserializer)? {
+        Warnings::One(warning) if warning.is_empty() => Vec::new(),
+        Warnings::One(warning) => vec![warning],
+        Warnings::Many(warnings) => warnings,
+    })
+}
+
+/// Deserializes a proof of work target from hex into proper [`Target`].
+fn deserialize_target<'d, D>(deserializer: D) -> Result<Target, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let hex = String::deserialize(deserializer)?;
+    Target::from_unprefixed_hex(&hex)
+        .map_err(|e| de::Error::custom(format!("failed to deserialize target: {e}")))
+}
+
+/// Deserializes a compact proof of work target from hex into proper [`CompactTarget`].
+fn deserialize_compact_target<'d, D>(deserializer: D) -> Result<CompactTarget, D::Error>
+where
+    D: Deserializer<'d>,
+{
+    let hex = String::deserialize(deserializer)?;
+    CompactTarget::from_unprefixed_hex(&hex)
+        .map_err(|e| de::Error::custom(format!("failed to deserialize compact target: {e}")))
+}
+
 /// Deserializes the *signed* amount in BTC into proper [`SignedAmount`]s.
 fn deserialize_signed_bitcoin<'d, D>(deserializer: D) -> Result<SignedAmount, D::Error>
 where
@@ -1151,7 +2301,7 @@ pub struct WalletProcessPsbtResult {
     /// Contains the PSBT after wallet processing with any signatures or input data
     /// that could be added. Will be `None` if the transaction was fully extracted
     /// and the PSBT is no longer needed.
-    #[serde(deserialize_with = "deserialize_option_psbt")]
+    #[serde(deserialize_with = "deserialize_option_psbt", default)]
     pub psbt: Option<Psbt>,
 
     /// Whether the transaction is complete and ready for broadcast.
@@ -1296,6 +2446,938 @@ pub struct PsbtBumpFee {
     pub errors: Option<Vec<String>>,
 }
 
+/// A component of a BIP 14 user agent, such as `Satoshi:27.0.0`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct UserAgent {
+    /// The name of the client, such as `Satoshi` for Bitcoin Core.
+    pub name: String,
+    /// The version of the client.
+    pub version: String,
+    /// The comments of the component, such as set with `-uacomment`.
+    pub comments: Vec<String>,
+}
+
+impl UserAgent {
+    /// Parses a BIP 14 user agent, such as `/Satoshi:27.0.0(comment)/`, into its components.
+    ///
+    /// Components that do not follow the format are skipped.
+    pub fn parse(user_agent: &str) -> Vec<UserAgent> {
+        user_agent
+            .split('/')
+            .filter(|component| !component.is_empty())
+            .filter_map(|component| {
+                let (name, rest) = component.split_once(':')?;
+                let (version, comments) = match rest.split_once('(') {
+                    Some((version, comments)) => (
+                        version,
+                        comments
+                            .strip_suffix(')')?
+                            .split(';')
+                            .map(|comment| comment.trim().to_string())
+                            .collect(),
+                    ),
+                    None => (rest, Vec::new()),
+                };
+                Some(UserAgent {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    comments,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Result of JSON-RPC method `getnetworkinfo`.
+///
+/// Method call: `getnetworkinfo`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct GetNetworkInfo {
+    /// The version of `bitcoind`, such as 270100 for v27.1.0.
+    pub version: u32,
+    /// The BIP 14 user agent of `bitcoind`, such as `/Satoshi:27.1.0/`.
+    #[serde(rename = "subversion")]
+    pub sub_version: String,
+    /// The P2P protocol version.
+    #[serde(rename = "protocolversion")]
+    pub protocol_version: u32,
+    /// The services offered to peers.
+    #[serde(
+        rename = "localservices",
+        deserialize_with = "deserialize_service_flags",
+        serialize_with = "serialize_service_flags"
+    )]
+    pub local_services: ServiceFlags,
+    /// Whether transactions are relayed, unless `-blocksonly` is set.
+    #[serde(rename = "localrelay")]
+    pub local_relay: bool,
+    /// The time offset to the peers in seconds.
+    #[serde(rename = "timeoffset")]
+    pub time_offset: i64,
+    /// The total number of connections.
+    pub connections: u32,
+    /// The number of inbound connections.
+    pub connections_in: u32,
+    /// The number of outbound connections.
+    pub connections_out: u32,
+    /// Whether P2P networking is enabled, see `setnetworkactive`.
+    #[serde(rename = "networkactive")]
+    pub network_active: bool,
+    /// The reachability of each network, such as `ipv4` or `onion`.
+    pub networks: Vec<NetworkReachability>,
+    /// The minimum relay fee rate of transactions.
+    #[serde(rename = "relayfee", deserialize_with = "deserialize_btc_per_kvb")]
+    pub relay_fee: FeeRate,
+    /// The minimum fee rate increment for mempool limiting and replacements.
+    #[serde(
+        rename = "incrementalfee",
+        deserialize_with = "deserialize_btc_per_kvb"
+    )]
+    pub incremental_fee: FeeRate,
+    /// The addresses the node is reachable on, as advertised to peers.
+    #[serde(rename = "localaddresses")]
+    pub local_addresses: Vec<LocalAddress>,
+    /// Network and blockchain warnings.
+    #[serde(deserialize_with = "deserialize_warnings")]
+    pub warnings: Vec<String>,
+}
+
+impl GetNetworkInfo {
+    /// Returns the major, minor and patch version of `bitcoind`.
+    pub fn server_version(&self) -> (u32, u32, u32) {
+        (
+            self.version / 10_000,
+            self.version / 100 % 100,
+            self.version % 100,
+        )
+    }
+
+    /// Parses [`GetNetworkInfo::sub_version`] into its components.
+    pub fn user_agent(&self) -> Vec<UserAgent> {
+        UserAgent::parse(&self.sub_version)
+    }
+}
+
+/// The reachability of a network in [`GetNetworkInfo`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NetworkReachability {
+    /// The network, such as `ipv4`, `ipv6`, `onion`, `i2p` or `cjdns`.
+    pub name: String,
+    /// Whether the network is disabled with `-onlynet`.
+    pub limited: bool,
+    /// Whether the network is reachable.
+    pub reachable: bool,
+    /// The proxy used for the network, empty if none.
+    pub proxy: String,
+    /// Whether randomized credentials are used for the proxy.
+    pub proxy_randomize_credentials: bool,
+}
+
+/// An address the node is reachable on in [`GetNetworkInfo`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LocalAddress {
+    /// The address.
+    pub address: String,
+    /// The port.
+    pub port: u16,
+    /// The relative score of the address.
+    pub score: u32,
+}
+
+/// The type of a connection to a peer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConnectionType {
+    /// An inbound connection.
+    Inbound,
+    /// An automatic outbound connection relaying blocks, transactions and addresses.
+    OutboundFullRelay,
+    /// An automatic outbound connection relaying blocks only.
+    BlockRelayOnly,
+    /// A connection added with `-addnode`, `-connect` or `addnode`.
+    Manual,
+    /// A short-lived connection to fetch addresses.
+    AddrFetch,
+    /// A short-lived connection to test that a peer is online.
+    Feeler,
+    /// A connection type unknown to this crate.
+    #[serde(untagged)]
+    Other(String),
+}
+
+/// A peer from JSON-RPC method `getpeerinfo`.
+///
+/// Method call: `getpeerinfo`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PeerInfo {
+    /// The id of the peer, as used by `disconnectnode`.
+    pub id: u64,
+    /// The address and port of the peer.
+    pub addr: String,
+    /// The local address and port of the connection.
+    #[serde(rename = "addrbind")]
+    pub addr_bind: Option<String>,
+    /// The local address as reported by the peer.
+    #[serde(rename = "addrlocal")]
+    pub addr_local: Option<String>,
+    /// The network of the peer, such as `ipv4` or `onion`.
+    pub network: String,
+    /// The services offered by the peer.
+    #[serde(
+        deserialize_with = "deserialize_service_flags",
+        serialize_with = "serialize_service_flags"
+    )]
+    pub services: ServiceFlags,
+    /// Whether the peer asked for transactions to be relayed.
+    #[serde(rename = "relaytxes")]
+    pub relay_txes: bool,
+    /// The time of the last message sent, in seconds since the Unix epoch.
+    #[serde(rename = "lastsend")]
+    pub last_send: u64,
+    /// The time of the last message received, in seconds since the Unix epoch.
+    #[serde(rename = "lastrecv")]
+    pub last_recv: u64,
+    /// The total number of bytes sent to the peer.
+    #[serde(rename = "bytessent")]
+    pub bytes_sent: u64,
+    /// The total number of bytes received from the peer.
+    #[serde(rename = "bytesrecv")]
+    pub bytes_recv: u64,
+    /// The time the connection was established, in seconds since the Unix epoch.
+    #[serde(rename = "conntime")]
+    pub conn_time: u64,
+    /// The time offset of the peer in seconds.
+    #[serde(rename = "timeoffset")]
+    pub time_offset: i64,
+    /// The last ping time in seconds, if any.
+    #[serde(rename = "pingtime")]
+    pub ping_time: Option<f64>,
+    /// The minimum ping time in seconds, if any.
+    #[serde(rename = "minping")]
+    pub min_ping: Option<f64>,
+    /// The P2P protocol version of the peer.
+    pub version: u32,
+    /// The BIP 14 user agent of the peer.
+    #[serde(rename = "subver")]
+    pub sub_version: String,
+    /// Whether the peer connected to the node.
+    pub inbound: bool,
+    /// The type of the connection.
+    pub connection_type: ConnectionType,
+    /// The height of the last header in common with the peer, -1 if unknown.
+    pub synced_headers: i64,
+    /// The height of the last block in common with the peer, -1 if unknown.
+    pub synced_blocks: i64,
+}
+
+impl PeerInfo {
+    /// Parses [`PeerInfo::sub_version`] into its components.
+    pub fn user_agent(&self) -> Vec<UserAgent> {
+        UserAgent::parse(&self.sub_version)
+    }
+}
+
+/// Result of JSON-RPC method `getnettotals`.
+///
+/// Method call: `getnettotals`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GetNetTotals {
+    /// The total number of bytes received.
+    #[serde(rename = "totalbytesrecv")]
+    pub total_bytes_recv: u64,
+    /// The total number of bytes sent.
+    #[serde(rename = "totalbytessent")]
+    pub total_bytes_sent: u64,
+    /// The current time in milliseconds since the Unix epoch.
+    #[serde(rename = "timemillis")]
+    pub time_millis: u64,
+    /// The upload target set with `-maxuploadtarget`.
+    #[serde(rename = "uploadtarget")]
+    pub upload_target: UploadTarget,
+}
+
+/// The upload target in [`GetNetTotals`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UploadTarget {
+    /// The length of the measuring timeframe in seconds.
+    pub timeframe: u64,
+    /// The target in bytes, 0 if unlimited.
+    pub target: u64,
+    /// Whether the target is reached.
+    pub target_reached: bool,
+    /// Whether historical blocks are still served.
+    pub serve_historical_blocks: bool,
+    /// The bytes left in the current timeframe.
+    pub bytes_left_in_cycle: u64,
+    /// The seconds left in the current timeframe.
+    pub time_left_in_cycle: u64,
+}
+
+/// The command of JSON-RPC method `addnode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddNodeCommand {
+    /// Adds the node to the list of nodes to keep connected to.
+    Add,
+    /// Removes the node from the list of nodes to keep connected to.
+    Remove,
+    /// Tries to connect to the node once.
+    OneTry,
+}
+
+/// The peer to disconnect with JSON-RPC method `disconnectnode`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DisconnectTarget {
+    /// The address and port of the peer, as in [`PeerInfo::addr`].
+    Address(String),
+    /// The id of the peer, as in [`PeerInfo::id`].
+    Id(u64),
+}
+
+/// The command of JSON-RPC method `setban`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SetBanCommand {
+    /// Bans the subnet and disconnects the peers in it.
+    Add,
+    /// Lifts the ban of the subnet.
+    Remove,
+}
+
+/// A banned subnet from JSON-RPC method `listbanned`.
+///
+/// Method call: `listbanned`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BannedSubnet {
+    /// The subnet, such as `192.168.0.6/32`.
+    pub address: String,
+    /// The time the ban was created, in seconds since the Unix epoch.
+    pub ban_created: u64,
+    /// The time the ban expires, in seconds since the Unix epoch.
+    pub banned_until: u64,
+    /// The duration of the ban in seconds.
+    pub ban_duration: u64,
+    /// The time left of the ban in seconds.
+    pub time_remaining: u64,
+}
+
+/// A rule supported by the client of JSON-RPC method `getblocktemplate`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateRule {
+    /// Segregated witness, required by `bitcoind`.
+    Segwit,
+    /// Signet block signing, required by `bitcoind` on signet.
+    Signet,
+}
+
+/// Result of JSON-RPC method `getblocktemplate`.
+///
+/// Method call: `getblocktemplate {"rules": ["segwit", ...]}`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct GetBlockTemplate {
+    /// The block version.
+    pub version: block::Version,
+    /// The rules the template follows.
+    pub rules: Vec<String>,
+    /// The hash of the block to build on.
+    #[serde(rename = "previousblockhash")]
+    pub previous_block_hash: BlockHash,
+    /// The transactions to include after the coinbase, in order.
+    pub transactions: Vec<BlockTemplateTransaction>,
+    /// The maximum value of the coinbase outputs, the subsidy plus the fees.
+    #[serde(rename = "coinbasevalue")]
+    pub coinbase_value: Amount,
+    /// The id to long poll for a new template with.
+    #[serde(rename = "longpollid")]
+    pub long_poll_id: String,
+    /// The proof of work target.
+    #[serde(deserialize_with = "deserialize_target")]
+    pub target: Target,
+    /// The compact proof of work target.
+    #[serde(deserialize_with = "deserialize_compact_target")]
+    pub bits: CompactTarget,
+    /// The minimum timestamp of the block.
+    #[serde(rename = "mintime")]
+    pub min_time: u32,
+    /// The current time of `bitcoind`, as a timestamp for the block.
+    #[serde(rename = "curtime")]
+    pub cur_time: u32,
+    /// The height of the block.
+    pub height: u64,
+    /// The maximum number of signature operations of the block.
+    #[serde(rename = "sigoplimit")]
+    pub sigop_limit: u64,
+    /// The maximum weight of the block.
+    #[serde(rename = "weightlimit")]
+    pub weight_limit: u64,
+    /// The witness commitment output of the coinbase, if the block needs one.
+    pub default_witness_commitment: Option<ScriptBuf>,
+    /// The challenge of the signet the block is for, if any.
+    pub signet_challenge: Option<ScriptBuf>,
+}
+
+/// Maximum size of the coinbase `scriptSig` allowed by consensus.
+const MAX_COINBASE_SCRIPT_SIG_SIZE: usize = 100;
+
+impl GetBlockTemplate {
+    /// Assembles a block from the template, paying [`GetBlockTemplate::coinbase_value`] to
+    /// `script_pubkey`.
+    ///
+    /// `coinbase_data` is pushed after the BIP 34 height in the coinbase `scriptSig`, such as
+    /// an extra nonce or a pool tag.
+    ///
+    /// # Errors
+    ///
+    /// [`ClientError::CoinbaseDataTooLong`] if the `scriptSig` would be longer than the 100
+    /// bytes allowed by consensus, which leaves room for about 90 bytes of data.
+    ///
+    /// # Note
+    ///
+    /// The proof of work is left to the caller: the header nonce is 0. On regtest, incrementing
+    /// it until [`Header::validate_pow`] passes takes a handful of attempts. On signet, the
+    /// block also needs a signature in its witness commitment.
+    pub fn block(
+        &self,
+        script_pubkey: ScriptBuf,
+        coinbase_data: &[u8],
+    ) -> Result<Block, ClientError> {
+        let too_long = || ClientError::CoinbaseDataTooLong(coinbase_data.len());
+        let push_data = PushBytesBuf::try_from(coinbase_data.to_vec()).map_err(|_| too_long())?;
+        let script_sig = script::Builder::new()
+            .push_int(self.height as i64)
+            .push_slice(push_data)
+            .into_script();
+        if script_sig.len() > MAX_COINBASE_SCRIPT_SIG_SIZE {
+            return Err(too_long());
+        }
+
+        let mut output = vec![TxOut {
+            value: self.coinbase_value,
+            script_pubkey,
+        }];
+        let mut witness = Witness::new();
+        if let Some(commitment) = &self.default_witness_commitment {
+            output.push(TxOut {
+                value: Amount::ZERO,
+                script_pubkey: commitment.clone(),
+            });
+            // The witness reserved value the default commitment is computed with.
+            witness.push([0u8; 32]);
+        }
+        let coinbase = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig,
+                sequence: Sequence::MAX,
+                witness,
+            }],
+            output,
+        };
+
+        let mut block = Block {
+            header: Header {
+                version: self.version,
+                prev_blockhash: self.previous_block_hash,
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: self.cur_time,
+                bits: self.bits,
+                nonce: 0,
+            },
+            txdata: std::iter::once(coinbase)
+                .chain(self.transactions.iter().map(|tx| tx.data.clone()))
+                .collect(),
+        };
+        block.header.merkle_root = block
+            .compute_merkle_root()
+            .expect("block has a coinbase transaction");
+        Ok(block)
+    }
+}
+
+/// A transaction of [`GetBlockTemplate`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BlockTemplateTransaction {
+    /// The transaction.
+    #[serde(deserialize_with = "deserialize_tx")]
+    pub data: Transaction,
+    /// The transaction id.
+    pub txid: Txid,
+    /// The witness transaction id.
+    pub hash: Wtxid,
+    /// The positions in [`GetBlockTemplate::transactions`], starting at 1, of the
+    /// transactions this one spends from.
+    pub depends: Vec<usize>,
+    /// The fee of the transaction.
+    pub fee: Amount,
+    /// The number of signature operations of the transaction.
+    pub sigops: u64,
+    /// The weight of the transaction.
+    pub weight: u64,
+}
+
+/// Result of JSON-RPC method `generateblock`.
+///
+/// Method call: `generateblock "output" ["rawtx/txid",...] ( submit )`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GenerateBlock {
+    /// The hash of the mined block.
+    pub hash: BlockHash,
+}
+
+/// Result of JSON-RPC method `getmininginfo`.
+///
+/// Method call: `getmininginfo`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct GetMiningInfo {
+    /// The height of the chain tip.
+    pub blocks: u64,
+    /// The weight of the last assembled block template, if any.
+    #[serde(rename = "currentblockweight")]
+    pub current_block_weight: Option<u64>,
+    /// The number of transactions of the last assembled block template, if any.
+    #[serde(rename = "currentblocktx")]
+    pub current_block_tx: Option<u64>,
+    /// The difficulty of the chain tip.
+    pub difficulty: f64,
+    /// The estimated hashes per second of the network.
+    #[serde(rename = "networkhashps")]
+    pub network_hash_ps: f64,
+    /// The number of transactions in the mempool.
+    #[serde(rename = "pooledtx")]
+    pub pooled_tx: u64,
+    /// The name of the chain, such as `regtest`.
+    pub chain: String,
+    /// Network and blockchain warnings.
+    #[serde(deserialize_with = "deserialize_warnings")]
+    pub warnings: Vec<String>,
+}
+
+/// The outcome of JSON-RPC methods `submitblock` and `submitheader` when a block or header is
+/// not accepted.
+///
+/// # Note
+///
+/// The BIP 22 reasons most relevant to miners have their own variant, the others are kept in
+/// [`SubmitBlockReason::Other`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SubmitBlockReason {
+    /// The block is already known and valid.
+    Duplicate,
+    /// The block is already known and invalid.
+    DuplicateInvalid,
+    /// The block is already known but not fully validated, such as on a side chain.
+    DuplicateInconclusive,
+    /// The block was accepted but not fully validated, such as on a side chain.
+    Inconclusive,
+    /// The block was rejected without a specific reason.
+    Rejected,
+    /// The proof of work does not meet the target.
+    HighHash,
+    /// The compact target does not match the expected difficulty.
+    BadDiffBits,
+    /// The merkle root does not match the transactions.
+    BadTxnMrklRoot,
+    /// The coinbase pays more than the subsidy and fees.
+    BadCbAmount,
+    /// The coinbase does not start with the height.
+    BadCbHeight,
+    /// The witness commitment does not match the transactions.
+    BadWitnessMerkleMatch,
+    /// The previous block is unknown or invalid.
+    BadPrevBlk,
+    /// The timestamp is not after the median time of the last 11 blocks.
+    TimeTooOld,
+    /// The timestamp is more than 2 hours in the future.
+    TimeTooNew,
+    /// Any other reason, as reported by `bitcoind`.
+    Other(String),
+}
+
+impl From<&str> for SubmitBlockReason {
+    fn from(reason: &str) -> Self {
+        match reason.trim() {
+            "duplicate" => Self::Duplicate,
+            "duplicate-invalid" => Self::DuplicateInvalid,
+            "duplicate-inconclusive" => Self::DuplicateInconclusive,
+            "inconclusive" => Self::Inconclusive,
+            "rejected" => Self::Rejected,
+            "high-hash" => Self::HighHash,
+            "bad-diffbits" => Self::BadDiffBits,
+            "bad-txnmrklroot" => Self::BadTxnMrklRoot,
+            "bad-cb-amount" => Self::BadCbAmount,
+            "bad-cb-height" => Self::BadCbHeight,
+            "bad-witness-merkle-match" => Self::BadWitnessMerkleMatch,
+            "bad-prevblk" | "prev-blk-not-found" => Self::BadPrevBlk,
+            // `submitheader` reports an unknown previous header in a sentence.
+            other if other.starts_with("Must submit previous header") => Self::BadPrevBlk,
+            "time-too-old" => Self::TimeTooOld,
+            "time-too-new" => Self::TimeTooNew,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for SubmitBlockReason {
+    fn from(reason: String) -> Self {
+        Self::from(reason.as_str())
+    }
+}
+
+/// Result of JSON-RPC method `getrpcinfo`.
+///
+/// Method call: `getrpcinfo`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GetRpcInfo {
+    /// The commands being executed, including this one.
+    pub active_commands: Vec<ActiveCommand>,
+    /// The path of the debug log.
+    #[serde(rename = "logpath")]
+    pub log_path: String,
+}
+
+/// A command being executed in [`GetRpcInfo`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ActiveCommand {
+    /// The name of the JSON-RPC method.
+    pub method: String,
+    /// How long the command has been running in microseconds.
+    pub duration: u64,
+}
+
+/// The state of an index from JSON-RPC method `getindexinfo`.
+///
+/// Method call: `getindexinfo ( "index_name" )`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct IndexInfo {
+    /// Whether the index is synced with the chain tip.
+    pub synced: bool,
+    /// The height of the last block indexed.
+    pub best_block_height: u64,
+}
+
+/// Result of JSON-RPC method `getdeploymentinfo`.
+///
+/// Method call: `getdeploymentinfo ( "blockhash" )`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct GetDeploymentInfo {
+    /// The hash of the block the state is for.
+    pub hash: BlockHash,
+    /// The height of the block the state is for.
+    pub height: u64,
+    /// The state of the deployments by name, such as `segwit` or `taproot`.
+    pub deployments: BTreeMap<String, DeploymentInfo>,
+}
+
+/// The kind of a deployment in [`GetDeploymentInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeploymentType {
+    /// A softfork activated at a fixed height.
+    Buried,
+    /// A softfork activated by BIP 9 version bits signalling.
+    Bip9,
+}
+
+/// The state of a deployment in [`GetDeploymentInfo`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DeploymentInfo {
+    /// The kind of deployment.
+    #[serde(rename = "type")]
+    pub type_: DeploymentType,
+    /// The height the rules are enforced from, once known.
+    pub height: Option<u64>,
+    /// Whether the rules are enforced for the next block.
+    pub active: bool,
+    /// The version bits state, for [`DeploymentType::Bip9`] deployments.
+    pub bip9: Option<Bip9Info>,
+}
+
+/// The BIP 9 state of a deployment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bip9Status {
+    /// The start time is not reached yet.
+    Defined,
+    /// Miners are signalling.
+    Started,
+    /// The threshold was reached, the rules activate after one more period.
+    LockedIn,
+    /// The rules are enforced.
+    Active,
+    /// The timeout was reached before the threshold.
+    Failed,
+}
+
+/// The BIP 9 version bits information of a [`DeploymentInfo`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Bip9Info {
+    /// The version bit signalling the deployment, while signalling is possible.
+    pub bit: Option<u8>,
+    /// The median time past from which signalling starts.
+    pub start_time: i64,
+    /// The median time past from which the deployment fails if not locked in.
+    pub timeout: i64,
+    /// The minimum height the rules can be enforced from.
+    pub min_activation_height: u64,
+    /// The state of the deployment for the block.
+    pub status: Bip9Status,
+    /// The height of the first block the status applies to.
+    pub since: u64,
+    /// The state of the deployment for the next block.
+    pub status_next: Bip9Status,
+    /// The signalling in the current period, while the deployment is started.
+    pub statistics: Option<Bip9Statistics>,
+}
+
+/// The signalling of a BIP 9 deployment in its current period.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Bip9Statistics {
+    /// The length of a period in blocks.
+    pub period: u32,
+    /// The number of signalling blocks needed in a period to lock in.
+    pub threshold: Option<u32>,
+    /// The number of blocks elapsed in the current period.
+    pub elapsed: u32,
+    /// The number of signalling blocks in the current period.
+    pub count: u32,
+    /// Whether the threshold can still be reached in the current period.
+    pub possible: Option<bool>,
+}
+
+/// Result of the `decoderawtransaction` RPC method.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DecodeRawTransaction {
+    /// The transaction id.
+    pub txid: Txid,
+    /// The witness transaction id.
+    pub hash: Wtxid,
+    /// The serialized size in bytes.
+    pub size: u64,
+    /// The virtual size in vbytes.
+    pub vsize: u64,
+    /// The weight in weight units.
+    pub weight: u64,
+    /// The version.
+    pub version: i32,
+    /// The lock time.
+    pub locktime: u32,
+    /// The inputs.
+    pub vin: Vec<DecodedTxIn>,
+    /// The outputs.
+    pub vout: Vec<DecodedTxOut>,
+}
+
+/// An input of a [`DecodeRawTransaction`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DecodedTxIn {
+    /// The `scriptSig` of a coinbase input.
+    #[serde(default)]
+    pub coinbase: Option<ScriptBuf>,
+    /// The id of the transaction spent, `None` for a coinbase input.
+    #[serde(default)]
+    pub txid: Option<Txid>,
+    /// The index of the output spent, `None` for a coinbase input.
+    #[serde(default)]
+    pub vout: Option<u32>,
+    /// The `scriptSig`, `None` for a coinbase input.
+    #[serde(
+        rename = "scriptSig",
+        default,
+        deserialize_with = "deserialize_option_script_sig"
+    )]
+    pub script_sig: Option<ScriptBuf>,
+    /// The witness.
+    #[serde(
+        rename = "txinwitness",
+        default,
+        deserialize_with = "deserialize_witness"
+    )]
+    pub witness: Witness,
+    /// The sequence number.
+    pub sequence: Sequence,
+}
+
+impl DecodedTxIn {
+    /// Returns the outpoint spent, `None` for a coinbase input.
+    pub fn previous_output(&self) -> Option<OutPoint> {
+        Some(OutPoint::new(self.txid?, self.vout?))
+    }
+}
+
+/// An output of a [`DecodeRawTransaction`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DecodedTxOut {
+    /// The value.
+    #[serde(deserialize_with = "deserialize_bitcoin")]
+    pub value: Amount,
+    /// The index of the output.
+    pub n: u32,
+    /// The decoded `scriptPubKey`.
+    #[serde(rename = "scriptPubKey")]
+    pub script_pubkey: DecodedScriptPubKey,
+}
+
+/// A decoded `scriptPubKey`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DecodedScriptPubKey {
+    /// The script.
+    #[serde(rename = "hex")]
+    pub script: ScriptBuf,
+    /// The script disassembly.
+    pub asm: String,
+    /// The inferred output descriptor.
+    pub desc: Option<String>,
+    /// The type of the script, such as `witness_v0_keyhash`.
+    #[serde(rename = "type")]
+    pub script_type: String,
+    /// The address of the script, if it has one.
+    pub address: Option<Address<NetworkUnchecked>>,
+}
+
+/// Result of the `decodescript` RPC method.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DecodeScript {
+    /// The script disassembly.
+    pub asm: String,
+    /// The inferred output descriptor.
+    pub desc: Option<String>,
+    /// The type of the script, such as `multisig`.
+    #[serde(rename = "type")]
+    pub script_type: String,
+    /// The address of the script as an output script, if it has one.
+    pub address: Option<Address<NetworkUnchecked>>,
+    /// The P2SH address wrapping the script, if it can be wrapped.
+    pub p2sh: Option<Address<NetworkUnchecked>>,
+    /// The P2WSH or P2WPKH output wrapping the script, if it can be wrapped.
+    pub segwit: Option<DecodeScriptSegwit>,
+}
+
+/// The segwit output wrapping a script in a [`DecodeScript`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DecodeScriptSegwit {
+    /// The segwit output script.
+    #[serde(rename = "hex")]
+    pub script: ScriptBuf,
+    /// The segwit output script disassembly.
+    pub asm: String,
+    /// The inferred output descriptor.
+    pub desc: Option<String>,
+    /// The type of the segwit output script, such as `witness_v0_scripthash`.
+    #[serde(rename = "type")]
+    pub script_type: String,
+    /// The address of the segwit output script.
+    pub address: Option<Address<NetworkUnchecked>>,
+    /// The P2SH address wrapping the segwit output script.
+    #[serde(rename = "p2sh-segwit")]
+    pub p2sh_segwit: Option<Address<NetworkUnchecked>>,
+}
+
+/// Result of the `decodepsbt` RPC method.
+///
+/// # Note
+///
+/// Only holds what `bitcoind` adds to the PSBT. The PSBT itself is already typed, use
+/// [`Psbt`] to look at its inputs and outputs.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DecodePsbt {
+    /// The decoded unsigned transaction.
+    pub tx: DecodeRawTransaction,
+    /// The fee paid, if the value of every input is known.
+    #[serde(default, deserialize_with = "deserialize_option_bitcoin")]
+    pub fee: Option<Amount>,
+}
+
+/// The role that has to process a PSBT next, as defined by BIP 174.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PsbtRole {
+    /// Creates the PSBT.
+    Creator,
+    /// Adds the UTXOs and scripts of the inputs.
+    Updater,
+    /// Adds the signatures.
+    Signer,
+    /// Builds the final `scriptSig`s and witnesses from the signatures.
+    Finalizer,
+    /// Extracts the signed transaction.
+    Extractor,
+}
+
+/// Result of the `analyzepsbt` RPC method.
+///
+/// Explains what is left to do with a PSBT, such as why
+/// [`WalletProcessPsbtResult::complete`] is `false`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AnalyzePsbt {
+    /// The analysis of each input.
+    #[serde(default)]
+    pub inputs: Vec<AnalyzePsbtInput>,
+    /// The estimated virtual size of the signed transaction, once all UTXOs are known.
+    pub estimated_vsize: Option<u64>,
+    /// The estimated fee rate of the signed transaction, once all UTXOs are known.
+    #[serde(default, deserialize_with = "deserialize_option_btc_per_kvb")]
+    pub estimated_feerate: Option<FeeRate>,
+    /// The fee paid, once all UTXOs are known.
+    #[serde(default, deserialize_with = "deserialize_option_bitcoin")]
+    pub fee: Option<Amount>,
+    /// The role that has to process the PSBT next.
+    pub next: PsbtRole,
+    /// Why the PSBT is invalid, if it is.
+    pub error: Option<String>,
+}
+
+/// The analysis of an input in an [`AnalyzePsbt`].
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AnalyzePsbtInput {
+    /// Whether the UTXO spent is known.
+    pub has_utxo: bool,
+    /// Whether the input has its final `scriptSig` and witness.
+    pub is_final: bool,
+    /// What is missing to sign the input, if anything.
+    pub missing: Option<PsbtMissing>,
+    /// The role that has to process the input next.
+    pub next: Option<PsbtRole>,
+}
+
+/// What is missing to sign an input of a PSBT, in an [`AnalyzePsbtInput`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PsbtMissing {
+    /// The hashes of the public keys needed to solve the script.
+    #[serde(default)]
+    pub pubkeys: Vec<PubkeyHash>,
+    /// The hashes of the public keys whose signatures are missing.
+    #[serde(default)]
+    pub signatures: Vec<PubkeyHash>,
+    /// The hash of the missing redeem script.
+    pub redeemscript: Option<ScriptHash>,
+    /// The hash of the missing witness script.
+    pub witnessscript: Option<WScriptHash>,
+}
+
+/// Result of the `getdescriptorinfo` RPC method.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GetDescriptorInfo {
+    /// The descriptor in canonical form with its checksum appended, such as
+    /// `addr(bcrt1q...)#v9e9qrqq`.
+    ///
+    /// Private keys are replaced by their public keys.
+    pub descriptor: String,
+    /// The checksum of the descriptor as given.
+    pub checksum: String,
+    /// Whether the descriptor is ranged, such as with a `*` in a derivation path.
+    #[serde(rename = "isrange")]
+    pub is_range: bool,
+    /// Whether the descriptor has all the information needed to sign for it.
+    #[serde(rename = "issolvable")]
+    pub is_solvable: bool,
+    /// Whether the descriptor as given has private keys.
+    #[serde(rename = "hasprivatekeys")]
+    pub has_private_keys: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1308,6 +3390,710 @@ mod tests {
     // Valid Bitcoin transaction hex (Genesis block coinbase transaction)
     const TEST_TX_HEX: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
 
+    #[test]
+    fn test_get_block_verbose_with_prevouts() {
+        use bitcoin::{
+            absolute::LockTime, consensus::encode::serialize_hex, constants::genesis_block,
+            transaction::Version, Network, OutPoint, Sequence, TxIn, Witness,
+        };
+
+        let coinbase = genesis_block(Network::Regtest).txdata[0].clone();
+        let spend = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(coinbase.compute_txid(), 0),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(49_999_000),
+                script_pubkey: ScriptBuf::new_op_return([]),
+            }],
+        };
+        let prevout_script = &coinbase.output[0].script_pubkey;
+        let json = serde_json::json!({
+            "hash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+            "confirmations": 1,
+            "size": 285,
+            "strippedsize": 285,
+            "weight": 1140,
+            "height": 1,
+            "version": 1,
+            "versionHex": "00000001",
+            "merkleroot": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+            "tx": [
+                {
+                    "txid": coinbase.compute_txid(),
+                    "hash": coinbase.compute_wtxid(),
+                    "size": 204,
+                    "vsize": 204,
+                    "weight": 816,
+                    "vin": [{ "coinbase": "04ffff001d", "sequence": 4294967295u32 }],
+                    "hex": serialize_hex(&coinbase),
+                },
+                {
+                    "txid": spend.compute_txid(),
+                    "hash": spend.compute_wtxid(),
+                    "size": 81,
+                    "vsize": 81,
+                    "weight": 324,
+                    "fee": 0.49000000,
+                    "vin": [{
+                        "txid": coinbase.compute_txid(),
+                        "vout": 0,
+                        "prevout": {
+                            "generated": true,
+                            "height": 0,
+                            "value": 0.50000000,
+                            "scriptPubKey": {
+                                "asm": prevout_script.to_asm_string(),
+                                "hex": prevout_script.to_hex_string(),
+                                "type": "pubkey",
+                            },
+                        },
+                        "sequence": 4294967295u32,
+                    }],
+                    "hex": serialize_hex(&spend),
+                },
+            ],
+            "time": 1296688602,
+            "mediantime": 1296688602,
+            "nonce": 2,
+            "bits": "207fffff",
+            "difficulty": 4.656542373906925e-10,
+            "chainwork": "0000000000000000000000000000000000000000000000000000000000000004",
+            "nTx": 2,
+            "previousblockhash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+        });
+
+        let block: GetBlockVerbose = serde_json::from_value(json).unwrap();
+        assert_eq!(block.height, 1);
+        assert_eq!(block.next_block_hash, None);
+
+        let [coinbase_tx, spend_tx] = &block.tx[..] else {
+            panic!("expected two transactions");
+        };
+        assert_eq!(coinbase_tx.transaction, coinbase);
+        assert_eq!(coinbase_tx.fee, None);
+        assert_eq!(coinbase_tx.prevouts(), None);
+
+        assert_eq!(spend_tx.transaction, spend);
+        assert_eq!(spend_tx.fee, Some(Amount::from_sat(49_000_000)));
+        assert_eq!(
+            spend_tx.prevouts(),
+            Some(vec![TxOut {
+                value: Amount::from_sat(50_000_000),
+                script_pubkey: prevout_script.clone(),
+            }])
+        );
+        assert_eq!(spend_tx.input_value(), Some(Amount::from_sat(50_000_000)));
+        assert!(spend_tx.vin[0].prevout.as_ref().unwrap().generated);
+    }
+
+    #[test]
+    fn test_tx_out_set() {
+        let json = r#"{
+            "height": 101,
+            "bestblock": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+            "txouts": 101,
+            "bogosize": 7575,
+            "hash_serialized_3": "9d6e8bd5f04fdbe1ad4b5c3bca6ec4bd4db36a1d5d7fa4bee5ffc0ba34a3ff8b",
+            "transactions": 101,
+            "disk_size": 7130,
+            "total_amount": 5050.00000000
+        }"#;
+        let info: GetTxOutSetInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.tx_outs, 101);
+        assert_eq!(info.total_amount, Amount::from_int_btc(5050));
+        assert!(info.hash_serialized_3.is_some());
+        assert_eq!(info.muhash, None);
+        assert_eq!(info.total_unspendable_amount, None);
+
+        let json = r#"{
+            "success": true,
+            "txouts": 101,
+            "height": 101,
+            "bestblock": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+            "unspents": [
+                {
+                    "txid": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+                    "vout": 1,
+                    "scriptPubKey": "0014d0c4a3ef09e997b6e99e397e518fe3e41a118ca1",
+                    "desc": "addr(bcrt1q6rz28mcfaxtmd6v789l9rrlrusdprr9pz3cppk)#4ahsl9pk",
+                    "amount": 0.50000000,
+                    "coinbase": false,
+                    "height": 101,
+                    "blockhash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+                    "confirmations": 1
+                }
+            ],
+            "total_amount": 0.50000000
+        }"#;
+        let scan: ScanTxOutSet = serde_json::from_str(json).unwrap();
+        assert!(scan.success);
+        let unspent = &scan.unspents[0];
+        assert_eq!(unspent.outpoint().vout, 1);
+        assert_eq!(unspent.tx_out().value, Amount::from_sat(50_000_000));
+        assert!(unspent.tx_out().script_pubkey.is_p2wpkh());
+        assert_eq!(unspent.height, 101);
+
+        let objects = [
+            ScanObject::from("addr(bcrt1q6rz28mcfaxtmd6v789l9rrlrusdprr9pz3cppk)"),
+            ScanObject::Ranged {
+                desc: "wpkh(tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp/0/*)".to_string(),
+                range: (0, 100),
+            },
+        ];
+        assert_eq!(
+            serde_json::to_value(objects).unwrap(),
+            serde_json::json!([
+                "addr(bcrt1q6rz28mcfaxtmd6v789l9rrlrusdprr9pz3cppk)",
+                {
+                    "desc": "wpkh(tpubD6NzVbkrYhZ4XgiXtGrdW5XDAPFCL9h7we1vwNCpn8tGbBcgfVYjXyhWo4E1xkh56hjod1RhGjxbaTLV3X4FyWuejifB9jusQ46QzG87VKp/0/*)",
+                    "range": [0, 100]
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_network_info() {
+        let json = r#"{
+            "version": 270100,
+            "subversion": "/Satoshi:27.1.0(dashboard; eu-west)/",
+            "protocolversion": 70016,
+            "localservices": "0000000000000c09",
+            "localservicesnames": ["NETWORK", "WITNESS", "NETWORK_LIMITED", "P2P_V2"],
+            "localrelay": true,
+            "timeoffset": 0,
+            "networkactive": true,
+            "connections": 10,
+            "connections_in": 0,
+            "connections_out": 10,
+            "networks": [
+                {
+                    "name": "ipv4",
+                    "limited": false,
+                    "reachable": true,
+                    "proxy": "",
+                    "proxy_randomize_credentials": false
+                }
+            ],
+            "relayfee": 0.00001000,
+            "incrementalfee": 0.00001000,
+            "localaddresses": [],
+            "warnings": ""
+        }"#;
+        let info: GetNetworkInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.server_version(), (27, 1, 0));
+        assert_eq!(
+            info.user_agent(),
+            vec![UserAgent {
+                name: "Satoshi".to_string(),
+                version: "27.1.0".to_string(),
+                comments: vec!["dashboard".to_string(), "eu-west".to_string()],
+            }]
+        );
+        assert!(info.local_services.has(ServiceFlags::WITNESS));
+        assert!(info.local_services.has(ServiceFlags::NETWORK_LIMITED));
+        assert!(!info.local_services.has(ServiceFlags::BLOOM));
+        assert_eq!(info.relay_fee, FeeRate::BROADCAST_MIN);
+        assert!(info.warnings.is_empty());
+
+        let info: GetNetworkInfo = serde_json::from_str(&json.replace(
+            r#""warnings": """#,
+            r#""warnings": ["This is a pre-release test build"]"#,
+        ))
+        .unwrap();
+        assert_eq!(info.warnings.len(), 1);
+
+        assert_eq!(
+            UserAgent::parse("/btcwire:0.5.0/btcd:0.12.0/"),
+            vec![
+                UserAgent {
+                    name: "btcwire".to_string(),
+                    version: "0.5.0".to_string(),
+                    comments: Vec::new(),
+                },
+                UserAgent {
+                    name: "btcd".to_string(),
+                    version: "0.12.0".to_string(),
+                    comments: Vec::new(),
+                },
+            ]
+        );
+        assert!(UserAgent::parse("garbage").is_empty());
+
+        let json = r#"{
+            "id": 3,
+            "addr": "203.0.113.7:8333",
+            "addrbind": "192.168.1.2:50124",
+            "network": "ipv4",
+            "services": "0000000000000409",
+            "servicesnames": ["NETWORK", "WITNESS", "NETWORK_LIMITED"],
+            "relaytxes": true,
+            "lastsend": 1700000100,
+            "lastrecv": 1700000101,
+            "last_transaction": 0,
+            "last_block": 0,
+            "bytessent": 1234,
+            "bytesrecv": 5678,
+            "conntime": 1700000000,
+            "timeoffset": -1,
+            "pingtime": 0.012,
+            "minping": 0.010,
+            "version": 70016,
+            "subver": "/Satoshi:26.0.0/",
+            "inbound": false,
+            "bip152_hb_to": false,
+            "bip152_hb_from": false,
+            "startingheight": 820000,
+            "presynced_headers": -1,
+            "synced_headers": 820010,
+            "synced_blocks": 820010,
+            "connection_type": "block-relay-only",
+            "transport_protocol_type": "v1",
+            "session_id": ""
+        }"#;
+        let peer: PeerInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(peer.connection_type, ConnectionType::BlockRelayOnly);
+        assert_eq!(peer.user_agent()[0].version, "26.0.0");
+        assert!(peer.services.has(ServiceFlags::NETWORK));
+        assert_eq!(peer.addr_local, None);
+
+        let peer: PeerInfo =
+            serde_json::from_str(&json.replace("block-relay-only", "private-broadcast")).unwrap();
+        assert_eq!(
+            peer.connection_type,
+            ConnectionType::Other("private-broadcast".to_string())
+        );
+
+        assert_eq!(
+            serde_json::to_value(AddNodeCommand::OneTry).unwrap(),
+            serde_json::json!("onetry")
+        );
+    }
+
+    #[test]
+    fn test_block_template() {
+        let json = r#"{
+            "capabilities": ["proposal"],
+            "version": 536870912,
+            "rules": ["csv", "!segwit", "taproot"],
+            "vbavailable": {},
+            "vbrequired": 0,
+            "previousblockhash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+            "transactions": [
+                {
+                    "data": "020000000001010000000000000000000000000000000000000000000000000000000000000000ffffffff0502cc000101ffffffff0200f2052a010000001600144a7f7e6fa5e3e3a11dc0ded4a30dbdafca0e1a9b0000000000000000266a24aa21a9ede2f61c3f71d1defd3fa999dfa36953755c690689799962b48bebd836974e8cf90120000000000000000000000000000000000000000000000000000000000000000000000000",
+                    "txid": "9a7e61d3bb6fb8a5d86afa2e8ed8d1fad1c4e52fec3cd2e5f48e6df8a3b1cbb3",
+                    "hash": "c2bd3b2bcd3a1bfb74b1a1a37ec0fcbd54a34bd5eafe7b2d7b4b2ab7d0d4d5d4",
+                    "depends": [],
+                    "fee": 1410,
+                    "sigops": 1,
+                    "weight": 561
+                }
+            ],
+            "coinbaseaux": {},
+            "coinbasevalue": 5000001410,
+            "longpollid": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e22061",
+            "target": "7fffff0000000000000000000000000000000000000000000000000000000000",
+            "mintime": 1296688603,
+            "mutable": ["time", "transactions", "prevblock"],
+            "noncerange": "00000000ffffffff",
+            "sigoplimit": 80000,
+            "sizelimit": 4000000,
+            "weightlimit": 4000000,
+            "curtime": 1700000000,
+            "bits": "207fffff",
+            "height": 200,
+            "default_witness_commitment": "6a24aa21a9ede2f61c3f71d1defd3fa999dfa36953755c690689799962b48bebd836974e8cf9"
+        }"#;
+        let template: GetBlockTemplate = serde_json::from_str(json).unwrap();
+        assert_eq!(template.coinbase_value, Amount::from_sat(5_000_001_410));
+        assert_eq!(template.transactions[0].fee, Amount::from_sat(1410));
+        assert_eq!(template.bits, CompactTarget::from_consensus(0x207fffff));
+        assert_eq!(template.target, Target::from_compact(template.bits));
+        assert_eq!(template.signet_challenge, None);
+
+        let script_pubkey = ScriptBuf::new_op_return([0xaa; 4]);
+        let block = template.block(script_pubkey.clone(), b"pool").unwrap();
+        assert_eq!(block.header.prev_blockhash, template.previous_block_hash);
+        assert_eq!(block.bip34_block_height().unwrap(), 200);
+        assert!(block.check_merkle_root());
+        assert_eq!(block.txdata.len(), 2);
+        let coinbase = &block.txdata[0];
+        assert_eq!(coinbase.output[0].value, template.coinbase_value);
+        assert_eq!(coinbase.output[0].script_pubkey, script_pubkey);
+        assert_eq!(
+            Some(&coinbase.output[1].script_pubkey),
+            template.default_witness_commitment.as_ref()
+        );
+        assert_eq!(coinbase.input[0].witness.len(), 1);
+
+        // The height takes 3 bytes and the push of the data 2 more.
+        assert!(template.block(script_pubkey.clone(), &[0; 95]).is_ok());
+        assert_eq!(
+            template.block(script_pubkey, &[0; 96]),
+            Err(ClientError::CoinbaseDataTooLong(96))
+        );
+
+        assert_eq!(
+            SubmitBlockReason::from("high-hash"),
+            SubmitBlockReason::HighHash
+        );
+        assert_eq!(
+            SubmitBlockReason::from("Must submit previous header (00ff) first"),
+            SubmitBlockReason::BadPrevBlk
+        );
+        assert_eq!(
+            SubmitBlockReason::from("bad-blk-sigops"),
+            SubmitBlockReason::Other("bad-blk-sigops".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deployment_info() {
+        let json = r#"{
+            "hash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+            "height": 0,
+            "deployments": {
+                "segwit": {"type": "buried", "active": true, "height": 0},
+                "testdummy": {
+                    "type": "bip9",
+                    "height": null,
+                    "active": false,
+                    "bip9": {
+                        "bit": 28,
+                        "start_time": 0,
+                        "timeout": 9223372036854775807,
+                        "min_activation_height": 0,
+                        "status": "started",
+                        "since": 144,
+                        "status_next": "started",
+                        "statistics": {
+                            "period": 144,
+                            "threshold": 108,
+                            "elapsed": 10,
+                            "count": 7,
+                            "possible": true
+                        }
+                    }
+                },
+                "taproot": {
+                    "type": "bip9",
+                    "height": 0,
+                    "active": true,
+                    "bip9": {
+                        "start_time": -1,
+                        "timeout": 9223372036854775807,
+                        "min_activation_height": 0,
+                        "status": "active",
+                        "since": 0,
+                        "status_next": "active"
+                    }
+                }
+            }
+        }"#;
+        let info: GetDeploymentInfo = serde_json::from_str(json).unwrap();
+        let segwit = &info.deployments["segwit"];
+        assert_eq!(segwit.type_, DeploymentType::Buried);
+        assert!(segwit.active);
+        assert!(segwit.bip9.is_none());
+
+        let testdummy = info.deployments["testdummy"].bip9.as_ref().unwrap();
+        assert_eq!(testdummy.status, Bip9Status::Started);
+        assert_eq!(testdummy.statistics.as_ref().unwrap().count, 7);
+
+        let taproot = info.deployments["taproot"].bip9.as_ref().unwrap();
+        assert_eq!(taproot.status, Bip9Status::Active);
+        assert_eq!(taproot.bit, None);
+
+        let json = r#"{"txindex": {"synced": true, "best_block_height": 101}}"#;
+        let indexes: BTreeMap<String, IndexInfo> = serde_json::from_str(json).unwrap();
+        assert!(indexes["txindex"].synced);
+    }
+
+    #[test]
+    fn test_fee_estimates() {
+        let estimate: SmartFeeEstimate =
+            serde_json::from_str(r#"{"feerate": 0.00002500, "blocks": 6}"#).unwrap();
+        assert_eq!(estimate.fee_rate, Some(FeeRate::from_sat_per_kwu(625)));
+        assert_eq!(estimate.blocks, 6);
+        assert!(estimate.errors.is_empty());
+
+        let estimate: SmartFeeEstimate = serde_json::from_str(
+            r#"{"errors": ["Insufficient data or no feerate found"], "blocks": 0}"#,
+        )
+        .unwrap();
+        assert_eq!(estimate.fee_rate, None);
+        assert_eq!(estimate.errors.len(), 1);
+
+        let json = r#"{
+            "medium": {
+                "feerate": 0.00001000,
+                "decay": 0.9952,
+                "scale": 2,
+                "pass": {
+                    "startrange": 1000,
+                    "endrange": 1050,
+                    "withintarget": 12.5,
+                    "totalconfirmed": 12.5,
+                    "inmempool": 0,
+                    "leftmempool": 0
+                },
+                "fail": {
+                    "startrange": 0,
+                    "endrange": 1000,
+                    "withintarget": 0,
+                    "totalconfirmed": 3.2,
+                    "inmempool": 1,
+                    "leftmempool": 0
+                }
+            },
+            "long": {
+                "decay": 0.99931,
+                "scale": 24,
+                "fail": {
+                    "startrange": 1000,
+                    "endrange": 1e99,
+                    "withintarget": 2.3,
+                    "totalconfirmed": 12.5,
+                    "inmempool": 0,
+                    "leftmempool": 0
+                },
+                "errors": ["Insufficient data or no feerate found which meets threshold"]
+            }
+        }"#;
+        let raw: EstimateRawFee = serde_json::from_str(json).unwrap();
+        assert!(raw.short.is_none());
+        let medium = raw.medium.unwrap();
+        assert_eq!(medium.fee_rate, Some(FeeRate::BROADCAST_MIN));
+        assert_eq!(medium.pass.unwrap().within_target, 12.5);
+        let long = raw.long.unwrap();
+        assert_eq!(long.fee_rate, None);
+        assert!(long.pass.is_none());
+        assert_eq!(long.errors.len(), 1);
+
+        assert_eq!(
+            serde_json::to_value(EstimateMode::Economical).unwrap(),
+            serde_json::json!("ECONOMICAL")
+        );
+    }
+
+    #[test]
+    fn test_get_mempool_entry() {
+        let json = r#"{
+            "vsize": 141,
+            "weight": 561,
+            "time": 1750000000,
+            "height": 101,
+            "descendantcount": 2,
+            "descendantsize": 251,
+            "descendantfees": 12100,
+            "ancestorcount": 1,
+            "ancestorsize": 141,
+            "ancestorfees": 141,
+            "wtxid": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+            "fees": {
+                "base": 0.00000141,
+                "modified": 0.00000141,
+                "ancestor": 0.00000141,
+                "descendant": 0.00012100
+            },
+            "depends": [],
+            "spentby": ["0e3e2357e806b6cdb1f70b54c3a3a17b6714ee1f0e68bebb44a74b1efd512098"],
+            "bip125-replaceable": true,
+            "unbroadcast": false
+        }"#;
+        let entry: GetMempoolEntry = serde_json::from_str(json).unwrap();
+        assert_eq!(entry.fees.base, Amount::from_sat(141));
+        assert_eq!(entry.spent_by.len(), 1);
+        assert!(entry.bip125_replaceable);
+        assert_eq!(entry.fee_rate(), FeeRate::from_sat_per_vb(1));
+        assert_eq!(entry.ancestor_fee_rate(), FeeRate::from_sat_per_vb(1));
+        // 12100 sat / 251 vB, rounded up.
+        assert_eq!(
+            entry.descendant_fee_rate(),
+            Some(FeeRate::from_sat_per_kwu(12_052))
+        );
+
+        let json = r#"{
+            "loaded": true,
+            "size": 2,
+            "bytes": 392,
+            "usage": 2768,
+            "total_fee": 0.00012241,
+            "maxmempool": 300000000,
+            "mempoolminfee": 0.00001000,
+            "minrelaytxfee": 0.00001000,
+            "incrementalrelayfee": 0.00001000,
+            "unbroadcastcount": 2,
+            "fullrbf": true
+        }"#;
+        let info: GetMempoolInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.mempoolminfee, FeeRate::BROADCAST_MIN);
+        assert_eq!(info.minrelaytxfee, FeeRate::BROADCAST_MIN);
+    }
+
+    #[test]
+    fn test_get_block_filter() {
+        use bitcoin::hashes::Hash;
+
+        // Filter of the testnet genesis block, from the BIP 158 test vectors.
+        let json = r#"{
+            "filter": "019dfca8",
+            "header": "21584579b7eb08997773e5aeff3a7f932700042d0ed2a6129012b7d7ae81b750"
+        }"#;
+        let filter: GetBlockFilter = serde_json::from_str(json).unwrap();
+        assert_eq!(filter.filter.content, vec![0x01, 0x9d, 0xfc, 0xa8]);
+        assert_eq!(
+            filter.filter.filter_header(&FilterHeader::all_zeros()),
+            filter.header
+        );
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            serde_json::from_str::<serde_json::Value>(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_chain_tips() {
+        let json = r#"[
+            {
+                "height": 102,
+                "hash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+                "branchlen": 0,
+                "status": "active"
+            },
+            {
+                "height": 101,
+                "hash": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+                "branchlen": 1,
+                "status": "valid-fork"
+            },
+            {
+                "height": 103,
+                "hash": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+                "branchlen": 2,
+                "status": "headers-only"
+            }
+        ]"#;
+        let tips: Vec<ChainTip> = serde_json::from_str(json).unwrap();
+        assert_eq!(tips[0].status, ChainTipStatus::Active);
+        assert_eq!(tips[1].status, ChainTipStatus::ValidFork);
+        assert_eq!(tips[1].branch_len, 1);
+        assert_eq!(tips[2].status, ChainTipStatus::HeadersOnly);
+    }
+
+    #[test]
+    fn test_get_block_stats() {
+        let json = r#"{
+            "avgfee": 1410,
+            "avgfeerate": 10,
+            "avgtxsize": 222,
+            "blockhash": "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+            "feerate_percentiles": [1, 2, 10, 25, 60],
+            "height": 102,
+            "ins": 3,
+            "maxfee": 2820,
+            "maxfeerate": 60,
+            "maxtxsize": 255,
+            "medianfee": 1410,
+            "mediantime": 1750000000,
+            "mediantxsize": 222,
+            "minfee": 141,
+            "minfeerate": 1,
+            "mintxsize": 141,
+            "outs": 8,
+            "subsidy": 5000000000,
+            "swtotal_size": 444,
+            "swtotal_weight": 1122,
+            "swtxs": 2,
+            "time": 1750000600,
+            "total_out": 9999995770,
+            "total_size": 444,
+            "total_weight": 1122,
+            "totalfee": 4230,
+            "txs": 3,
+            "utxo_increase": 5,
+            "utxo_increase_actual": 4,
+            "utxo_size_inc": 380,
+            "utxo_size_inc_actual": 300
+        }"#;
+        let stats: GetBlockStats = serde_json::from_str(json).unwrap();
+        assert_eq!(stats.avg_fee, Some(Amount::from_sat(1410)));
+        assert_eq!(stats.avg_fee_rate, FeeRate::from_sat_per_vb(10));
+        assert_eq!(
+            stats.fee_rate_percentiles.unwrap()[4],
+            FeeRate::from_sat_per_vb(60).unwrap()
+        );
+        assert_eq!(stats.subsidy, Some(Amount::from_int_btc(50)));
+        assert_eq!(stats.utxo_increase_actual, Some(4));
+
+        // Only the selected fields are returned.
+        let stats: GetBlockStats =
+            serde_json::from_str(r#"{"height": 102, "minfeerate": 1}"#).unwrap();
+        assert_eq!(
+            stats,
+            GetBlockStats {
+                height: Some(102),
+                min_fee_rate: FeeRate::from_sat_per_vb(1),
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            serde_json::to_value([
+                BlockStatsField::FeeRatePercentiles,
+                BlockStatsField::TotalFee
+            ])
+            .unwrap(),
+            serde_json::json!(["feerate_percentiles", "totalfee"])
+        );
+        assert_eq!(
+            serde_json::to_value(BlockStatsTarget::from(7)).unwrap(),
+            serde_json::json!(7)
+        );
+    }
+
+    #[test]
+    fn test_reject_reason_parsing() {
+        let cases = [
+            ("min relay fee not met, 0 < 110", RejectReason::MinRelayFeeNotMet),
+            ("mempool min fee not met, 1 < 2", RejectReason::MempoolMinFeeNotMet),
+            (
+                "insufficient fee, rejecting replacement abcd; new feerate 0.00001 BTC/kvB <= old feerate 0.00002 BTC/kvB",
+                RejectReason::InsufficientFee,
+            ),
+            ("bad-txns-inputs-missingorspent", RejectReason::MissingInputs),
+            ("missing-inputs", RejectReason::MissingInputs),
+            (
+                "too-long-mempool-chain, too many descendants for tx abcd [limit: 25]",
+                RejectReason::TooLongMempoolChain,
+            ),
+            ("txn-mempool-conflict", RejectReason::MempoolConflict),
+            ("scriptpubkey", RejectReason::NonStandard("scriptpubkey".to_string())),
+            (
+                "mandatory-script-verify-flag-failed (Signature must be zero for failed CHECK(MULTI)SIG operation)",
+                RejectReason::ScriptVerifyFailed,
+            ),
+            (
+                "package-not-child-with-parents",
+                RejectReason::Other("package-not-child-with-parents".to_string()),
+            ),
+        ];
+        for (reason, expected) in cases {
+            assert_eq!(RejectReason::from(reason), expected, "{reason}");
+        }
+
+        let json = r#"{"txid":"4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b","reject-reason":"dust"}"#;
+        let accept: TestMempoolAccept = serde_json::from_str(json).unwrap();
+        assert_eq!(accept.rejection(), Some(RejectReason::Dust));
+    }
+
     #[test]
     fn test_wallet_process_psbt_result() {
         let valid_psbt = TEST_PSBT;
@@ -1328,6 +4114,13 @@ mod tests {
         let result2: WalletProcessPsbtResult = serde_json::from_str(&json2).unwrap();
         assert!(result2.psbt.is_some());
         assert!(!result2.complete);
+
+        // Test extracted by finalizepsbt, without psbt
+        let json3 = format!(r#"{{"hex":"{test_tx_hex}","complete":true}}"#);
+        let result3: WalletProcessPsbtResult = serde_json::from_str(&json3).unwrap();
+        assert!(result3.psbt.is_none());
+        assert!(result3.complete);
+        assert!(result3.hex.is_some());
     }
 
     #[test]
@@ -1369,4 +4162,148 @@ mod tests {
         let result2 = serde_json::from_str::<WalletCreateFundedPsbt>(&json2);
         assert!(result2.is_err());
     }
+
+    #[test]
+    fn test_decoding() {
+        let tx: Transaction = consensus::encode::deserialize_hex(TEST_TX_HEX).unwrap();
+        let script_pubkey = tx.output[0].script_pubkey.to_hex_string();
+        let json = format!(
+            r#"{{
+                "txid": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+                "hash": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+                "version": 1,
+                "size": 204,
+                "vsize": 204,
+                "weight": 816,
+                "locktime": 0,
+                "vin": [
+                    {{"coinbase": "{}", "sequence": 4294967295}},
+                    {{
+                        "txid": "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b",
+                        "vout": 0,
+                        "scriptSig": {{"asm": "", "hex": ""}},
+                        "txinwitness": ["3044", "02aa"],
+                        "sequence": 4294967293
+                    }}
+                ],
+                "vout": [{{
+                    "value": 50.00000000,
+                    "n": 0,
+                    "scriptPubKey": {{"asm": "", "desc": "pk()", "hex": "{script_pubkey}", "type": "pubkey"}}
+                }}]
+            }}"#,
+            tx.input[0].script_sig.to_hex_string(),
+        );
+        let decoded: DecodeRawTransaction = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.txid, tx.compute_txid());
+        assert_eq!(
+            decoded.vin[0].coinbase,
+            Some(tx.input[0].script_sig.clone())
+        );
+        assert_eq!(decoded.vin[0].previous_output(), None);
+        assert!(decoded.vin[0].witness.is_empty());
+        assert_eq!(
+            decoded.vin[1].previous_output(),
+            Some(OutPoint::new(tx.compute_txid(), 0))
+        );
+        assert_eq!(decoded.vin[1].script_sig, Some(ScriptBuf::new()));
+        assert_eq!(
+            decoded.vin[1].witness.to_vec(),
+            vec![vec![0x30, 0x44], vec![0x02, 0xaa]]
+        );
+        assert_eq!(decoded.vin[1].sequence, Sequence::ENABLE_RBF_NO_LOCKTIME);
+        assert_eq!(decoded.vout[0].value, Amount::from_btc(50.0).unwrap());
+        assert_eq!(
+            decoded.vout[0].script_pubkey.script,
+            tx.output[0].script_pubkey
+        );
+        assert_eq!(decoded.vout[0].script_pubkey.address, None);
+
+        let json = r#"{
+            "asm": "1 02aa 02bb 2 OP_CHECKMULTISIG",
+            "desc": "multi(1,02aa,02bb)#00000000",
+            "type": "multisig",
+            "p2sh": "2N8ghn7p9wH6oJcL9AWdEmuPvGH7ZykEQvB",
+            "segwit": {
+                "asm": "0 17d2e2f86a729e53479d69b12622756546fc739730913f1fb7b74e07c14b1d87",
+                "hex": "002017d2e2f86a729e53479d69b12622756546fc739730913f1fb7b74e07c14b1d87",
+                "address": "bcrt1qzlfw97r2w209x3uadxcjvgn4v4r0cuuhxzgn78ahka8q0s2trkrsz83aj4",
+                "type": "witness_v0_scripthash",
+                "p2sh-segwit": "2NFGu9RRL9GXiKMu3TB7ie26kXjBEd5Mo57"
+            }
+        }"#;
+        let decoded: DecodeScript = serde_json::from_str(json).unwrap();
+        assert_eq!(decoded.script_type, "multisig");
+        assert_eq!(decoded.address, None);
+        assert!(decoded.p2sh.is_some());
+        let segwit = decoded.segwit.unwrap();
+        assert!(segwit.script.is_p2wsh());
+        assert_eq!(segwit.desc, None);
+        assert!(segwit.p2sh_segwit.is_some());
+
+        let json = r#"{
+            "inputs": [
+                {
+                    "has_utxo": true,
+                    "is_final": false,
+                    "next": "signer",
+                    "missing": {"signatures": ["d0c59903c5bac2868760e90fd521a4665aa76520"]}
+                },
+                {
+                    "has_utxo": false,
+                    "is_final": false,
+                    "next": "updater"
+                }
+            ],
+            "next": "updater"
+        }"#;
+        let analysis: AnalyzePsbt = serde_json::from_str(json).unwrap();
+        assert_eq!(analysis.next, PsbtRole::Updater);
+        assert_eq!(analysis.estimated_vsize, None);
+        assert_eq!(analysis.estimated_feerate, None);
+        assert_eq!(analysis.fee, None);
+        let missing = analysis.inputs[0].missing.as_ref().unwrap();
+        assert_eq!(
+            missing.signatures,
+            vec!["d0c59903c5bac2868760e90fd521a4665aa76520"
+                .parse::<PubkeyHash>()
+                .unwrap()]
+        );
+        assert!(missing.pubkeys.is_empty());
+        assert_eq!(analysis.inputs[1].next, Some(PsbtRole::Updater));
+        assert_eq!(analysis.inputs[1].missing, None);
+
+        let json = r#"{
+            "inputs": [{"has_utxo": true, "is_final": true, "next": "extractor"}],
+            "estimated_vsize": 141,
+            "estimated_feerate": 0.00001000,
+            "fee": 0.00000141,
+            "next": "extractor"
+        }"#;
+        let analysis: AnalyzePsbt = serde_json::from_str(json).unwrap();
+        assert_eq!(analysis.next, PsbtRole::Extractor);
+        assert_eq!(analysis.estimated_vsize, Some(141));
+        assert_eq!(
+            analysis.estimated_feerate,
+            Some(FeeRate::from_sat_per_vb_unchecked(1))
+        );
+        assert_eq!(analysis.fee, Some(Amount::from_sat(141)));
+    }
+
+    #[test]
+    fn test_descriptor_info() {
+        let json = r#"{
+            "descriptor": "wpkh([d34db33f/84h/0h/0h]0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798)#qwlqgth7",
+            "checksum": "tpmnx7xj",
+            "isrange": false,
+            "issolvable": true,
+            "hasprivatekeys": true
+        }"#;
+        let info: GetDescriptorInfo = serde_json::from_str(json).unwrap();
+        assert!(info.descriptor.ends_with("#qwlqgth7"));
+        assert_eq!(info.checksum, "tpmnx7xj");
+        assert!(!info.is_range);
+        assert!(info.is_solvable);
+        assert!(info.has_private_keys);
+    }
 }